@@ -0,0 +1,531 @@
+use crate::config::Config;
+use crate::model::{KillProcessResponse, PortFilter, PortInfo, os};
+use crate::portwatch::{PortEvent, metrics, time_format::TimeFormat, tracker::Tracker};
+
+use std::{
+    path::{Path, PathBuf},
+    process::ExitCode,
+    sync::atomic::{AtomicBool, Ordering},
+    thread,
+    time::{Duration, Instant},
+};
+
+/// Printed by `--help`
+pub const USAGE: &str = "\
+Usage: harboor-sweep [OPTIONS]
+
+Without options, starts the interactive port explorer.
+
+Print ports once:
+      --once               Print the current ports and exit
+      --listening          Only listening ports (implies --once)
+      --port <PORT>        Only this port (implies --once), fails when nothing holds it
+      --pid <PID>          Only this process (implies --once)
+      --filter <QUERY>     Same matching as the search bar (implies --once)
+
+Kill:
+      --kill-port <PORT>   Kill whatever listens on the port
+      --kill-pid <PID>     Kill the process
+
+Watch and export:
+      --watch              Print ports opening and closing until Ctrl+C
+      --interval <SECS>    Seconds between polls in watch mode [default: refresh_interval_secs from the config]
+      --prometheus <PATH>  Write port counts in the Prometheus text format, `-` for stdout
+
+Offline:
+      --from-snapshot <PATH>
+                           Explore a JSON, YAML or CSV port export in the TUI instead of this machine
+
+Troubleshooting:
+      --debug              Start the TUI showing how long each port fetch takes (F12 toggles it)
+
+Output:
+      --format <FORMAT>    text, json or jsonl [default: text]
+
+  -h, --help               Print this help and exit
+  -V, --version            Print the version and exit
+";
+
+/// How results are printed
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum OutputFormat {
+    /// Human readable lines
+    #[default]
+    Text,
+    /// JSON on stdout, for scripts
+    Json,
+    /// One compact JSON object per line, for streaming into `jq` or a log pipeline
+    Jsonl,
+}
+
+impl std::str::FromStr for OutputFormat {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(OutputFormat::Text),
+            "json" => Ok(OutputFormat::Json),
+            "jsonl" => Ok(OutputFormat::Jsonl),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Command line options. Without any, the TUI starts.
+#[derive(Debug, Default, PartialEq)]
+pub struct CliArgs {
+    /// Print the ports once and exit instead of starting the TUI
+    pub once: bool,
+    /// Which ports to print in one-shot mode
+    pub filter: PortFilter,
+    /// Kill whatever listens on this port and exit
+    pub kill_port: Option<u16>,
+    /// Kill this process and exit
+    pub kill_pid: Option<u32>,
+    /// Print ports opening and closing until interrupted
+    pub watch: bool,
+    /// Seconds between polls in watch mode, the config's `refresh_interval_secs` when not given
+    pub interval: Option<u64>,
+    /// Write Prometheus metrics to this file, or stdout for `-`, and exit
+    pub prometheus: Option<PathBuf>,
+    /// How `--once`, `--watch` and the kill flags print their results
+    pub format: OutputFormat,
+    /// Explore the ports saved in this JSON, YAML or CSV export instead of the running system
+    pub from_snapshot: Option<PathBuf>,
+    /// Show the fetch timing overlay when the TUI starts
+    pub debug: bool,
+    /// Print [`USAGE`] and exit
+    pub help: bool,
+    /// Print the version and exit
+    pub version: bool,
+}
+
+impl CliArgs {
+    /// Parses the arguments following the program name.
+    /// Any filter flag implies `--once`. `--help` and `--version` win over everything else,
+    /// even arguments that would not parse.
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, String> {
+        let args: Vec<String> = args.into_iter().collect();
+        if args.iter().any(|a| a == "-h" || a == "--help") {
+            return Ok(Self {
+                help: true,
+                ..Self::default()
+            });
+        }
+        if args.iter().any(|a| a == "-V" || a == "--version") {
+            return Ok(Self {
+                version: true,
+                ..Self::default()
+            });
+        }
+
+        let mut parsed = Self::default();
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--once" => parsed.once = true,
+                "--listening" => {
+                    parsed.filter.listening_only = true;
+                    parsed.once = true;
+                }
+                "--port" => {
+                    parsed.filter.port = Some(parse_value(&arg, args.next())?);
+                    parsed.once = true;
+                }
+                "--pid" => {
+                    parsed.filter.pid = Some(parse_value(&arg, args.next())?);
+                    parsed.once = true;
+                }
+                "--kill-port" => parsed.kill_port = Some(parse_value(&arg, args.next())?),
+                "--kill-pid" => parsed.kill_pid = Some(parse_value(&arg, args.next())?),
+                "--format" => parsed.format = parse_value(&arg, args.next())?,
+                "--watch" => parsed.watch = true,
+                "--debug" => parsed.debug = true,
+                "--prometheus" => parsed.prometheus = Some(parse_value(&arg, args.next())?),
+                "--from-snapshot" => {
+                    parsed.from_snapshot = Some(parse_value(&arg, args.next())?);
+                }
+                "--interval" => match parse_value(&arg, args.next())? {
+                    0 => return Err(format!("{} must be at least 1", arg)),
+                    secs => parsed.interval = Some(secs),
+                },
+                "--filter" => {
+                    let query = args
+                        .next()
+                        .ok_or_else(|| format!("{} expects a value", arg))?;
+                    parsed.filter.set_query(&query);
+                    parsed.once = true;
+                }
+                _ => {
+                    return Err(format!(
+                        "Unknown argument: {}\nRun with --help to list the options",
+                        arg
+                    ));
+                }
+            }
+        }
+
+        Ok(parsed)
+    }
+}
+
+fn parse_value<T: std::str::FromStr>(flag: &str, value: Option<String>) -> Result<T, String> {
+    let value = value.ok_or_else(|| format!("{} expects a value", flag))?;
+    value
+        .parse()
+        .map_err(|_| format!("Invalid value for {}: {}", flag, value))
+}
+
+/// Prints the matching ports as a plain table. Fails when `--port` was given
+/// and nothing holds it, so the command can be used in shell conditionals.
+pub fn run_once(args: &CliArgs) -> ExitCode {
+    let ports = match os::fetch_ports() {
+        Ok(ports) => ports,
+        Err(e) => {
+            eprintln!("{}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let mut matches: Vec<&PortInfo> = args.filter.apply(&ports).collect();
+    matches.sort_by_key(|p| (p.port, p.pid));
+
+    match args.format {
+        OutputFormat::Json => print_json(&matches),
+        OutputFormat::Jsonl => matches.iter().for_each(print_json_line),
+        OutputFormat::Text => {
+            println!(
+                "{:<6} {:<5} {:<8} {:<8} {:<20} PATH",
+                "PORT", "PROTO", "STATE", "PID", "PROCESS"
+            );
+            for p in &matches {
+                println!(
+                    "{:<6} {:<5} {:<8} {:<8} {:<20} {}",
+                    p.port,
+                    p.protocol_label(),
+                    format!("{:?}", p.port_state),
+                    p.pid,
+                    p.process_name,
+                    p.process_path
+                );
+            }
+        }
+    }
+
+    exit_code(args.filter.port.is_none() || !matches.is_empty())
+}
+
+/// Kills the processes listening on `port`, printing each outcome, as a JSON
+/// array of responses with `--format json`.
+/// Succeeds only when something was listening and every kill worked.
+pub fn run_kill_port(port: u16, format: OutputFormat) -> ExitCode {
+    let ports = match os::fetch_ports() {
+        Ok(ports) => ports,
+        Err(e) => {
+            eprintln!("{}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let filter = PortFilter {
+        port: Some(port),
+        listening_only: true,
+        ..Default::default()
+    };
+    // IPv4 and IPv6 sockets of one process show up as separate rows
+    let mut pids: Vec<u32> = filter.apply(&ports).map(|p| p.pid).collect();
+    pids.sort_unstable();
+    pids.dedup();
+
+    if pids.is_empty() {
+        if format == OutputFormat::Json {
+            print_json(&Vec::<KillProcessResponse>::new());
+        }
+        eprintln!("No process is listening on port {}", port);
+        return ExitCode::FAILURE;
+    }
+
+    let responses: Vec<KillProcessResponse> = pids.into_iter().map(os::kill_process).collect();
+    match format {
+        OutputFormat::Json => print_json(&responses),
+        OutputFormat::Jsonl => responses.iter().for_each(print_json_line),
+        OutputFormat::Text => responses.iter().for_each(print_kill_response),
+    }
+
+    exit_code(responses.iter().all(|r| r.success))
+}
+
+/// Kills a single process, printing the outcome.
+pub fn run_kill_pid(pid: u32, format: OutputFormat) -> ExitCode {
+    let response = os::kill_process(pid);
+    match format {
+        OutputFormat::Json => print_json(&response),
+        OutputFormat::Jsonl => print_json_line(&response),
+        OutputFormat::Text => print_kill_response(&response),
+    }
+    exit_code(response.success)
+}
+
+/// Polls every `--interval` seconds and prints each port that opened or
+/// closed, until Ctrl+C. Both JSON formats print one event per line, text
+/// lines are timestamped with the config's `time_format` and `timezone`.
+pub fn run_watch(args: &CliArgs, config: &Config) -> ExitCode {
+    let interrupted = interrupt_flag();
+    let time_format =
+        TimeFormat::new(config.time_format.clone(), config.timezone).unwrap_or_else(|e| {
+            eprintln!("{}", e);
+            TimeFormat {
+                pattern: None,
+                timezone: config.timezone,
+            }
+        });
+    let fetch = || {
+        os::fetch_ports().map(|ports| {
+            ports
+                .into_iter()
+                .filter(|p| args.filter.matches(p))
+                .collect::<Vec<_>>()
+        })
+    };
+
+    let mut tracker = Tracker::new();
+    match fetch() {
+        Ok(ports) => tracker.start(ports),
+        Err(e) => {
+            eprintln!("{}", e);
+            return ExitCode::FAILURE;
+        }
+    }
+
+    let interval = Duration::from_secs(args.interval.unwrap_or(config.refresh_interval_secs));
+    loop {
+        let started = Instant::now();
+        while started.elapsed() < interval {
+            if interrupted.load(Ordering::SeqCst) {
+                return ExitCode::SUCCESS;
+            }
+            thread::sleep(Duration::from_millis(100));
+        }
+
+        let ports = match fetch() {
+            Ok(ports) => ports,
+            Err(e) => {
+                eprintln!("{}", e);
+                continue;
+            }
+        };
+        for event in tracker.track_once(ports) {
+            match args.format {
+                OutputFormat::Json | OutputFormat::Jsonl => print_json_line(event),
+                OutputFormat::Text => print_event(event, &time_format),
+            }
+        }
+        // Nothing is exported, so only the baseline has to be kept
+        tracker.events.clear();
+    }
+}
+
+/// Writes port counts for node_exporter's textfile collector, e.g. from cron.
+/// The filter flags narrow which ports are counted.
+pub fn run_prometheus(args: &CliArgs, path: &Path) -> ExitCode {
+    let ports: Vec<PortInfo> = match os::fetch_ports() {
+        Ok(ports) => ports
+            .into_iter()
+            .filter(|p| args.filter.matches(p))
+            .collect(),
+        Err(e) => {
+            eprintln!("{}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    if path == Path::new("-") {
+        print!("{}", metrics::prometheus_metrics(&ports));
+        return ExitCode::SUCCESS;
+    }
+    match metrics::write_prometheus_textfile(&ports, path) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("Failed to write {}: {}", path.display(), e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn print_event(event: &PortEvent, time_format: &TimeFormat) {
+    let (change, timestamp, p) = match event {
+        PortEvent::PortOpened { timestamp, port } => ("opened", timestamp, port),
+        PortEvent::PortClosed { timestamp, port } => ("closed", timestamp, port),
+        PortEvent::InitialState { .. } => return,
+    };
+    println!(
+        "{} {:<6} {:<6} {:<5} {:<8} {}",
+        time_format.format(*timestamp, "%Y-%m-%d %H:%M:%S"),
+        change,
+        p.port,
+        p.protocol_label(),
+        p.pid,
+        p.process_name
+    );
+}
+
+/// Set once Ctrl+C is pressed, so watch mode can stop between polls
+#[cfg(target_family = "unix")]
+fn interrupt_flag() -> &'static AtomicBool {
+    static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+    extern "C" fn on_interrupt(_: libc::c_int) {
+        INTERRUPTED.store(true, Ordering::SeqCst);
+    }
+    let handler = on_interrupt as extern "C" fn(libc::c_int) as libc::sighandler_t;
+    unsafe {
+        libc::signal(libc::SIGINT, handler);
+        libc::signal(libc::SIGTERM, handler);
+    }
+    &INTERRUPTED
+}
+
+/// Set once Ctrl+C or Ctrl+Break is pressed, so watch mode can stop between polls
+#[cfg(target_family = "windows")]
+fn interrupt_flag() -> &'static AtomicBool {
+    use windows::Win32::Foundation::BOOL;
+    use windows::Win32::System::Console::{CTRL_BREAK_EVENT, CTRL_C_EVENT, SetConsoleCtrlHandler};
+
+    static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+    unsafe extern "system" fn on_interrupt(ctrl_type: u32) -> BOOL {
+        if ctrl_type == CTRL_C_EVENT || ctrl_type == CTRL_BREAK_EVENT {
+            INTERRUPTED.store(true, Ordering::SeqCst);
+            return BOOL(1);
+        }
+        // Closing the console or logging off still ends the process
+        BOOL(0)
+    }
+    if let Err(e) = unsafe { SetConsoleCtrlHandler(Some(Some(on_interrupt)), true) } {
+        eprintln!(
+            "Cannot handle Ctrl+C, it will end the process directly: {}",
+            e
+        );
+    }
+    &INTERRUPTED
+}
+
+fn print_kill_response(response: &KillProcessResponse) {
+    if response.success {
+        println!("{}", response.message);
+    } else {
+        eprintln!("{}", response.message);
+    }
+}
+
+fn print_json<T: serde::Serialize + ?Sized>(value: &T) {
+    match serde_json::to_string_pretty(value) {
+        Ok(json) => println!("{}", json),
+        Err(e) => eprintln!("Failed to serialize output: {}", e),
+    }
+}
+
+fn print_json_line<T: serde::Serialize>(value: &T) {
+    match serde_json::to_string(value) {
+        Ok(json) => println!("{}", json),
+        Err(e) => eprintln!("Failed to serialize output: {}", e),
+    }
+}
+
+fn exit_code(success: bool) -> ExitCode {
+    if success {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<CliArgs, String> {
+        CliArgs::parse(args.iter().map(|a| a.to_string()))
+    }
+
+    #[test]
+    fn no_arguments_start_the_tui() {
+        assert_eq!(parse(&[]), Ok(CliArgs::default()));
+    }
+
+    #[test]
+    fn filter_flags_imply_once() {
+        let args = parse(&["--port", "8080", "--listening"]).unwrap();
+        assert!(args.once);
+        assert!(args.filter.listening_only);
+        assert_eq!(args.filter.port, Some(8080));
+
+        let args = parse(&["--pid", "42", "--filter", "node"]).unwrap();
+        assert_eq!(args.filter.pid, Some(42));
+        assert_eq!(args.filter.query, "node");
+    }
+
+    #[test]
+    fn parses_kill_port() {
+        let args = parse(&["--kill-port", "8080"]).unwrap();
+        assert_eq!(args.kill_port, Some(8080));
+        assert!(!args.once);
+    }
+
+    #[test]
+    fn parses_kill_pid_and_format() {
+        let args = parse(&["--kill-pid", "42", "--format", "json"]).unwrap();
+        assert_eq!(args.kill_pid, Some(42));
+        assert_eq!(args.format, OutputFormat::Json);
+        assert!(parse(&["--format", "xml"]).is_err());
+    }
+
+    #[test]
+    fn parses_watch() {
+        let args = parse(&["--watch", "--interval", "5", "--format", "jsonl"]).unwrap();
+        assert!(args.watch);
+        assert_eq!(args.interval, Some(5));
+        assert_eq!(args.format, OutputFormat::Jsonl);
+        assert_eq!(parse(&["--watch"]).unwrap().interval, None);
+        assert!(parse(&["--interval", "0"]).is_err());
+    }
+
+    #[test]
+    fn parses_prometheus_path() {
+        let args = parse(&["--prometheus", "/var/lib/node_exporter/ports.prom"]).unwrap();
+        assert_eq!(
+            args.prometheus,
+            Some(PathBuf::from("/var/lib/node_exporter/ports.prom"))
+        );
+    }
+
+    #[test]
+    fn parses_from_snapshot() {
+        let args = parse(&["--from-snapshot", "snapshots/ports.json"]).unwrap();
+        assert_eq!(
+            args.from_snapshot,
+            Some(PathBuf::from("snapshots/ports.json"))
+        );
+        assert!(!args.once);
+        assert!(parse(&["--from-snapshot"]).is_err());
+
+        let args = parse(&["--debug"]).unwrap();
+        assert!(args.debug);
+        assert!(!args.once);
+    }
+
+    #[test]
+    fn help_and_version_win() {
+        assert!(parse(&["--help"]).unwrap().help);
+        assert!(parse(&["--once", "-h"]).unwrap().help);
+        assert!(parse(&["-V"]).unwrap().version);
+        assert!(parse(&["--bogus", "--version"]).unwrap().version);
+        assert!(parse(&["--port", "http", "--help"]).unwrap().help);
+    }
+
+    #[test]
+    fn rejects_bad_values() {
+        assert!(parse(&["--port"]).is_err());
+        assert!(parse(&["--port", "http"]).is_err());
+        assert!(parse(&["--bogus"]).is_err());
+    }
+}
@@ -0,0 +1,380 @@
+use crate::keymap::{Action, KeyBinding};
+use crate::model::PortInfo;
+use crate::portwatch::time_format::Timezone;
+use crate::state::{DEFAULT_INTERVAL_SECS, MAX_INTERVAL_SECS, MIN_INTERVAL_SECS};
+use crate::ui::process_table_component::{Column, ExposureHighlight, SortBy};
+use crate::ui::theme::PALETTES;
+use crate::util::glob_match;
+
+use serde::{Deserialize, Serialize};
+use serde_yaml::{Mapping, Value};
+use std::{collections::BTreeMap, env, fs, io, path::PathBuf};
+
+/// How far `PageUp` / `PageDown` move the selection
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PageStep {
+    /// A whole screen of rows
+    #[default]
+    Full,
+    /// Half a screen, like `Ctrl+D` / `Ctrl+U`
+    Half,
+}
+
+/// What is done with the command line of a process after killing it
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RestartHint {
+    Off,
+    /// Show it in the status line and activity log
+    #[default]
+    Show,
+    /// Also copy it to the clipboard, ready to paste into a shell
+    Copy,
+}
+
+/// User settings read from `config.yaml` in the platform config directory.
+/// Any missing key keeps its default value.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// Send a desktop notification when a new listening port opens while tracking
+    pub notify_on_open: bool,
+    /// Append every tracked event to this file as JSON Lines
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stream_events_to: Option<PathBuf>,
+    /// Prepend hostname, OS, tool version and export time to exported files
+    pub export_metadata: bool,
+    /// Export a running audit and the kill log when quitting instead of discarding them
+    pub export_on_exit: bool,
+    /// On Unix, send SIGTERM first and only SIGKILL when the process is still
+    /// running after this many milliseconds
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub kill_grace_period_ms: Option<u64>,
+    /// Seconds between confirming a kill and carrying it out, during which Esc aborts.
+    /// `0` kills right away
+    pub kill_countdown_secs: u64,
+    /// Offer the command line of a killed process so it can be started again
+    pub restart_hint: RestartHint,
+    /// Ports whose processes are killed without the confirmation popup
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub skip_confirm_ports: Vec<u16>,
+    /// Process names or path globs (e.g. `/usr/sbin/*`) that are only killed after
+    /// explicitly overriding the protection in the kill popup
+    pub protected_processes: Vec<String>,
+    /// PIDs protected the same way
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub protected_pids: Vec<u32>,
+    /// Use vim-style navigation: `j`/`k`, `gg`/`G`, and `dd` to kill
+    pub vim_keys: bool,
+    /// How far `PageUp` / `PageDown` scroll
+    pub page_step: PageStep,
+    /// Whether moving past the last row selects the first and vice versa, in the
+    /// table and the help popup
+    pub wrap_selection: bool,
+    /// Seconds between automatic list refreshes, 1 to 60
+    pub refresh_interval_secs: u64,
+    /// Color theme to start with, an index into the palettes `Shift+Left` / `Shift+Right` cycle
+    pub theme: usize,
+    /// Table columns to show; saved when changed in the UI
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub columns: Option<Vec<Column>>,
+    /// Every column, in the order they are drawn, e.g. to put the PID before the port
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub column_order: Option<Vec<Column>>,
+    /// Sort columns that start out highest first when selected, e.g. `[port, pid]`
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub sort_descending: Vec<SortBy>,
+    /// Which ports reachable from other machines are drawn in the warning color
+    pub exposure_highlight: ExposureHighlight,
+    /// Leave this tool's own process out of the list
+    pub hide_own_process: bool,
+    /// Show the IPv4 and IPv6 rows of one socket as a single `tcp46` row
+    pub merge_dual_stack: bool,
+    /// Leave out PID 0, kernel threads and other entries without a real executable
+    pub hide_system_processes: bool,
+    /// strftime pattern for timestamps in exports and the footer, e.g. `%Y-%m-%dT%H:%M:%SZ`.
+    /// Unset keeps RFC 3339 in exports and `%H:%M:%S` in the footer
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub time_format: Option<String>,
+    /// Whether timestamps are written in local time or UTC
+    pub timezone: Timezone,
+    /// Normal-mode keys by action, e.g. `kill: d` or `quit: [q, ctrl+c]`.
+    /// Actions left out keep their default keys
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub keys: BTreeMap<Action, KeyBinding>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            notify_on_open: false,
+            stream_events_to: None,
+            export_metadata: false,
+            export_on_exit: false,
+            kill_grace_period_ms: None,
+            kill_countdown_secs: 3,
+            restart_hint: RestartHint::default(),
+            skip_confirm_ports: Vec::new(),
+            protected_processes: [
+                "sshd",
+                "systemd",
+                "init",
+                "launchd",
+                "gdm*",
+                "sddm",
+                "lightdm",
+                "wininit.exe",
+                "csrss.exe",
+                "lsass.exe",
+                "services.exe",
+            ]
+            .map(String::from)
+            .to_vec(),
+            protected_pids: Vec::new(),
+            vim_keys: false,
+            page_step: PageStep::default(),
+            wrap_selection: true,
+            refresh_interval_secs: DEFAULT_INTERVAL_SECS,
+            theme: 0,
+            columns: None,
+            column_order: None,
+            sort_descending: Vec::new(),
+            exposure_highlight: ExposureHighlight::default(),
+            hide_own_process: true,
+            merge_dual_stack: false,
+            hide_system_processes: true,
+            time_format: None,
+            timezone: Timezone::default(),
+            keys: BTreeMap::new(),
+        }
+    }
+}
+
+impl Config {
+    /// Location of the config file, e.g. `~/.config/harboor-sweep/config.yaml`
+    pub fn path() -> Option<PathBuf> {
+        let base = if cfg!(target_family = "windows") {
+            env::var_os("APPDATA").map(PathBuf::from)
+        } else {
+            env::var_os("XDG_CONFIG_HOME")
+                .map(PathBuf::from)
+                .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+        }?;
+        Some(base.join("harboor-sweep").join("config.yaml"))
+    }
+
+    /// Loads the config file, falling back to defaults when it is missing or unreadable.
+    /// JSON is accepted too since it is valid YAML. Also returns what was wrong with
+    /// the file, see [`Config::parse`].
+    pub fn load() -> (Self, Vec<String>) {
+        Self::path()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .map(|content| Self::parse(&content))
+            .unwrap_or_default()
+    }
+
+    /// Reads the settings in `content`. An entry that does not parse or is out of
+    /// range keeps its default and is reported, leaving the other entries in effect;
+    /// a file that is not YAML at all gives the defaults.
+    pub fn parse(content: &str) -> (Self, Vec<String>) {
+        let mut errors = Vec::new();
+        let entries = match serde_yaml::from_str::<Value>(content) {
+            Ok(Value::Mapping(entries)) => entries,
+            Ok(Value::Null) => Mapping::new(),
+            Ok(_) => {
+                errors.push("The config file must be a list of `key: value` settings".to_string());
+                Mapping::new()
+            }
+            Err(e) => {
+                errors.push(format!("The config file is not valid YAML: {}", e));
+                Mapping::new()
+            }
+        };
+
+        // Try each entry on its own so one typo does not discard the whole file
+        let known = setting_names();
+        let mut valid = Mapping::new();
+        for (key, value) in entries {
+            if !key.as_str().is_some_and(|key| known.contains(&key)) {
+                errors.push(invalid(key.as_str().unwrap_or("?"), "unknown setting"));
+                continue;
+            }
+            let entry = Mapping::from_iter([(key.clone(), value.clone())]);
+            match serde_yaml::from_value::<Config>(Value::Mapping(entry)) {
+                Ok(_) => {
+                    valid.insert(key, value);
+                }
+                Err(e) => errors.push(invalid(key.as_str().unwrap_or("?"), e)),
+            }
+        }
+        let mut config: Config = serde_yaml::from_value(Value::Mapping(valid)).unwrap_or_default();
+
+        let defaults = Config::default();
+        if !(MIN_INTERVAL_SECS..=MAX_INTERVAL_SECS).contains(&config.refresh_interval_secs) {
+            errors.push(invalid(
+                "refresh_interval_secs",
+                format!(
+                    "must be {} to {} seconds, got {}",
+                    MIN_INTERVAL_SECS, MAX_INTERVAL_SECS, config.refresh_interval_secs
+                ),
+            ));
+            config.refresh_interval_secs = defaults.refresh_interval_secs;
+        }
+        if config.theme >= PALETTES.len() {
+            errors.push(invalid(
+                "theme",
+                format!("must be 0 to {}, got {}", PALETTES.len() - 1, config.theme),
+            ));
+            config.theme = defaults.theme;
+        }
+
+        (config, errors)
+    }
+
+    /// Whether the process behind `item` is listed in `protected_pids` or matches
+    /// a name or path in `protected_processes`.
+    pub fn is_protected(&self, item: &PortInfo) -> bool {
+        self.protected_pids.contains(&item.pid)
+            || self.protected_processes.iter().any(|pattern| {
+                glob_match(pattern, &item.process_name) || glob_match(pattern, &item.process_path)
+            })
+    }
+
+    /// Writes `columns` to the config file, creating it and its folder if needed.
+    /// The other entries of the file, including ones this version does not know,
+    /// are kept as they are.
+    pub fn save_columns(&self) -> io::Result<()> {
+        let path = Self::path()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "No config directory"))?;
+        let content = match fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(e),
+        };
+        let yaml = with_columns(&content, self.columns.as_deref())?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, yaml)
+    }
+}
+
+/// `content` with its `columns` entry replaced, or removed for `None`
+fn with_columns(content: &str, columns: Option<&[Column]>) -> io::Result<String> {
+    let mut entries = match serde_yaml::from_str::<Value>(content).map_err(io::Error::other)? {
+        Value::Mapping(entries) => entries,
+        Value::Null => Mapping::new(),
+        _ => {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "The config file is not a list of `key: value` settings",
+            ));
+        }
+    };
+    match columns {
+        Some(columns) => {
+            let columns = serde_yaml::to_value(columns).map_err(io::Error::other)?;
+            entries.insert("columns".into(), columns);
+        }
+        None => {
+            entries.remove("columns");
+        }
+    }
+    serde_yaml::to_string(&entries).map_err(io::Error::other)
+}
+
+/// The keys [`Config`] reads, taken from its `Deserialize` impl so the list cannot
+/// fall behind the struct
+fn setting_names() -> &'static [&'static str] {
+    struct FieldNames<'a>(&'a mut &'static [&'static str]);
+
+    impl<'de> serde::Deserializer<'de> for FieldNames<'_> {
+        type Error = serde::de::value::Error;
+
+        fn deserialize_struct<V: serde::de::Visitor<'de>>(
+            self,
+            _name: &'static str,
+            fields: &'static [&'static str],
+            _visitor: V,
+        ) -> Result<V::Value, Self::Error> {
+            *self.0 = fields;
+            Err(serde::de::Error::custom("only the field names are wanted"))
+        }
+
+        fn deserialize_any<V: serde::de::Visitor<'de>>(
+            self,
+            _visitor: V,
+        ) -> Result<V::Value, Self::Error> {
+            Err(serde::de::Error::custom("expected a struct"))
+        }
+
+        serde::forward_to_deserialize_any! {
+            bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string bytes
+            byte_buf option unit unit_struct newtype_struct seq tuple tuple_struct map
+            enum identifier ignored_any
+        }
+    }
+
+    let mut fields: &'static [&'static str] = &[];
+    let _ = Config::deserialize(FieldNames(&mut fields));
+    fields
+}
+
+/// Why the `key` entry of the config file was ignored
+fn invalid(key: &str, reason: impl std::fmt::Display) -> String {
+    format!(
+        "Invalid `{}` in the config file: {}, using the default",
+        key, reason
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn invalid_entries_keep_their_defaults() {
+        let (config, errors) = Config::parse(
+            "theme: 9\nrefresh_interval_secs: 0\nsort_descending: [port, bogus]\nvim_keys: true\nvim_key: false\n",
+        );
+        assert_eq!(config.theme, 0);
+        assert_eq!(config.refresh_interval_secs, DEFAULT_INTERVAL_SECS);
+        assert!(config.sort_descending.is_empty());
+        assert!(config.vim_keys);
+        assert_eq!(errors.len(), 4);
+        assert!(
+            errors
+                .iter()
+                .any(|e| e.contains("`sort_descending`") && e.contains("bogus"))
+        );
+        assert!(
+            errors
+                .iter()
+                .any(|e| e.contains("`theme`") && e.contains("got 9"))
+        );
+        assert!(errors.contains(&invalid("vim_key", "unknown setting")));
+
+        let (config, errors) = Config::parse("vim_keys: [true");
+        assert!(!config.vim_keys);
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].starts_with("The config file is not valid YAML"));
+
+        assert!(Config::parse("").1.is_empty());
+    }
+
+    #[test]
+    fn saving_columns_keeps_the_other_entries() {
+        let content = "vim_keys: true\ncolumns: [port]\nfuture_setting: 3\n";
+        let saved = with_columns(content, Some(&[Column::Pid, Column::Port])).unwrap();
+        assert_eq!(
+            saved,
+            "vim_keys: true\ncolumns:\n- pid\n- port\nfuture_setting: 3\n"
+        );
+
+        let saved = with_columns(&saved, None).unwrap();
+        assert_eq!(saved, "vim_keys: true\nfuture_setting: 3\n");
+        assert_eq!(with_columns("", None).unwrap(), "{}\n");
+        assert!(with_columns("[port]", None).is_err());
+    }
+}
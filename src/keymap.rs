@@ -0,0 +1,352 @@
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, collections::HashMap, str::FromStr};
+
+/// Something a key does in normal mode. The snake_case names are the keys of
+/// the `keys` section in the config file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Action {
+    Quit,
+    Help,
+    ToggleSearch,
+    EditSearch,
+    JumpToPort,
+    QuickFilters,
+    Kill,
+    Refresh,
+    Pause,
+    SlowerRefresh,
+    FasterRefresh,
+    MineOnly,
+    HideUnknownPaths,
+    ExternalOnly,
+    ShowOwnProcess,
+    ShowSystemProcesses,
+    MergeDualStack,
+    FollowNewPorts,
+    ActivityLog,
+    DebugOverlay,
+    ToggleAudit,
+    ExportAudit,
+    ExportKills,
+    CycleExportFormat,
+    ExportSnapshot,
+    ExportSelected,
+    SnapshotBrowser,
+    SnapshotDiff,
+    RevealInFileManager,
+    CopyKillCommand,
+    CopyVisibleJson,
+    ReverseDns,
+    GroupByProcess,
+    ExpandGroup,
+    CompactLayout,
+    HexPids,
+    Columns,
+    UsageColumns,
+    UserColumn,
+    RemoteColumn,
+    SortPort,
+    SortPid,
+    SortName,
+    SortPath,
+    SortParentPid,
+    SortCpu,
+    SortMemory,
+    SortUptime,
+    SortUser,
+    ReverseSort,
+    Down,
+    Up,
+    PageDown,
+    PageUp,
+    HalfPageDown,
+    HalfPageUp,
+    FirstRow,
+    LastRow,
+    ScrollLeft,
+    ScrollRight,
+    NextTheme,
+    PreviousTheme,
+}
+
+/// Keys of every action when the config does not remap it
+const DEFAULTS: &[(Action, &[&str])] = &[
+    (Action::Quit, &["q", "esc", "ctrl+c"]),
+    (Action::Help, &["f1", "?"]),
+    (Action::ToggleSearch, &["ctrl+f"]),
+    (Action::EditSearch, &["e"]),
+    (Action::JumpToPort, &["/"]),
+    (Action::QuickFilters, &["t"]),
+    (Action::Kill, &["k"]),
+    (Action::Refresh, &["r"]),
+    (Action::Pause, &["p"]),
+    (Action::SlowerRefresh, &["+"]),
+    (Action::FasterRefresh, &["-"]),
+    (Action::MineOnly, &["m"]),
+    (Action::HideUnknownPaths, &["h"]),
+    (Action::ExternalOnly, &["b"]),
+    (Action::ShowOwnProcess, &["i"]),
+    (Action::ShowSystemProcesses, &["shift+h"]),
+    (Action::MergeDualStack, &["shift+m"]),
+    (Action::FollowNewPorts, &["n"]),
+    (Action::ActivityLog, &["shift+l"]),
+    (Action::DebugOverlay, &["f12"]),
+    (Action::ToggleAudit, &["shift+s", "ctrl+s"]),
+    (Action::ExportAudit, &["shift+e", "ctrl+e"]),
+    (Action::ExportKills, &["shift+k"]),
+    (Action::CycleExportFormat, &["f"]),
+    (Action::ExportSnapshot, &["f2"]),
+    (Action::ExportSelected, &["x"]),
+    (Action::SnapshotBrowser, &["f3"]),
+    (Action::SnapshotDiff, &["f4"]),
+    (Action::RevealInFileManager, &["shift+o"]),
+    (Action::CopyKillCommand, &["y"]),
+    (Action::CopyVisibleJson, &["shift+y"]),
+    (Action::ReverseDns, &["d"]),
+    (Action::GroupByProcess, &["v"]),
+    (Action::ExpandGroup, &["enter"]),
+    (Action::CompactLayout, &["l"]),
+    (Action::HexPids, &["shift+x"]),
+    (Action::Columns, &["c"]),
+    (Action::UsageColumns, &["u"]),
+    (Action::UserColumn, &["o"]),
+    (Action::RemoteColumn, &["a"]),
+    (Action::SortPort, &["1"]),
+    (Action::SortPid, &["2"]),
+    (Action::SortName, &["3"]),
+    (Action::SortPath, &["4"]),
+    (Action::SortParentPid, &["5"]),
+    (Action::SortCpu, &["6"]),
+    (Action::SortMemory, &["7"]),
+    (Action::SortUptime, &["8"]),
+    (Action::SortUser, &["9"]),
+    (Action::ReverseSort, &["tab"]),
+    (Action::Down, &["down"]),
+    (Action::Up, &["up"]),
+    (Action::PageDown, &["pagedown"]),
+    (Action::PageUp, &["pageup"]),
+    (Action::HalfPageDown, &["ctrl+d"]),
+    (Action::HalfPageUp, &["ctrl+u"]),
+    (Action::FirstRow, &["shift+pageup"]),
+    (Action::LastRow, &["shift+pagedown"]),
+    (Action::ScrollLeft, &["left"]),
+    (Action::ScrollRight, &["right"]),
+    (Action::NextTheme, &["shift+right"]),
+    (Action::PreviousTheme, &["shift+left"]),
+];
+
+/// One key with its modifiers, e.g. `ctrl+f`, `shift+s`, `f1` or `?`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KeyCombo {
+    modifiers: KeyModifiers,
+    code: KeyCode,
+}
+
+impl KeyCombo {
+    /// Folds the ways terminals report the same key into one: typed characters
+    /// carry their case instead of `Shift`, and `Ctrl` letters are lowercase.
+    fn normalized(modifiers: KeyModifiers, code: KeyCode) -> Self {
+        match code {
+            KeyCode::Char(c) if modifiers.contains(KeyModifiers::CONTROL) => Self {
+                modifiers: modifiers - KeyModifiers::SHIFT,
+                code: KeyCode::Char(c.to_ascii_lowercase()),
+            },
+            KeyCode::Char(_) => Self {
+                modifiers: modifiers - KeyModifiers::SHIFT,
+                code,
+            },
+            _ => Self { modifiers, code },
+        }
+    }
+}
+
+impl From<KeyEvent> for KeyCombo {
+    fn from(key: KeyEvent) -> Self {
+        Self::normalized(key.modifiers, key.code)
+    }
+}
+
+impl FromStr for KeyCombo {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("Invalid key \"{}\"", s);
+        let (modifier_names, key) = match s.rsplit_once('+') {
+            Some((modifiers, key)) if !key.is_empty() => (modifiers, key),
+            _ => ("", s),
+        };
+
+        let mut modifiers = KeyModifiers::NONE;
+        for name in modifier_names.split('+').filter(|n| !n.is_empty()) {
+            modifiers |= match name.to_lowercase().as_str() {
+                "ctrl" | "control" => KeyModifiers::CONTROL,
+                "alt" => KeyModifiers::ALT,
+                "shift" => KeyModifiers::SHIFT,
+                _ => return Err(invalid()),
+            };
+        }
+
+        let mut chars = key.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(c), None) if modifiers.contains(KeyModifiers::SHIFT) => {
+                KeyCode::Char(c.to_ascii_uppercase())
+            }
+            (Some(c), None) => KeyCode::Char(c),
+            _ => match key.to_lowercase().as_str() {
+                "esc" | "escape" => KeyCode::Esc,
+                "enter" | "return" => KeyCode::Enter,
+                "tab" => KeyCode::Tab,
+                "backspace" => KeyCode::Backspace,
+                "delete" | "del" => KeyCode::Delete,
+                "space" => KeyCode::Char(' '),
+                "up" => KeyCode::Up,
+                "down" => KeyCode::Down,
+                "left" => KeyCode::Left,
+                "right" => KeyCode::Right,
+                "pageup" => KeyCode::PageUp,
+                "pagedown" => KeyCode::PageDown,
+                "home" => KeyCode::Home,
+                "end" => KeyCode::End,
+                name => match name.strip_prefix('f').map(str::parse) {
+                    Some(Ok(n @ 1..=12)) => KeyCode::F(n),
+                    _ => return Err(invalid()),
+                },
+            },
+        };
+        Ok(Self::normalized(modifiers, code))
+    }
+}
+
+/// Keys of one action in the config, a single combo or a list of them
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum KeyBinding {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl KeyBinding {
+    fn combos(&self) -> &[String] {
+        match self {
+            KeyBinding::One(combo) => std::slice::from_ref(combo),
+            KeyBinding::Many(combos) => combos,
+        }
+    }
+}
+
+/// Which action each key triggers in normal mode
+#[derive(Debug, Clone)]
+pub struct Keymap {
+    actions: HashMap<KeyCombo, Action>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self::new(&BTreeMap::new()).0
+    }
+}
+
+impl Keymap {
+    /// Builds the keymap from the defaults and the config's remappings. A remapped
+    /// action loses its default keys, and a remapped key no longer triggers its default
+    /// action. Keys that do not parse are skipped and reported.
+    pub fn new(overrides: &BTreeMap<Action, KeyBinding>) -> (Self, Vec<String>) {
+        let mut actions = HashMap::new();
+        let mut errors = Vec::new();
+
+        for (action, combos) in DEFAULTS {
+            if overrides.contains_key(action) {
+                continue;
+            }
+            for combo in combos.iter() {
+                let combo = combo.parse().expect("default keys parse");
+                actions.insert(combo, *action);
+            }
+        }
+        for (action, binding) in overrides {
+            for combo in binding.combos() {
+                match combo.parse() {
+                    Ok(combo) => {
+                        actions.insert(combo, *action);
+                    }
+                    Err(e) => errors.push(e),
+                }
+            }
+        }
+
+        (Self { actions }, errors)
+    }
+
+    /// Action bound to the pressed key, if any
+    pub fn action(&self, key: KeyEvent) -> Option<Action> {
+        self.actions.get(&KeyCombo::from(key)).copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(modifiers: KeyModifiers, code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, modifiers)
+    }
+
+    #[test]
+    fn parses_key_combos() {
+        let parse = |s: &str| s.parse::<KeyCombo>();
+        assert_eq!(
+            parse("ctrl+f"),
+            Ok(KeyCombo::from(key(
+                KeyModifiers::CONTROL,
+                KeyCode::Char('f')
+            )))
+        );
+        assert_eq!(parse("shift+s"), parse("S"));
+        assert_eq!(parse("F1"), parse("f1"));
+        assert_eq!(
+            parse("shift+pageup"),
+            Ok(KeyCombo::from(key(KeyModifiers::SHIFT, KeyCode::PageUp)))
+        );
+        assert!(parse("hyper+x").is_err());
+        assert!(parse("f13").is_err());
+        assert!(parse("pgup").is_err());
+    }
+
+    #[test]
+    fn terminal_reports_of_the_same_key_match() {
+        let keymap = Keymap::default();
+        let shifted = key(KeyModifiers::SHIFT, KeyCode::Char('S'));
+        let plain = key(KeyModifiers::NONE, KeyCode::Char('S'));
+        assert_eq!(keymap.action(shifted), Some(Action::ToggleAudit));
+        assert_eq!(keymap.action(plain), Some(Action::ToggleAudit));
+        let ctrl_upper = key(KeyModifiers::CONTROL, KeyCode::Char('C'));
+        assert_eq!(keymap.action(ctrl_upper), Some(Action::Quit));
+        let question = key(KeyModifiers::SHIFT, KeyCode::Char('?'));
+        assert_eq!(keymap.action(question), Some(Action::Help));
+    }
+
+    #[test]
+    fn remapped_keys_replace_the_defaults() {
+        let overrides = BTreeMap::from([
+            (Action::Kill, KeyBinding::One("d".into())),
+            (
+                Action::Quit,
+                KeyBinding::Many(vec!["ctrl+q".into(), "nope+q".into()]),
+            ),
+        ]);
+        let (keymap, errors) = Keymap::new(&overrides);
+        let press = |c| keymap.action(key(KeyModifiers::NONE, KeyCode::Char(c)));
+
+        assert_eq!(press('d'), Some(Action::Kill));
+        assert_eq!(press('k'), None);
+        assert_eq!(press('q'), None);
+        assert_eq!(
+            keymap.action(key(KeyModifiers::CONTROL, KeyCode::Char('q'))),
+            Some(Action::Quit)
+        );
+        assert_eq!(press('1'), Some(Action::SortPort));
+        assert_eq!(errors, ["Invalid key \"nope+q\""]);
+    }
+}
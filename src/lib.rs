@@ -0,0 +1,10 @@
+//! Port and process discovery behind `harboor-sweep`, usable without the TUI.
+//!
+//! [`model::os::fetch_ports`] lists the sockets of the running system,
+//! [`model::os::kill_process`] stops their owners and [`portwatch`] tracks
+//! changes between refreshes and exports snapshots.
+
+pub mod model;
+pub mod portwatch;
+
+pub use model::os;
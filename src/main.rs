@@ -1,17 +1,39 @@
-mod model;
-mod portwatch;
+mod cli;
+mod config;
+mod keymap;
+mod state;
 mod ui;
 mod util;
 
-use crate::model::{PortInfo, os};
+use harboor_sweep::{model, portwatch};
+
+use crate::cli::CliArgs;
+use crate::config::{Config, PageStep, RestartHint};
+use crate::keymap::{Action, Keymap};
+use crate::model::{
+    HostResolver, KillProcessResponse, OsPortSource, PortFilter, PortInfo, PortSource,
+    ProcessPortState, QuickFilter, QuickFilters, UsageSampler, merge_dual_stack, os,
+};
+use crate::state::{AppState, MAX_INTERVAL_SECS, MIN_INTERVAL_SECS};
 use crate::ui::{
+    activity_log_component::ActivityLogComponent,
+    columns_component::ColumnsComponent,
+    debug_overlay_component::{DebugOverlayComponent, FetchTiming},
     footer_component::FooterComponent,
+    jump_component::JumpComponent,
     keybindings_component::KeybindingsComponent,
     kill_process_component::{KillAction, KillComponent},
+    legend_component::LegendComponent,
     process_search_component::ProcessSearchComponent,
     process_table_component::ProcessTableComponent,
-    process_table_component::SortBy,
+    process_table_component::{Column, EmptyState, SortBy, SortDirection},
+    quick_filter_component::QuickFilterComponent,
+    quit_component::{QuitAction, QuitComponent},
+    snapshot_browser_component::SnapshotBrowserComponent,
+    snapshot_diff_component::SnapshotDiffComponent,
     snapshots_component::{ExportAction, SnapshotsComponent},
+    status_component::{StatusComponent, StatusKind},
+    summary_component::SummaryComponent,
     theme::Theme,
 };
 
@@ -22,38 +44,139 @@ use ratatui::{
     layout::{Constraint, Direction, Layout},
 };
 
-use crate::portwatch::{snapshot::export_snapshot, tracker::Tracker};
-use std::{sync::mpsc, thread, time};
+use crate::portwatch::{
+    PortEvent,
+    export::to_json,
+    kill_log::KillLog,
+    snapshot::{SnapshotPortSource, export_process_snapshot, export_snapshot},
+    time_format::TimeFormat,
+    tracker::Tracker,
+};
+use crate::util::{copy_to_clipboard, desktop_notify, reveal_in_file_manager};
+use std::{
+    env,
+    io::{self, Write},
+    panic,
+    path::PathBuf,
+    process::ExitCode,
+    sync::{Arc, mpsc},
+    thread, time,
+};
 
 const ITEM_HEIGHT: u16 = 1;
+/// Quiet time after a search keystroke before the table is re-filtered
+const FILTER_DEBOUNCE: time::Duration = time::Duration::from_millis(100);
 
-fn bootstrap() -> Result<()> {
+fn bootstrap(source: Arc<dyn PortSource>, debug: bool) -> Result<()> {
     let (event_tx, event_rx) = mpsc::channel::<MultithreadingEvent>();
     let tx_to_input_events = event_tx.clone();
     let tx_to_background_thread = event_tx.clone();
+    let (refresh_tx, refresh_rx) = mpsc::channel::<()>();
+
+    let (config, config_errors) = Config::load();
+    // Shown again in the status line, since the TUI hides stderr until it exits
+    for e in &config_errors {
+        eprintln!("{}", e);
+    }
+    let mut app = App::new(config, source);
+    if let Some(e) = config_errors.first() {
+        app.status.error(e.clone());
+    }
+    app.config_invalid = !config_errors.is_empty();
+    app.debug_overlay.display = debug;
+    let source = Arc::clone(&app.source);
+    let state = app.state.clone();
 
     thread::spawn(move || {
         handle_input_events(tx_to_input_events);
     });
     thread::spawn(move || {
-        run_background_thread(tx_to_background_thread);
+        run_background_thread(tx_to_background_thread, refresh_rx, source, state);
     });
 
     let terminal = ratatui::init();
-    let result = App::new().run(terminal, event_rx);
+    let result = app.run(terminal, event_tx, event_rx, refresh_tx);
 
     ratatui::restore();
-    result
+    // Repeat where the audit went, the status line is gone with the TUI
+    if let Ok(Some(message)) = &result {
+        println!("{}", message);
+    }
+    result.map(|_| ())
 }
-fn main() -> Result<()> {
-    color_eyre::install()?;
-    bootstrap()
+/// Installs color-eyre's error and panic reports, restoring the terminal before a
+/// panic is printed so a crash does not leave the shell in raw mode.
+fn install_hooks() -> Result<()> {
+    let (panic_hook, eyre_hook) = color_eyre::config::HookBuilder::default().into_hooks();
+    eyre_hook.install()?;
+
+    let panic_hook = panic_hook.into_panic_hook();
+    panic::set_hook(Box::new(move |info| {
+        ratatui::restore();
+        panic_hook(info);
+    }));
+    Ok(())
+}
+
+fn main() -> Result<ExitCode> {
+    install_hooks()?;
+
+    let args = match CliArgs::parse(env::args().skip(1)) {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("{}", e);
+            return Ok(ExitCode::from(2));
+        }
+    };
+    if args.help {
+        print!("{}", cli::USAGE);
+        return Ok(ExitCode::SUCCESS);
+    }
+    if args.version {
+        println!("harboor-sweep {}", env!("CARGO_PKG_VERSION"));
+        return Ok(ExitCode::SUCCESS);
+    }
+    if let Some(port) = args.kill_port {
+        return Ok(cli::run_kill_port(port, args.format));
+    }
+    if let Some(pid) = args.kill_pid {
+        return Ok(cli::run_kill_pid(pid, args.format));
+    }
+    if let Some(path) = &args.prometheus {
+        return Ok(cli::run_prometheus(&args, path));
+    }
+    if args.watch {
+        let (config, config_errors) = Config::load();
+        for e in &config_errors {
+            eprintln!("{}", e);
+        }
+        return Ok(cli::run_watch(&args, &config));
+    }
+    if args.once {
+        return Ok(cli::run_once(&args));
+    }
+
+    let source: Arc<dyn PortSource> = match &args.from_snapshot {
+        Some(path) => match SnapshotPortSource::open(path) {
+            Ok(source) => Arc::new(source),
+            Err(e) => {
+                eprintln!("Cannot read snapshot {}: {}", path.display(), e);
+                return Ok(ExitCode::FAILURE);
+            }
+        },
+        None => Arc::new(OsPortSource),
+    };
+    bootstrap(source, args.debug)?;
+    Ok(ExitCode::SUCCESS)
 }
 
 /// The main application which holds the state and logic of the application.
-#[derive(Debug, Default)]
+#[derive(Debug)]
 pub struct App {
     pub application_mode: ApplicationMode,
+    pub config: Config,
+    /// Normal-mode keys, the defaults merged with the config's `keys`
+    pub keymap: Keymap,
 
     // Search component
     pub search: ProcessSearchComponent,
@@ -62,17 +185,82 @@ pub struct App {
     pub theme: Theme,
     pub kill_process: KillComponent,
     pub snapshots_component: SnapshotsComponent,
+    pub snapshot_diff: SnapshotDiffComponent,
+    pub snapshot_browser: SnapshotBrowserComponent,
+    pub columns_component: ColumnsComponent,
+    pub quick_filter: QuickFilterComponent,
     pub footer_component: FooterComponent,
+    pub status: StatusComponent,
+    pub summary: SummaryComponent,
+    pub legend: LegendComponent,
+    pub jump: JumpComponent,
+    pub activity: ActivityLogComponent,
+    pub quit: QuitComponent,
+    pub debug_overlay: DebugOverlayComponent,
     pub tracker: Tracker,
+    /// Every kill carried out this session, for the kill audit trail
+    pub kill_log: KillLog,
 
     // processes
     processes: Vec<PortInfo>,
-    processes_filtered: Vec<PortInfo>,
+    /// Where the background thread reads ports from
+    source: Arc<dyn PortSource>,
+
+    // Reverse DNS for remote addresses, created when enabled
+    resolver: Option<HostResolver>,
+
+    // Sender used by worker threads to report back to the UI loop
+    event_tx: Option<mpsc::Sender<MultithreadingEvent>>,
+    /// Asks the background thread to fetch ports immediately
+    refresh_tx: Option<mpsc::Sender<()>>,
+    /// Refresh interval and monitoring flag shared with the background thread
+    state: AppState,
+    /// A manual refresh was requested and has not arrived yet
+    refreshing: bool,
+    /// Keep the list still; background updates only feed the tracker
+    paused: bool,
+    /// Only list ports owned by the current user
+    mine_only: bool,
+    /// Hide ports whose process path is unknown or restricted
+    hide_unknown_paths: bool,
+    /// Only list ports bound to an address other machines can reach
+    external_only: bool,
+    /// Leave this tool's own process out of the list
+    hide_own_process: bool,
+    /// Show the IPv4 and IPv6 rows of one socket as a single `tcp46` row
+    merge_dual_stack: bool,
+    /// Leave out PID 0, kernel threads and similar entries that cannot be acted on
+    hide_system_processes: bool,
+    /// Move the selection to ports the audit sees opening
+    follow_new_ports: bool,
+    /// "Export & quit" was picked, so the running audit is exported on the way out
+    export_audit_on_quit: bool,
+    /// The config file had errors when loaded; writing to it could drop what the
+    /// user is still fixing, so nothing is saved
+    config_invalid: bool,
+    /// The first fetch has come back, successful or not
+    loaded: bool,
+    /// Latest refresh held back while a popup is open, so rows cannot shift
+    /// under a pending kill or export, with the last port the audit saw opening
+    /// meanwhile
+    queued_update: Option<(PortsUpdate, Option<String>)>,
+    /// Protocol, family and state chips of the quick-filter bar
+    quick_filters: QuickFilters,
+    /// Account name of the current user, looked up once
+    current_user: Option<String>,
+    /// First key of a two-key vim binding such as `gg` or `dd`
+    pending_key: Option<char>,
+    /// When the search last changed without re-filtering yet
+    filter_dirty_since: Option<time::Instant>,
 }
 
+/// One fetch of the background thread
+type PortsUpdate = Result<Vec<PortInfo>, String>;
+
 enum MultithreadingEvent {
     Crossterm(Event),
-    ProccesesUpdate(Vec<PortInfo>),
+    ProccesesUpdate(PortsUpdate, FetchTiming),
+    ExportFinished(io::Result<PathBuf>),
 }
 
 fn handle_input_events(tx: mpsc::Sender<MultithreadingEvent>) {
@@ -92,15 +280,56 @@ fn handle_input_events(tx: mpsc::Sender<MultithreadingEvent>) {
     }
 }
 
-fn run_background_thread(tx: mpsc::Sender<MultithreadingEvent>) {
+/// Fetches ports every `state` interval, or right away when a refresh is requested.
+/// A saved snapshot never changes and a paused list is not monitored, so those are
+/// only fetched on request.
+fn run_background_thread(
+    tx: mpsc::Sender<MultithreadingEvent>,
+    refresh_rx: mpsc::Receiver<()>,
+    source: Arc<dyn PortSource>,
+    state: AppState,
+) {
+    let mut sampler = UsageSampler::default();
     loop {
-        let event = MultithreadingEvent::ProccesesUpdate(Vec::new());
-        tx.send(event).unwrap();
+        let started = time::Instant::now();
+        let ports = source.fetch().map(|mut ports| {
+            if source.is_live() {
+                sampler.sample(&mut ports);
+            }
+            ports
+        });
+        let timing = FetchTiming {
+            took: started.elapsed(),
+            rows: ports.as_ref().map_or(0, Vec::len),
+        };
+        if tx
+            .send(MultithreadingEvent::ProccesesUpdate(ports, timing))
+            .is_err()
+        {
+            break;
+        }
 
-        thread::sleep(time::Duration::from_millis(2_000));
+        let wait = if source.is_live() && state.is_monitoring() {
+            state.interval()
+        } else {
+            time::Duration::MAX
+        };
+        match refresh_rx.recv_timeout(wait) {
+            // Coalesce refresh requests that piled up during the fetch
+            Ok(()) => while refresh_rx.try_recv().is_ok() {},
+            Err(mpsc::RecvTimeoutError::Timeout) => {}
+            Err(mpsc::RecvTimeoutError::Disconnected) => break,
+        }
     }
 }
 
+/// Emits a terminal bell so the outcome of an action is noticed.
+fn ring_bell() {
+    let mut stdout = io::stdout();
+    let _ = stdout.write_all(b"\x07");
+    let _ = stdout.flush();
+}
+
 #[derive(Debug, Default)]
 pub enum ApplicationMode {
     #[default]
@@ -109,6 +338,13 @@ pub enum ApplicationMode {
     Helping,
     Killing,
     Snapshotting,
+    Comparing,
+    Browsing,
+    Columns,
+    Filtering,
+    Jumping,
+    Activity,
+    Quitting,
 }
 
 enum AppControlFlow {
@@ -118,21 +354,100 @@ enum AppControlFlow {
 
 impl App {
     /// Construct a new instance of [`App`].
-    pub fn new() -> Self {
+    pub fn new(config: Config, source: Arc<dyn PortSource>) -> Self {
+        let mut tracker = Tracker::new();
+        tracker.include_metadata = config.export_metadata;
+        let mut status = StatusComponent::default();
+        match TimeFormat::new(config.time_format.clone(), config.timezone) {
+            Ok(time_format) => tracker.time_format = time_format,
+            Err(e) => {
+                status.error(e);
+                tracker.time_format.timezone = config.timezone;
+            }
+        }
+        if let Some(path) = &config.stream_events_to
+            && let Err(e) = tracker.enable_streaming(path)
+        {
+            status.error(format!("Cannot stream events to {}: {}", path.display(), e));
+        }
+
+        let mut table = ProcessTableComponent::default();
+        if let Some(order) = &config.column_order
+            && let Err(e) = table.set_column_order(order)
+        {
+            status.error(format!("{}, using the default order", e));
+        }
+        if let Some(columns) = &config.columns {
+            table.set_visible_columns(columns);
+        }
+        table.set_descending_by_default(&config.sort_descending);
+        table.exposure_highlight = config.exposure_highlight;
+        table.wrap_selection = config.wrap_selection;
+        let mut keybindings = KeybindingsComponent::default();
+        keybindings.wrap_selection = config.wrap_selection;
+        let theme = Theme::new(config.theme);
+        let hide_own_process = config.hide_own_process;
+        let merge_dual_stack = config.merge_dual_stack;
+        let hide_system_processes = config.hide_system_processes;
+        let state = AppState::default();
+        if let Err(e) = state.update_interval(config.refresh_interval_secs) {
+            status.error(e);
+        }
+        let (keymap, key_errors) = Keymap::new(&config.keys);
+        if let Some(e) = key_errors.first() {
+            status.error(format!("{} in the keys config, using the defaults", e));
+        }
+
         Self {
             application_mode: ApplicationMode::Normal,
+            config,
+            keymap,
 
             search: ProcessSearchComponent::default(),
-            table: ProcessTableComponent::default(),
-            keybindings: KeybindingsComponent::default(),
-            theme: Theme::default(),
+            table,
+            keybindings,
+            theme,
             kill_process: KillComponent::default(),
             snapshots_component: SnapshotsComponent::default(),
+            snapshot_diff: SnapshotDiffComponent::default(),
+            snapshot_browser: SnapshotBrowserComponent::default(),
+            columns_component: ColumnsComponent::default(),
+            quick_filter: QuickFilterComponent::default(),
             footer_component: FooterComponent::default(),
-            tracker: Tracker::new(),
+            status,
+            summary: SummaryComponent::default(),
+            legend: LegendComponent::default(),
+            jump: JumpComponent::default(),
+            activity: ActivityLogComponent::default(),
+            quit: QuitComponent::default(),
+            debug_overlay: DebugOverlayComponent::default(),
+            tracker,
+            kill_log: KillLog::default(),
             // Processes
             processes: Vec::new(),
-            processes_filtered: Vec::new(),
+            source,
+
+            resolver: None,
+            event_tx: None,
+            refresh_tx: None,
+            refreshing: false,
+            state,
+            paused: false,
+            mine_only: false,
+            hide_unknown_paths: false,
+            external_only: false,
+            hide_own_process,
+            merge_dual_stack,
+            hide_system_processes,
+            follow_new_ports: false,
+            export_audit_on_quit: false,
+            config_invalid: false,
+            loaded: false,
+            queued_update: None,
+            quick_filters: QuickFilters::default(),
+            current_user: os::current_user(),
+            pending_key: None,
+            filter_dirty_since: None,
         }
     }
 
@@ -140,22 +455,61 @@ impl App {
     fn run(
         mut self,
         mut terminal: DefaultTerminal,
+        tx: mpsc::Sender<MultithreadingEvent>,
         rx: mpsc::Receiver<MultithreadingEvent>,
-    ) -> Result<()> {
+        refresh_tx: mpsc::Sender<()>,
+    ) -> Result<Option<String>> {
+        self.event_tx = Some(tx);
+        self.refresh_tx = Some(refresh_tx);
+
         loop {
-            match rx.recv().unwrap() {
-                MultithreadingEvent::Crossterm(event) => match event {
+            // Wake up without an event when a search debounce or kill countdown needs it
+            let event = match self.next_wakeup() {
+                Some(at) => {
+                    match rx.recv_timeout(at.saturating_duration_since(time::Instant::now())) {
+                        Ok(event) => Some(event),
+                        Err(mpsc::RecvTimeoutError::Timeout) => None,
+                        Err(mpsc::RecvTimeoutError::Disconnected) => return Ok(None),
+                    }
+                }
+                None => Some(rx.recv().unwrap()),
+            };
+
+            match event {
+                None => {}
+                Some(MultithreadingEvent::Crossterm(event)) => match event {
                     Event::Key(key) if key.kind == KeyEventKind::Press => {
                         if matches!(self.handle_key_event(key)?, AppControlFlow::Exit) {
-                            return Ok(());
+                            let message = self.export_on_exit();
+                            if message.is_some() {
+                                terminal.draw(|frame| self.render(frame))?;
+                            }
+                            return Ok(message);
                         }
                     }
                     _ => {}
                 },
-                MultithreadingEvent::ProccesesUpdate(_data) => self.monitor_ports_loop(),
+                Some(MultithreadingEvent::ProccesesUpdate(ports, timing)) => {
+                    self.debug_overlay.last_fetch = Some(timing);
+                    self.monitor_ports_loop(ports)
+                }
+                Some(MultithreadingEvent::ExportFinished(result)) => self.report_export(result),
+            }
+
+            if self
+                .filter_dirty_since
+                .is_some_and(|since| since.elapsed() >= FILTER_DEBOUNCE)
+            {
+                self.update_filtered_processes();
+            }
+            if self.kill_process.countdown_due() {
+                self.confirm_kill();
             }
+            self.apply_queued_update();
 
+            let started = time::Instant::now();
             terminal.draw(|frame| self.render(frame))?;
+            self.debug_overlay.last_render = Some(started.elapsed());
         }
     }
     /// Render the application's UI.
@@ -168,12 +522,25 @@ impl App {
             layout_constraints.push(Constraint::Length(3));
         }
 
+        let show_quick_filter = self.search.display || self.quick_filter.display;
+        if show_quick_filter {
+            layout_constraints.push(Constraint::Length(1));
+        }
+
         layout_constraints.push(Constraint::Min(1));
+        layout_constraints.push(Constraint::Length(1));
+        layout_constraints.push(Constraint::Length(1));
 
-        if self.footer_component.display {
+        let show_footer = self.footer_component.display || self.paused;
+        if show_footer {
             layout_constraints.push(Constraint::Length(3));
         }
 
+        let show_status = self.status.is_visible();
+        if show_status {
+            layout_constraints.push(Constraint::Length(1));
+        }
+
         let areas = Layout::default()
             .direction(Direction::Vertical)
             .constraints(layout_constraints)
@@ -188,22 +555,50 @@ impl App {
             index += 1;
         }
 
+        if show_quick_filter {
+            self.quick_filter.render(
+                frame,
+                areas[index],
+                &self.theme.table,
+                self.quick_filters,
+                &self.application_mode,
+            );
+            index += 1;
+        }
+
         let table_area = areas[index];
         self.table.visible_rows = table_area.height as usize - 1;
         self.table.render(frame, table_area, &self.theme.table);
         index += 1;
 
-        if self.footer_component.display {
+        // The jump prompt borrows the legend line while open
+        if self.jump.display {
+            self.jump.render(frame, areas[index], &self.theme.table);
+        } else {
+            self.legend.items = self.legend_items();
+            self.legend.render(frame, areas[index], &self.theme.table);
+        }
+        index += 1;
+
+        self.summary.render(frame, areas[index], &self.theme.table);
+        index += 1;
+
+        if show_footer {
             let footer_area = areas[index];
             self.footer_component.render(
                 frame,
                 footer_area,
                 &self.theme.table,
-                self.tracker.export_format,
-                self.tracker.is_active,
-                self.tracker.started_at,
-                self.tracker.events.len(),
+                &self.tracker,
+                self.paused,
+                &self.tracker.time_format,
             );
+            index += 1;
+        }
+
+        if show_status {
+            let status_area = areas[index];
+            self.status.render(frame, status_area, &self.theme.table);
         }
 
         // Popups
@@ -211,6 +606,75 @@ impl App {
         self.kill_process.render(frame, area, &self.theme.table);
         self.snapshots_component
             .render(frame, area, &self.theme.table);
+        self.snapshot_diff.render(frame, area, &self.theme.table);
+        self.snapshot_browser.render(frame, area, &self.theme.table);
+        self.columns_component
+            .render(frame, area, &self.theme.table, &self.table.columns);
+        self.activity.render(frame, area, &self.theme.table);
+        self.quit.render(frame, area, &self.theme.table);
+        self.debug_overlay.render(frame, area, &self.theme.table);
+    }
+
+    /// What currently shapes the table: sort, search and every active toggle.
+    fn legend_items(&self) -> Vec<String> {
+        let direction = match self.table.sort_direction {
+            SortDirection::Ascending => "▲",
+            SortDirection::Descending => "▼",
+        };
+        let mut items = vec![format!(
+            "Sort: {} {}",
+            self.table.sort_by.label(),
+            direction
+        )];
+
+        let query = self.search.value.trim();
+        if !query.is_empty() {
+            let case = if self.search.case_sensitive {
+                " (match case)"
+            } else {
+                ""
+            };
+            items.push(format!("Search: \"{}\"{}", query, case));
+        }
+        if self.mine_only {
+            items.push("Mine only".into());
+        }
+        if self.hide_unknown_paths {
+            items.push("Known paths only".into());
+        }
+        if self.external_only {
+            items.push("Exposed only".into());
+        }
+        if !self.hide_own_process {
+            items.push("Showing itself".into());
+        }
+        if !self.hide_system_processes {
+            items.push("Showing system processes".into());
+        }
+        if self.follow_new_ports {
+            items.push("Following new ports".into());
+        }
+        if self.merge_dual_stack {
+            items.push("Dual-stack merged".into());
+        }
+        if !self.quick_filters.is_all() {
+            let hidden: Vec<&str> = QuickFilter::ALL
+                .iter()
+                .filter(|f| !self.quick_filters.is_on(**f))
+                .map(|f| f.label())
+                .collect();
+            items.push(format!("Hidden: {}", hidden.join(", ")));
+        }
+        if self.table.grouped {
+            items.push("Grouped by process".into());
+        }
+        if self.paused {
+            items.push("Paused".into());
+        }
+        if !self.source.is_live() {
+            items.push("Offline snapshot".into());
+        }
+        items
     }
 
     /// Toggles the processes search display.
@@ -226,7 +690,7 @@ impl App {
     }
     /// Toggles the keybindings display.
     fn toggle_keybindings_display(&mut self) {
-        self.keybindings.display = !self.keybindings.display;
+        self.keybindings.toggle();
 
         if self.keybindings.display {
             self.application_mode = ApplicationMode::Helping;
@@ -245,6 +709,56 @@ impl App {
         }
     }
 
+    /// Toggles the snapshot comparison display.
+    fn toggle_snapshot_diff_display(&mut self) {
+        self.snapshot_diff.toggle();
+
+        if self.snapshot_diff.display {
+            self.application_mode = ApplicationMode::Comparing;
+        } else {
+            self.application_mode = ApplicationMode::Normal;
+        }
+    }
+
+    /// Toggles the snapshot browser display.
+    fn toggle_snapshot_browser_display(&mut self) {
+        self.snapshot_browser.toggle();
+
+        if self.snapshot_browser.display {
+            self.application_mode = ApplicationMode::Browsing;
+        } else {
+            self.application_mode = ApplicationMode::Normal;
+        }
+    }
+
+    /// Toggles the activity log display.
+    fn toggle_activity_display(&mut self) {
+        self.activity.toggle();
+
+        if self.activity.display {
+            self.application_mode = ApplicationMode::Activity;
+        } else {
+            self.application_mode = ApplicationMode::Normal;
+        }
+    }
+
+    /// Opens the selected snapshot from the browser in the compare view.
+    fn open_browsed_snapshot(&mut self) {
+        let Some(file) = self.snapshot_browser.selected() else {
+            return;
+        };
+        if !file.is_loadable() {
+            self.status
+                .error("Only JSON, YAML or CSV port snapshots can be compared");
+            return;
+        }
+
+        let path = file.path.clone();
+        self.snapshot_browser.display = false;
+        self.snapshot_diff.open_with(path);
+        self.application_mode = ApplicationMode::Comparing;
+    }
+
     /// User input controller handling different modes.
     fn handle_key_event(&mut self, key: KeyEvent) -> Result<AppControlFlow> {
         match self.application_mode {
@@ -265,81 +779,182 @@ impl App {
                 self.handle_snapshotting_mode_key(key);
                 Ok(AppControlFlow::Continue)
             }
+            ApplicationMode::Comparing => {
+                self.handle_comparing_mode_key(key);
+                Ok(AppControlFlow::Continue)
+            }
+            ApplicationMode::Browsing => {
+                self.handle_browsing_mode_key(key);
+                Ok(AppControlFlow::Continue)
+            }
+            ApplicationMode::Columns => {
+                self.handle_columns_mode_key(key);
+                Ok(AppControlFlow::Continue)
+            }
+            ApplicationMode::Filtering => {
+                self.handle_filtering_mode_key(key);
+                Ok(AppControlFlow::Continue)
+            }
+            ApplicationMode::Jumping => {
+                self.handle_jumping_mode_key(key);
+                Ok(AppControlFlow::Continue)
+            }
+            ApplicationMode::Activity => {
+                self.handle_activity_mode_key(key);
+                Ok(AppControlFlow::Continue)
+            }
+            ApplicationMode::Quitting => Ok(self.handle_quitting_mode_key(key)),
         }
     }
+    /// Vim-style bindings layered over the Normal mode ones when `vim_keys` is set.
+    /// Returns whether the key was handled.
+    fn handle_vim_key(&mut self, key: KeyEvent) -> bool {
+        let pending = self.pending_key.take();
+        match (key.modifiers, key.code, pending) {
+            (KeyModifiers::NONE, KeyCode::Char('g'), Some('g')) => self.table.first_row(),
+            (KeyModifiers::NONE, KeyCode::Char('d'), Some('d')) => {
+                if self.table.selected().is_some() {
+                    self.request_kill();
+                }
+            }
+            (KeyModifiers::NONE, KeyCode::Char(c @ ('g' | 'd')), _) => self.pending_key = Some(c),
+            (KeyModifiers::NONE, KeyCode::Char('j'), _) => self.table.next_row(),
+            (KeyModifiers::NONE, KeyCode::Char('k'), _) => self.table.previous_row(),
+            (KeyModifiers::SHIFT, KeyCode::Char('G'), _) => self.table.last_row(),
+            (KeyModifiers::SHIFT, KeyCode::Char('D'), _) => self.toggle_reverse_dns(),
+            _ => return false,
+        }
+        true
+    }
     fn handle_normal_mode_key(&mut self, key: KeyEvent) -> Result<AppControlFlow> {
-        match (key.modifiers, key.code) {
-            // Quit from application
-            (KeyModifiers::NONE, KeyCode::Char('q' | 'Q'))
-            | (KeyModifiers::NONE, KeyCode::Esc)
-            | (KeyModifiers::CONTROL, KeyCode::Char('c' | 'C')) => {
-                return Ok(AppControlFlow::Exit);
+        if self.config.vim_keys && self.handle_vim_key(key) {
+            return Ok(AppControlFlow::Continue);
+        }
+
+        let Some(action) = self.keymap.action(key) else {
+            return Ok(AppControlFlow::Continue);
+        };
+        match action {
+            // Quitting drops a running audit's events unless export_on_exit saves them
+            Action::Quit if self.tracker.is_active && !self.config.export_on_exit => {
+                self.quit.open(self.tracker.events.len());
+                self.application_mode = ApplicationMode::Quitting;
             }
+            Action::Quit => return Ok(AppControlFlow::Exit),
             // Toggle UI elements
-            (KeyModifiers::CONTROL, KeyCode::Char('f' | 'F')) => {
-                self.toggle_processes_search_display()
+            Action::ToggleSearch => self.toggle_processes_search_display(),
+            Action::ExportAudit => {
+                let result = self.tracker.export(None);
+                self.report_export(result);
             }
-            (KeyModifiers::CONTROL, KeyCode::Char('e')) => match self.tracker.export(None) {
-                _ => {}
-            },
-            (KeyModifiers::NONE, KeyCode::Char('f' | 'F')) => {
+            Action::ExportKills => {
+                if self.kill_log.is_empty() {
+                    self.status.error("No kills to export yet");
+                } else {
+                    let result = self.export_kill_log();
+                    self.report_export(result);
+                }
+            }
+            Action::CycleExportFormat => {
                 self.tracker.export_format = self.tracker.export_format.next();
             }
-            (KeyModifiers::CONTROL, KeyCode::Char('s')) => {
+            Action::ToggleAudit => {
                 if !self.tracker.is_active {
                     self.tracker.start(self.processes.clone());
                 } else {
-                    self.tracker.stop();
+                    let result = self.tracker.stop();
+                    self.report_export(result);
                 }
 
                 self.footer_component.toggle();
+                self.sync_monitoring();
             }
 
-            (KeyModifiers::NONE, KeyCode::F(1)) | (_, KeyCode::Char('?')) => {
-                self.toggle_keybindings_display();
-            }
-            (KeyModifiers::NONE, KeyCode::F(2)) => self.toggle_snapshotting_display(),
+            Action::Help => self.toggle_keybindings_display(),
+            Action::Refresh => self.request_refresh(),
+            Action::Pause => self.toggle_pause(),
+            Action::SlowerRefresh => self.change_interval(1),
+            Action::FasterRefresh => self.change_interval(-1),
+            Action::MineOnly => self.toggle_mine_only(),
+            Action::HideUnknownPaths => self.toggle_unknown_paths(),
+            Action::ExternalOnly => self.toggle_external_only(),
+            Action::ShowOwnProcess => self.toggle_own_process(),
+            Action::ShowSystemProcesses => self.toggle_system_processes(),
+            Action::MergeDualStack => self.toggle_merge_dual_stack(),
+            Action::FollowNewPorts => self.toggle_follow_new_ports(),
+            Action::ActivityLog => self.toggle_activity_display(),
+            Action::DebugOverlay => self.debug_overlay.toggle(),
+            Action::ExportSnapshot => self.toggle_snapshotting_display(),
+            Action::ExportSelected => self.export_selected_process(),
+            Action::RevealInFileManager => self.reveal_selected_process(),
+            Action::CopyKillCommand => self.copy_kill_command(),
+            Action::CopyVisibleJson => self.copy_visible_json(),
+            Action::GroupByProcess => self.table.toggle_grouped(),
+            Action::CompactLayout => self.table.toggle_compact(),
+            Action::HexPids => self.table.toggle_hex_pids(),
+            Action::ExpandGroup => self.table.toggle_expanded(),
+            Action::SnapshotBrowser => self.toggle_snapshot_browser_display(),
+            Action::SnapshotDiff => self.toggle_snapshot_diff_display(),
             // Modify Search input mode
-            (KeyModifiers::NONE, KeyCode::Char('e')) => {
+            Action::EditSearch => {
                 self.application_mode = ApplicationMode::Editing;
             }
+            Action::JumpToPort => {
+                self.jump.open(self.table.state.selected());
+                self.application_mode = ApplicationMode::Jumping;
+            }
             // Navigate in the list
-            (KeyModifiers::SHIFT, KeyCode::PageUp) => self.table.first_row(),
-            (KeyModifiers::SHIFT, KeyCode::PageDown) => self.table.last_row(),
-            (KeyModifiers::NONE, KeyCode::PageUp) => self.table.page_up(),
-            (KeyModifiers::NONE, KeyCode::PageDown) => self.table.page_down(),
-            (KeyModifiers::NONE, KeyCode::Down) => self.table.next_row(),
-            (KeyModifiers::NONE, KeyCode::Up) => self.table.previous_row(),
+            Action::FirstRow => self.table.first_row(),
+            Action::LastRow => self.table.last_row(),
+            Action::PageUp => match self.config.page_step {
+                PageStep::Full => self.table.page_up(),
+                PageStep::Half => self.table.half_page_up(),
+            },
+            Action::PageDown => match self.config.page_step {
+                PageStep::Full => self.table.page_down(),
+                PageStep::Half => self.table.half_page_down(),
+            },
+            Action::HalfPageDown => self.table.half_page_down(),
+            Action::HalfPageUp => self.table.half_page_up(),
+            Action::Down => self.table.next_row(),
+            Action::Up => self.table.previous_row(),
+            Action::ScrollLeft => self.table.scroll_left(),
+            Action::ScrollRight => self.table.scroll_right(),
             // Table actions
-            (KeyModifiers::NONE, KeyCode::Char('k')) if self.table.state.selected().is_some() => {
-                self.kill_process.display = !self.kill_process.display;
-                if self.kill_process.display {
-                    self.application_mode = ApplicationMode::Killing;
-                } else {
-                    self.application_mode = ApplicationMode::Normal;
-                }
-
-                if let Some(idx) = self.table.state.selected() {
-                    // assuming kill_process.item implements Clone (or Copy),
-                    // otherwise use a reference
-                    self.kill_process.item = Option::from(self.processes_filtered[idx].clone());
+            Action::Kill => {
+                if self.table.selected().is_some() {
+                    self.request_kill()
                 }
             }
             // Change sorting in table
-            (KeyModifiers::NONE, KeyCode::Char('1')) => self.table.set_or_toggle_sort(SortBy::Port),
-            (KeyModifiers::NONE, KeyCode::Char('2')) => self.table.set_or_toggle_sort(SortBy::PID),
-            (KeyModifiers::NONE, KeyCode::Char('3')) => {
-                self.table.set_or_toggle_sort(SortBy::ProcessName)
+            Action::SortPort => self.sort_table(SortBy::Port),
+            Action::SortPid => self.sort_table(SortBy::Pid),
+            Action::SortName => self.sort_table(SortBy::ProcessName),
+            Action::SortPath => self.sort_table(SortBy::ProcessPath),
+            Action::SortParentPid => self.sort_table(SortBy::ParentPid),
+            Action::SortCpu => self.sort_table(SortBy::Cpu),
+            Action::SortMemory => self.sort_table(SortBy::Memory),
+            Action::SortUptime => self.sort_table(SortBy::Uptime),
+            Action::SortUser => self.sort_table(SortBy::User),
+            Action::ReverseSort => self.table.toggle_sort_direction(None),
+            Action::UsageColumns => {
+                self.table.toggle_usage_columns();
+                self.save_columns();
             }
-            (KeyModifiers::NONE, KeyCode::Char('4')) => {
-                self.table.set_or_toggle_sort(SortBy::ProcessPath)
+            Action::UserColumn => {
+                self.table.toggle_column(Column::User);
+                self.save_columns();
             }
-            // Change theme
-            (KeyModifiers::SHIFT, KeyCode::Right) => self.theme.cycle_next(),
-            (KeyModifiers::SHIFT, KeyCode::Left) => {
-                self.theme.cycle_prev();
+            Action::RemoteColumn => {
+                self.table.toggle_column(Column::Remote);
+                self.save_columns();
             }
-            _ => {}
+            Action::Columns => self.toggle_columns_display(),
+            Action::QuickFilters => self.toggle_quick_filter_display(),
+            Action::ReverseDns => self.toggle_reverse_dns(),
+            // Change theme
+            Action::NextTheme => self.theme.cycle_next(),
+            Action::PreviousTheme => self.theme.cycle_prev(),
         }
         Ok(AppControlFlow::Continue)
     }
@@ -354,34 +969,135 @@ impl App {
             // Navigate in the list
             (KeyModifiers::SHIFT, KeyCode::PageUp) => self.keybindings.first_row(),
             (KeyModifiers::SHIFT, KeyCode::PageDown) => self.keybindings.last_row(),
-            (KeyModifiers::NONE, KeyCode::PageUp) => self.keybindings.page_up(),
-            (KeyModifiers::NONE, KeyCode::PageDown) => self.keybindings.page_down(),
+            (KeyModifiers::NONE, KeyCode::PageUp) => match self.config.page_step {
+                PageStep::Full => self.keybindings.page_up(),
+                PageStep::Half => self.keybindings.half_page_up(),
+            },
+            (KeyModifiers::NONE, KeyCode::PageDown) => match self.config.page_step {
+                PageStep::Full => self.keybindings.page_down(),
+                PageStep::Half => self.keybindings.half_page_down(),
+            },
+            // Ctrl+U clears the filter here, so Ctrl+B scrolls back up
+            (KeyModifiers::CONTROL, KeyCode::Char('d')) => self.keybindings.half_page_down(),
+            (KeyModifiers::CONTROL, KeyCode::Char('b')) => self.keybindings.half_page_up(),
             (KeyModifiers::NONE, KeyCode::Down) => self.keybindings.next_row(),
             (KeyModifiers::NONE, KeyCode::Up) => self.keybindings.previous_row(),
 
+            // Filter the list
+            (KeyModifiers::NONE, KeyCode::Left) => self.keybindings.filter.move_cursor_left(),
+            (KeyModifiers::NONE, KeyCode::Right) => self.keybindings.filter.move_cursor_right(),
+            (KeyModifiers::NONE, KeyCode::Backspace) => {
+                self.keybindings.filter.delete_char();
+                self.keybindings.apply_filter();
+            }
+            (KeyModifiers::CONTROL, KeyCode::Char('u')) => {
+                self.keybindings.filter.clear_to_start();
+                self.keybindings.apply_filter();
+            }
+            (KeyModifiers::NONE | KeyModifiers::SHIFT, KeyCode::Char(c)) => {
+                self.keybindings.filter.insert_char(c);
+                self.keybindings.apply_filter();
+            }
+
+            _ => {}
+        }
+    }
+    /// Moves the selection to the typed port as each digit arrives.
+    fn handle_jumping_mode_key(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Char(c) => self.jump.push(c),
+            KeyCode::Backspace => self.jump.pop(),
+            KeyCode::Enter => {
+                self.jump.close();
+                self.application_mode = ApplicationMode::Normal;
+                return;
+            }
+            KeyCode::Esc => {
+                if let Some(row) = self.jump.origin {
+                    self.table.select_row(row);
+                }
+                self.jump.close();
+                self.application_mode = ApplicationMode::Normal;
+                return;
+            }
+            _ => return,
+        }
+
+        if self.jump.value.is_empty() {
+            self.jump.found = true;
+            return;
+        }
+        let row = self.table.find_port(&self.jump.value);
+        self.jump.found = row.is_some();
+        if let Some(row) = row {
+            self.table.select_row(row);
+        }
+    }
+
+    fn handle_activity_mode_key(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('L') => self.toggle_activity_display(),
+            KeyCode::Down => self.activity.next_row(),
+            KeyCode::Up => self.activity.previous_row(),
+            KeyCode::Home => self.activity.first_row(),
+            KeyCode::End => self.activity.last_row(),
             _ => {}
         }
     }
+
     fn handle_editing_mode_key(&mut self, key: KeyEvent) {
         match key.code {
+            KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.search.clear_to_start();
+                self.schedule_filter_update();
+            }
+            KeyCode::Char('w') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.search.delete_word();
+                self.schedule_filter_update();
+            }
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::ALT) => {
+                self.search.toggle_case_sensitive();
+                self.update_filtered_processes();
+            }
             KeyCode::Char(to_insert) => {
                 self.search.insert_char(to_insert);
-                self.update_filtered_processes();
+                self.schedule_filter_update();
             }
             KeyCode::Backspace => {
                 self.search.delete_char();
-                self.update_filtered_processes();
+                self.schedule_filter_update();
             }
             KeyCode::Left => self.search.move_cursor_left(),
             KeyCode::Right => self.search.move_cursor_right(),
-            KeyCode::Down => {
+            KeyCode::Tab => {
+                self.flush_filter_update();
+                self.application_mode = ApplicationMode::Filtering;
+            }
+            KeyCode::Down if key.modifiers.contains(KeyModifiers::SHIFT) => {
+                self.search.submit();
+                self.flush_filter_update();
                 self.application_mode = ApplicationMode::Normal;
                 self.table.next_row()
             }
-            KeyCode::Up => {
+            KeyCode::Up if key.modifiers.contains(KeyModifiers::SHIFT) => {
+                self.search.submit();
+                self.flush_filter_update();
                 self.application_mode = ApplicationMode::Normal;
                 self.table.previous_row()
             }
+            KeyCode::Up => {
+                self.search.history_prev();
+                self.schedule_filter_update();
+            }
+            KeyCode::Down => {
+                self.search.history_next();
+                self.schedule_filter_update();
+            }
+            KeyCode::Enter => {
+                self.search.submit();
+                self.flush_filter_update();
+                self.application_mode = ApplicationMode::Normal;
+            }
             KeyCode::Esc => self.toggle_processes_search_display(),
 
             _ => {}
@@ -389,38 +1105,118 @@ impl App {
     }
     fn handle_killing_mode_key(&mut self, key: KeyEvent) {
         match (key.modifiers, key.code) {
+            // During the countdown only aborting is possible
+            (_, KeyCode::Esc) if self.kill_process.countdown_until.is_some() => {
+                self.kill_process.hide();
+                self.application_mode = ApplicationMode::Normal;
+                self.status.info("Kill aborted");
+            }
+            _ if self.kill_process.countdown_until.is_some() => {}
             (KeyModifiers::NONE, KeyCode::Left) => {
                 self.kill_process.action = KillAction::Kill;
             }
             (KeyModifiers::NONE, KeyCode::Right) => {
                 self.kill_process.action = KillAction::Cancel;
             }
-            (KeyModifiers::NONE, KeyCode::Enter) => {
-                match self.kill_process.action {
-                    KillAction::Kill => {
-                        if let Some(item) = self.kill_process.item.take() {
-                            let killing_response = os::kill_process(item.pid);
-                            if killing_response.success {
-                                self.processes.retain(|p| p.pid != item.pid);
-                                self.update_filtered_processes();
-                            }
-                        }
-                    }
-                    KillAction::Cancel => {
-                        self.kill_process.item.take();
+            (KeyModifiers::NONE, KeyCode::Char('t')) => self.kill_process.toggle_kill_tree(),
+            (KeyModifiers::NONE, KeyCode::Char('a')) => {
+                self.kill_process.toggle_kill_all();
+                self.kill_process.protected = self.protected_target().is_some();
+            }
+            (KeyModifiers::NONE, KeyCode::Char('o')) => {
+                self.kill_process.toggle_override_protection()
+            }
+            (KeyModifiers::NONE, KeyCode::Char('g')) => self.kill_process.toggle_kill_group(),
+            (KeyModifiers::NONE, KeyCode::Enter) => match self.kill_process.action {
+                KillAction::Kill if self.kill_process.blocked() => {
+                    if let Some(name) = self.protected_target() {
+                        self.status.error(format!(
+                            "{} is protected in the config, press o to override",
+                            name
+                        ));
                     }
                 }
-                self.kill_process.display = false;
+                // An elevated retry was already confirmed once
+                KillAction::Kill
+                    if self.config.kill_countdown_secs > 0 && !self.kill_process.elevate =>
+                {
+                    self.kill_process.start_countdown(time::Duration::from_secs(
+                        self.config.kill_countdown_secs,
+                    ));
+                }
+                KillAction::Kill => self.confirm_kill(),
+                KillAction::Cancel => {
+                    self.kill_process.hide();
+                    self.application_mode = ApplicationMode::Normal;
+                }
+            },
+            (KeyModifiers::NONE, KeyCode::Esc) => {
+                self.kill_process.hide();
                 self.application_mode = ApplicationMode::Normal;
             }
+            _ => {}
+        }
+    }
+
+    /// Carries out the kill chosen in the popup and closes it, unless it is
+    /// reopened to offer an elevated retry.
+    fn confirm_kill(&mut self) {
+        self.kill_process.countdown_until = None;
+        if let Some(item) = self.kill_process.item.take() {
+            if self.kill_process.kill_all {
+                self.kill_all_on_port(item.port);
+            } else if let Some(pgid) = self
+                .kill_process
+                .pgid
+                .filter(|_| self.kill_process.kill_group)
+            {
+                self.kill_group(&item, pgid);
+            } else {
+                let tree = self.kill_process.kill_tree;
+                let elevate = self.kill_process.elevate;
+                if self.kill_entry(item, tree, elevate) {
+                    return;
+                }
+            }
+        }
+        self.kill_process.display = false;
+        self.application_mode = ApplicationMode::Normal;
+    }
+
+    /// The earliest moment the event loop has to wake up on its own.
+    fn next_wakeup(&self) -> Option<time::Instant> {
+        let filter = self.filter_dirty_since.map(|since| since + FILTER_DEBOUNCE);
+        [filter, self.kill_process.next_tick()]
+            .into_iter()
+            .flatten()
+            .min()
+    }
+    fn handle_quitting_mode_key(&mut self, key: KeyEvent) -> AppControlFlow {
+        match (key.modifiers, key.code) {
+            (KeyModifiers::NONE, KeyCode::Left) => self.quit.previous_action(),
+            (KeyModifiers::NONE, KeyCode::Right) | (KeyModifiers::NONE, KeyCode::Tab) => {
+                self.quit.next_action()
+            }
+            (KeyModifiers::NONE, KeyCode::Enter) => match self.quit.action {
+                QuitAction::ExportAndQuit => {
+                    self.export_audit_on_quit = true;
+                    return AppControlFlow::Exit;
+                }
+                QuitAction::QuitWithoutSaving => return AppControlFlow::Exit,
+                QuitAction::Cancel => {
+                    self.quit.hide();
+                    self.application_mode = ApplicationMode::Normal;
+                }
+            },
             (KeyModifiers::NONE, KeyCode::Esc) => {
-                self.kill_process.display = false;
+                self.quit.hide();
                 self.application_mode = ApplicationMode::Normal;
-                self.kill_process.item.take();
             }
             _ => {}
         }
+        AppControlFlow::Continue
     }
+
     fn handle_snapshotting_mode_key(&mut self, key: KeyEvent) {
         match (key.modifiers, key.code) {
             (KeyModifiers::NONE, KeyCode::Esc) | (KeyModifiers::NONE, KeyCode::F(2)) => {
@@ -438,16 +1234,25 @@ impl App {
             (KeyModifiers::NONE, KeyCode::Up) => {
                 self.snapshots_component.prev_format();
             }
+            (KeyModifiers::NONE, KeyCode::Tab) => {
+                self.snapshots_component.toggle_scope();
+            }
             (KeyModifiers::NONE, KeyCode::Enter) => {
-                match self.kill_process.action {
-                    KillAction::Kill => {
-                        let entries = self.table.items.clone();
-                        let export_type = self.snapshots_component.selected_format.clone();
-                        thread::spawn(move || {
-                            let _ = export_snapshot(&entries, export_type, None);
+                match self.snapshots_component.action {
+                    ExportAction::Export => {
+                        let entries = if self.snapshots_component.filtered_only {
+                            self.table.items.clone()
+                        } else {
+                            self.processes.clone()
+                        };
+                        let export_type = self.snapshots_component.selected_format;
+                        let with_metadata = self.config.export_metadata;
+                        let time = self.tracker.time_format.clone();
+                        self.export_in_background(move || {
+                            export_snapshot(&entries, export_type, None, with_metadata, &time)
                         });
                     }
-                    KillAction::Cancel => {}
+                    ExportAction::Cancel => {}
                 }
                 self.toggle_snapshotting_display();
             }
@@ -455,42 +1260,1248 @@ impl App {
             _ => {}
         }
     }
-    /// Monitors the ports and updates the processes list.
-    fn monitor_ports_loop(&mut self) {
-        match os::fetch_ports() {
-            Ok(ports) => {
-                // Always update the visible process list
-                self.processes = ports.clone();
-                self.update_filtered_processes();
-                let length = self.processes_filtered.len() * ITEM_HEIGHT as usize;
-                self.table.scroll = self.table.scroll.content_length(length);
+    fn handle_comparing_mode_key(&mut self, key: KeyEvent) {
+        match (key.modifiers, key.code) {
+            (KeyModifiers::NONE, KeyCode::Esc) | (KeyModifiers::NONE, KeyCode::F(4)) => {
+                self.toggle_snapshot_diff_display()
+            }
+            (KeyModifiers::NONE, KeyCode::Down) => self.snapshot_diff.next_row(),
+            (KeyModifiers::NONE, KeyCode::Up) => self.snapshot_diff.previous_row(),
+            (KeyModifiers::NONE, KeyCode::Enter) => self.snapshot_diff.select(),
+            (KeyModifiers::NONE, KeyCode::Backspace) => self.snapshot_diff.reset(),
+            _ => {}
+        }
+    }
+
+    fn handle_browsing_mode_key(&mut self, key: KeyEvent) {
+        match (key.modifiers, key.code) {
+            (KeyModifiers::NONE, KeyCode::Esc) | (KeyModifiers::NONE, KeyCode::F(3)) => {
+                self.toggle_snapshot_browser_display()
+            }
+            (KeyModifiers::NONE, KeyCode::Down) => self.snapshot_browser.next_row(),
+            (KeyModifiers::NONE, KeyCode::Up) => self.snapshot_browser.previous_row(),
+            (KeyModifiers::NONE, KeyCode::PageDown) => self.snapshot_browser.page_down(),
+            (KeyModifiers::NONE, KeyCode::PageUp) => self.snapshot_browser.page_up(),
+            (KeyModifiers::SHIFT, KeyCode::PageUp) => self.snapshot_browser.first_row(),
+            (KeyModifiers::SHIFT, KeyCode::PageDown) => self.snapshot_browser.last_row(),
+            (KeyModifiers::NONE, KeyCode::Char('r')) => self.snapshot_browser.refresh(),
+            (KeyModifiers::NONE, KeyCode::Enter) => self.open_browsed_snapshot(),
+            _ => {}
+        }
+    }
 
-                // If tracking is active, update tracker
-                if self.tracker.is_active {
-                    self.tracker.track_once(ports);
+    fn handle_columns_mode_key(&mut self, key: KeyEvent) {
+        let len = self.table.columns.len();
+        match (key.modifiers, key.code) {
+            (KeyModifiers::NONE, KeyCode::Esc) | (KeyModifiers::NONE, KeyCode::Char('c')) => {
+                self.toggle_columns_display()
+            }
+            (KeyModifiers::NONE, KeyCode::Down) => self.columns_component.next_row(len),
+            (KeyModifiers::NONE, KeyCode::Up) => self.columns_component.previous_row(len),
+            (KeyModifiers::NONE, KeyCode::Char(' ')) | (KeyModifiers::NONE, KeyCode::Enter) => {
+                if let Some(setting) = self
+                    .columns_component
+                    .selected()
+                    .and_then(|i| self.table.columns.get(i))
+                {
+                    self.table.toggle_column(setting.column);
+                    self.save_columns();
                 }
             }
-            Err(e) => {
-                eprintln!("Error fetching ports: {}", e);
+            _ => {}
+        }
+    }
+
+    fn handle_filtering_mode_key(&mut self, key: KeyEvent) {
+        match (key.modifiers, key.code) {
+            (KeyModifiers::NONE, KeyCode::Esc) | (KeyModifiers::NONE, KeyCode::Char('t')) => {
+                self.toggle_quick_filter_display()
+            }
+            (KeyModifiers::NONE, KeyCode::Tab) if self.search.display => {
+                self.application_mode = ApplicationMode::Editing;
             }
+            (KeyModifiers::NONE, KeyCode::Left) => self.quick_filter.previous(),
+            (KeyModifiers::NONE, KeyCode::Right) => self.quick_filter.next(),
+            (KeyModifiers::NONE, KeyCode::Char(' ')) | (KeyModifiers::NONE, KeyCode::Enter) => {
+                self.quick_filters
+                    .toggle(self.quick_filter.selected_filter());
+                self.update_filtered_processes();
+            }
+            _ => {}
         }
     }
 
-    /// Filters ports and updates filtered list.
-    fn update_filtered_processes(&mut self) {
-        let q = self.search.value.to_lowercase();
-        self.processes_filtered = self
-            .processes
-            .iter()
-            .filter(|p| {
-                // match pid
-                p.pid.to_string().contains(&q)
-                    || p.port.to_string().contains(&q)
-                    || p.process_name.to_lowercase().contains(&q)
-            })
-            .cloned()
-            .collect();
+    /// Opens the kill confirmation for the selected row, or kills right away
+    /// when its port is listed in `skip_confirm_ports`.
+    fn request_kill(&mut self) {
+        match self.table.selected() {
+            Some(item)
+                if self.config.skip_confirm_ports.contains(&item.port)
+                    && !self.config.is_protected(item)
+                    && self.source.is_live() =>
+            {
+                let item = item.clone();
+                self.kill_entry(item, false, false);
+            }
+            _ => self.toggle_kill_display(),
+        }
+    }
+
+    /// Kills `item` and reports the outcome. Returns whether the kill popup
+    /// was reopened to offer an elevated retry.
+    fn kill_entry(&mut self, mut item: PortInfo, tree: bool, elevate: bool) -> bool {
+        // The command line goes away with the process, so read it for the restart hint first
+        if self.config.restart_hint != RestartHint::Off && item.cmdline.is_none() {
+            item.cmdline = os::process_cmdline(item.pid);
+        }
+        let killing_response = match (elevate, tree) {
+            (true, _) => os::kill_process_elevated(item.pid, tree),
+            (false, true) => os::kill_process_tree(item.pid),
+            (false, false) => self.kill_pid(item.pid),
+        };
+        ring_bell();
+        let signal = self.kill_signal(tree, elevate);
+        self.kill_log.record(&item, &signal, &killing_response);
+        if killing_response.success {
+            self.processes.retain(|p| p.pid != item.pid);
+            self.update_filtered_processes();
+            self.summary.update(&self.processes);
+            self.log(StatusKind::Info, killing_response.message);
+            self.offer_restart(&item);
+        } else if killing_response.access_denied && !elevate {
+            // Keep the popup open so the kill can be retried elevated
+            self.log(StatusKind::Error, killing_response.message);
+            self.kill_process.offer_elevation(item);
+            self.application_mode = ApplicationMode::Killing;
+            return true;
+        } else {
+            self.log(StatusKind::Error, killing_response.message);
+        }
+        false
+    }
+
+    /// Shows or copies the command line of a killed process, as set by `restart_hint`,
+    /// so a dev server can be started again right away.
+    fn offer_restart(&mut self, item: &PortInfo) {
+        let Some(cmdline) = item.cmdline.as_deref() else {
+            return;
+        };
+        match self.config.restart_hint {
+            RestartHint::Off => {}
+            RestartHint::Show => self.log(StatusKind::Info, format!("Restart with: {}", cmdline)),
+            RestartHint::Copy => match copy_to_clipboard(cmdline) {
+                Ok(()) => self.log(
+                    StatusKind::Info,
+                    format!("Copied restart command `{}`", cmdline),
+                ),
+                Err(e) => self
+                    .status
+                    .error(format!("Cannot copy to clipboard: {}", e)),
+            },
+        }
+    }
+
+    /// How a kill asks the process to exit, as written to the kill log.
+    fn kill_signal(&self, tree: bool, elevate: bool) -> String {
+        let mut signal = if cfg!(target_family = "windows") {
+            "terminate".to_string()
+        } else {
+            match self.config.kill_grace_period_ms {
+                Some(ms) if !tree && !elevate => format!("SIGTERM, SIGKILL after {} ms", ms),
+                _ => "SIGTERM".to_string(),
+            }
+        };
+        if tree {
+            signal.push_str(" with children");
+        }
+        if elevate {
+            signal.push_str(" (elevated)");
+        }
+        signal
+    }
+
+    /// Toggles the kill confirmation for the selected row.
+    fn toggle_kill_display(&mut self) {
+        // PIDs of a snapshot may belong to unrelated processes on this machine
+        if !self.kill_process.display && !self.source.is_live() {
+            self.status
+                .error("Viewing a saved snapshot, its processes cannot be killed");
+            return;
+        }
+        // The selection may point past the rows if the list just shrank
+        if !self.kill_process.display && self.table.selected().is_none() {
+            return;
+        }
+        self.kill_process.display = !self.kill_process.display;
+        if self.kill_process.display {
+            self.application_mode = ApplicationMode::Killing;
+        } else {
+            self.application_mode = ApplicationMode::Normal;
+        }
+
+        // In grouped view the group's first port stands for the whole process
+        self.kill_process.item = self.table.selected().cloned();
+        if self.kill_process.display
+            && let Some(item) = self.kill_process.item.as_mut()
+        {
+            item.cmdline = os::process_cmdline(item.pid);
+        }
+        self.kill_process.kill_tree = false;
+        self.kill_process.kill_all = false;
+        self.kill_process.kill_group = false;
+        self.kill_process.override_protection = false;
+        self.kill_process.protected = self.protected_target().is_some();
+        self.kill_process.pgid = self
+            .kill_process
+            .item
+            .as_ref()
+            .and_then(|item| os::process_group_id(item.pid));
+        self.kill_process.port_pids = match &self.kill_process.item {
+            Some(item) => self.pids_on_port(item.port),
+            None => Vec::new(),
+        };
+    }
+
+    /// The first protected process the kill popup would hit, as `name (PID n)`
+    fn protected_target(&self) -> Option<String> {
+        let item = self.kill_process.item.as_ref()?;
+        let mut targets: Vec<&PortInfo> = vec![item];
+        if self.kill_process.kill_all {
+            targets.extend(self.processes.iter().filter(|p| p.port == item.port));
+        }
+        targets
+            .into_iter()
+            .find(|p| self.config.is_protected(p))
+            .map(|p| format!("{} (PID {})", p.process_name, p.pid))
+    }
+
+    /// Kills a single process, giving it a grace period first when configured.
+    fn kill_pid(&self, pid: u32) -> KillProcessResponse {
+        match self.config.kill_grace_period_ms {
+            Some(ms) => os::kill_process_escalate(pid, time::Duration::from_millis(ms)),
+            None => os::kill_process(pid),
+        }
+    }
+
+    /// Distinct PIDs holding `port`, across every listed row
+    fn pids_on_port(&self, port: u16) -> Vec<u32> {
+        let mut pids: Vec<u32> = self
+            .processes
+            .iter()
+            .filter(|p| p.port == port)
+            .map(|p| p.pid)
+            .collect();
+        pids.sort_unstable();
+        pids.dedup();
+        pids
+    }
+
+    /// Kills every process on `port`, e.g. a server together with its workers,
+    /// and reports how many of them went down.
+    fn kill_all_on_port(&mut self, port: u16) {
+        let tree = self.kill_process.kill_tree;
+        let signal = self.kill_signal(tree, false);
+        let pids = self.pids_on_port(port);
+        let mut failures = Vec::new();
+        for &pid in &pids {
+            let response = if tree {
+                os::kill_process_tree(pid)
+            } else {
+                self.kill_pid(pid)
+            };
+            if let Some(target) = self
+                .processes
+                .iter()
+                .find(|p| p.pid == pid && p.port == port)
+            {
+                self.kill_log.record(target, &signal, &response);
+            }
+            if response.success {
+                self.processes.retain(|p| p.pid != pid);
+            } else {
+                failures.push(response.message);
+            }
+        }
+        ring_bell();
+
+        self.update_filtered_processes();
+        self.summary.update(&self.processes);
+        let killed = pids.len() - failures.len();
+        match failures.first() {
+            None => self.log(
+                StatusKind::Info,
+                format!("Killed {} processes on port {}", killed, port),
+            ),
+            Some(first) => self.log(
+                StatusKind::Error,
+                format!(
+                    "Killed {} of {} processes on port {}: {}",
+                    killed,
+                    pids.len(),
+                    port,
+                    first
+                ),
+            ),
+        }
+    }
+
+    /// Sends SIGTERM to the process group of `item`, freeing ports held by
+    /// children a launch script forked. Other members vanish on the next refresh.
+    fn kill_group(&mut self, item: &PortInfo, pgid: u32) {
+        let response = os::kill_process_group(pgid, os::SIGTERM);
+        ring_bell();
+        self.kill_log
+            .record(item, &format!("SIGTERM to group {}", pgid), &response);
+        if response.success {
+            self.processes.retain(|p| p.pid != item.pid);
+            self.update_filtered_processes();
+            self.summary.update(&self.processes);
+            self.log(StatusKind::Info, response.message);
+        } else {
+            self.log(StatusKind::Error, response.message);
+        }
+    }
+
+    /// Focuses the quick-filter bar, or leaves it. The bar stays visible
+    /// while the search box is open.
+    fn toggle_quick_filter_display(&mut self) {
+        if matches!(self.application_mode, ApplicationMode::Filtering) {
+            self.quick_filter.display = false;
+            self.application_mode = if self.search.display {
+                ApplicationMode::Editing
+            } else {
+                ApplicationMode::Normal
+            };
+        } else {
+            self.quick_filter.display = true;
+            self.application_mode = ApplicationMode::Filtering;
+        }
+    }
+
+    /// Toggles the column settings display.
+    fn toggle_columns_display(&mut self) {
+        self.columns_component.toggle();
+
+        if self.columns_component.display {
+            self.application_mode = ApplicationMode::Columns;
+        } else {
+            self.application_mode = ApplicationMode::Normal;
+        }
+    }
+
+    /// Persists the visible columns to the config file.
+    fn save_columns(&mut self) {
+        self.config.columns = Some(self.table.visible_columns());
+        if self.config_invalid {
+            self.status
+                .error("Column settings not saved, fix the errors in the config file first");
+            return;
+        }
+        if let Err(e) = self.config.save_columns() {
+            self.status
+                .error(format!("Failed to save column settings: {}", e));
+        }
+    }
+
+    /// Switches between all ports and only those owned by the current user.
+    fn toggle_mine_only(&mut self) {
+        let Some(user) = self.current_user.clone() else {
+            self.status.error("Could not determine the current user");
+            return;
+        };
+
+        self.mine_only = !self.mine_only;
+        self.update_filtered_processes();
+        if self.mine_only {
+            self.status
+                .info(format!("Showing only ports owned by {}", user));
+        } else {
+            self.status.info("Showing ports of all users");
+        }
+    }
+
+    /// Switches between all ports and only those with a resolvable process path.
+    fn toggle_unknown_paths(&mut self) {
+        self.hide_unknown_paths = !self.hide_unknown_paths;
+        self.update_filtered_processes();
+        if self.hide_unknown_paths {
+            self.status.info("Hiding processes with unknown paths");
+        } else {
+            self.status.info("Showing processes with unknown paths");
+        }
+    }
+
+    /// Switches between all ports and only those reachable from other machines.
+    fn toggle_external_only(&mut self) {
+        self.external_only = !self.external_only;
+        self.update_filtered_processes();
+        if self.external_only {
+            self.status.info("Showing only externally reachable ports");
+        } else {
+            self.status.info("Showing loopback and external ports");
+        }
+    }
+
+    /// Switches between hiding and listing this tool's own process.
+    fn toggle_own_process(&mut self) {
+        self.hide_own_process = !self.hide_own_process;
+        self.update_filtered_processes();
+        if self.hide_own_process {
+            self.status.info("Hiding harboor-sweep's own process");
+        } else {
+            self.status.info("Showing harboor-sweep's own process");
+        }
+    }
+
+    /// Switches between hiding and listing kernel and OS pseudo-processes.
+    fn toggle_system_processes(&mut self) {
+        self.hide_system_processes = !self.hide_system_processes;
+        self.update_filtered_processes();
+        if self.hide_system_processes {
+            self.status.info("Hiding kernel and system processes");
+        } else {
+            self.status.info("Showing kernel and system processes");
+        }
+    }
+
+    /// Switches between one row per IPv4/IPv6 socket and merged dual-stack rows.
+    fn toggle_merge_dual_stack(&mut self) {
+        self.merge_dual_stack = !self.merge_dual_stack;
+        self.update_filtered_processes();
+        if self.merge_dual_stack {
+            self.status
+                .info("Merging IPv4 and IPv6 rows of the same socket");
+        } else {
+            self.status.info("Listing IPv4 and IPv6 rows separately");
+        }
+    }
+
+    /// Switches whether the selection jumps to ports opened while auditing.
+    fn toggle_follow_new_ports(&mut self) {
+        self.follow_new_ports = !self.follow_new_ports;
+        if !self.follow_new_ports {
+            self.status.info("No longer following new ports");
+        } else if self.tracker.is_active {
+            self.status.info("Following newly opened ports");
+        } else {
+            self.status
+                .info("Following newly opened ports once auditing starts (S)");
+        }
+    }
+
+    /// Asks the background thread for an immediate port fetch.
+    fn request_refresh(&mut self) {
+        if let Some(tx) = &self.refresh_tx
+            && tx.send(()).is_ok()
+        {
+            self.refreshing = true;
+            self.status.info("Refreshing…");
+        }
+    }
+
+    /// Sorts by `by`, or by port when no row has a value for it, such as CPU usage
+    /// before the first sample.
+    fn sort_table(&mut self, by: SortBy) {
+        if self.table.has_sort_data(by) {
+            self.table.set_or_toggle_sort(by);
+        } else {
+            self.table.set_sort_column(SortBy::Port);
+            self.status
+                .error(format!("No {} data to sort by, sorted by Port", by.label()));
+        }
+    }
+
+    /// Pauses or resumes automatic list updates.
+    fn toggle_pause(&mut self) {
+        self.paused = !self.paused;
+        self.sync_monitoring();
+    }
+
+    /// Stops the background thread's automatic fetches while nothing uses them:
+    /// the list is paused and no audit is running. Resuming fetches right away.
+    fn sync_monitoring(&mut self) {
+        let monitoring = !self.paused || self.tracker.is_active;
+        if self.state.set_monitoring(monitoring) && monitoring {
+            self.wake_background_thread();
+        }
+    }
+
+    /// Lengthens or shortens the time between automatic refreshes by `delta` seconds.
+    fn change_interval(&mut self, delta: i64) {
+        let secs = self
+            .state
+            .interval_secs()
+            .saturating_add_signed(delta)
+            .clamp(MIN_INTERVAL_SECS, MAX_INTERVAL_SECS);
+        if self.state.update_interval(secs).is_ok() {
+            self.wake_background_thread();
+            self.status.info(format!("Refreshing every {}s", secs));
+        }
+    }
+
+    /// Cuts the background thread's current wait short so new settings apply now.
+    fn wake_background_thread(&self) {
+        if let Some(tx) = &self.refresh_tx {
+            let _ = tx.send(());
+        }
+    }
+
+    /// Runs an export on a worker thread and reports the result back to the UI loop.
+    fn export_in_background<F>(&self, export: F)
+    where
+        F: FnOnce() -> io::Result<PathBuf> + Send + 'static,
+    {
+        let tx = self.event_tx.clone();
+        thread::spawn(move || {
+            let result = export();
+            if let Some(tx) = tx {
+                let _ = tx.send(MultithreadingEvent::ExportFinished(result));
+            }
+        });
+    }
+
+    /// Exports only the selected row, in the snapshot export format.
+    fn export_selected_process(&mut self) {
+        let Some(entry) = self.table.selected().cloned() else {
+            self.status.error("Select a row to export");
+            return;
+        };
+        let export_type = self.snapshots_component.selected_format;
+        let with_metadata = self.config.export_metadata;
+        let time = self.tracker.time_format.clone();
+        self.export_in_background(move || {
+            export_process_snapshot(&entry, export_type, None, with_metadata, &time)
+        });
+    }
+
+    /// Shows the selected process's binary in the platform file manager.
+    fn reveal_selected_process(&mut self) {
+        let Some(entry) = self.table.selected() else {
+            self.status.error("Select a row to open its location");
+            return;
+        };
+        if !entry.has_known_path() {
+            self.status
+                .error(format!("Location of {} is unknown", entry.process_name));
+            return;
+        }
+        let path = PathBuf::from(&entry.process_path);
+        match reveal_in_file_manager(&path) {
+            Ok(()) => self.status.info(format!("Opened {}", path.display())),
+            Err(e) => self
+                .status
+                .error(format!("Cannot open file manager: {}", e)),
+        }
+    }
+
+    /// Copies a command that kills the selected process with elevation, to run in
+    /// a shell of your own.
+    fn copy_kill_command(&mut self) {
+        let Some(entry) = self.table.selected() else {
+            self.status.error("Select a row to copy its kill command");
+            return;
+        };
+        let command = os::manual_kill_command(entry.pid);
+        match copy_to_clipboard(&command) {
+            Ok(()) => self.status.info(format!("Copied `{}`", command)),
+            Err(e) => self
+                .status
+                .error(format!("Cannot copy to clipboard: {}", e)),
+        }
+    }
+
+    /// Copies the rows as filtered and sorted to the clipboard as JSON, in the
+    /// same layout as a JSON snapshot export.
+    fn copy_visible_json(&mut self) {
+        if self.table.items.is_empty() {
+            self.status.error("No rows to copy");
+            return;
+        }
+        let copied = to_json(
+            &self.table.items,
+            self.config.export_metadata,
+            &self.tracker.time_format,
+        )
+        .and_then(|json| copy_to_clipboard(&json));
+        match copied {
+            Ok(()) => self
+                .status
+                .info(format!("Copied {} rows as JSON", self.table.items.len())),
+            Err(e) => self
+                .status
+                .error(format!("Cannot copy to clipboard: {}", e)),
+        }
+    }
+
+    /// Stops a running audit and exports it when `export_on_exit` is set, so
+    /// quitting does not lose the recorded events. Returns the outcome to print
+    /// once the terminal is restored.
+    fn export_on_exit(&mut self) -> Option<String> {
+        let mut messages = Vec::new();
+        if self.tracker.is_active && (self.config.export_on_exit || self.export_audit_on_quit) {
+            let result = self.tracker.stop();
+            messages.push(match &result {
+                Ok(path) => format!("Audit exported to {}", path.display()),
+                Err(e) => format!("Audit export failed: {}", e),
+            });
+            self.report_export(result);
+        }
+        if self.config.export_on_exit && !self.kill_log.is_empty() {
+            let result = self.export_kill_log();
+            messages.push(match &result {
+                Ok(path) => format!("Kill log exported to {}", path.display()),
+                Err(e) => format!("Kill log export failed: {}", e),
+            });
+            self.report_export(result);
+        }
+        (!messages.is_empty()).then(|| messages.join("\n"))
+    }
+
+    /// Writes the kill log in the audit's export format.
+    fn export_kill_log(&self) -> io::Result<PathBuf> {
+        self.kill_log.export(
+            self.tracker.export_format,
+            None,
+            self.config.export_metadata,
+            &self.tracker.time_format,
+        )
+    }
+
+    /// Surfaces the outcome of an export in the status line.
+    fn report_export(&mut self, result: io::Result<PathBuf>) {
+        match result {
+            Ok(path) => self.log(StatusKind::Info, format!("Saved {}", path.display())),
+            Err(e) => self.log(StatusKind::Error, format!("Export failed: {}", e)),
+        }
+    }
+
+    /// Shows `text` in the status line and keeps it in the activity log.
+    fn log(&mut self, kind: StatusKind, text: impl Into<String>) {
+        let text = text.into();
+        self.activity.record(kind, text.clone());
+        match kind {
+            StatusKind::Info => self.status.info(text),
+            StatusKind::Error => self.status.error(text),
+        }
+    }
+
+    /// Turns reverse DNS lookups of remote addresses on or off.
+    fn toggle_reverse_dns(&mut self) {
+        if self.resolver.take().is_some() {
+            self.status.info("Reverse DNS disabled");
+        } else {
+            self.resolver = Some(HostResolver::new());
+            self.status.info("Reverse DNS enabled");
+        }
+    }
+
+    /// Whether a popup or prompt is open that acts on the rows as they were when it opened.
+    fn list_frozen(&self) -> bool {
+        !matches!(
+            self.application_mode,
+            ApplicationMode::Normal | ApplicationMode::Editing
+        )
+    }
+
+    /// Applies the refresh held back by [`Self::list_frozen`] once the popup is closed.
+    fn apply_queued_update(&mut self) {
+        if !self.list_frozen()
+            && let Some((ports, opened)) = self.queued_update.take()
+        {
+            self.update_processes(ports, Some(opened));
+        }
+    }
+
+    /// Applies a refresh from the background thread to the processes list.
+    fn monitor_ports_loop(&mut self, ports: PortsUpdate) {
+        // While paused only a manual refresh updates the list, auditing keeps running
+        if self.paused && !self.refreshing {
+            if let Ok(ports) = ports {
+                self.track(ports);
+            }
+            return;
+        }
+
+        // Hold the list still under an open popup, only the audit sees the change now
+        if self.list_frozen() {
+            let opened = match &ports {
+                Ok(ports) => self.track(ports.clone()),
+                Err(_) => None,
+            };
+            // An opening seen by an earlier held-back refresh is still worth following
+            let earlier = self.queued_update.take().and_then(|(_, opened)| opened);
+            self.queued_update = Some((ports, opened.or(earlier)));
+            return;
+        }
+        self.queued_update = None;
+        self.update_processes(ports, None);
+    }
+
+    /// Shows a refresh in the processes list. `tracked` holds the port the audit saw
+    /// opening when the refresh was already fed to it while held back.
+    fn update_processes(&mut self, ports: PortsUpdate, tracked: Option<Option<String>>) {
+        let manual = std::mem::take(&mut self.refreshing);
+        self.loaded = true;
+        if manual && let Ok(ports) = &ports {
+            self.status.info(format!("Refreshed {} ports", ports.len()));
+        }
+
+        match ports {
+            Ok(mut ports) => {
+                if let Some(resolver) = &self.resolver {
+                    for port in ports.iter_mut() {
+                        if let Some(addr) = port.remote_address {
+                            port.remote_host = resolver.lookup(addr.ip());
+                        }
+                    }
+                }
+
+                // Flash ports that opened or closed since the previous refresh
+                if !self.processes.is_empty() {
+                    let (added, removed) = Tracker::diff_ports(&self.processes, &ports);
+                    self.table.mark_changes(&added, removed);
+                }
+
+                // If tracking is active, update tracker; it keeps its own copy as baseline
+                let opened = match tracked {
+                    Some(opened) => opened,
+                    None => self.track(ports.clone()),
+                };
+
+                // Always update the visible process list
+                self.processes = ports;
+                self.summary.update(&self.processes);
+                self.update_filtered_processes();
+
+                // Land on the newest port, unless the filter hides it
+                if self.follow_new_ports
+                    && let Some(row) = opened.and_then(|id| self.table.row_of(&id))
+                {
+                    self.table.select_row(row);
+                }
+            }
+            Err(e) if manual => self.status.error(format!("Refresh failed: {}", e)),
+            Err(e) => {
+                self.status.error(e);
+                // A failed first fetch should not read as still loading
+                if self.processes.is_empty() {
+                    self.update_filtered_processes();
+                }
+            }
+        }
+    }
+
+    /// Feeds a refresh to the audit when it is running, recording the port
+    /// changes in the activity log. Returns the id of the last port that opened.
+    fn track(&mut self, ports: Vec<PortInfo>) -> Option<String> {
+        if !self.tracker.is_active {
+            return None;
+        }
+        let events = self.tracker.track_once(ports);
+        if self.config.notify_on_open {
+            Self::notify_opened_ports(events);
+        }
+
+        let mut opened = None;
+        for event in events {
+            let (verb, port) = match event {
+                PortEvent::PortOpened { port, .. } => {
+                    opened = Some(port.id.clone());
+                    ("opened", port)
+                }
+                PortEvent::PortClosed { port, .. } => ("closed", port),
+                PortEvent::InitialState { .. } => continue,
+            };
+            self.activity.record(
+                StatusKind::Info,
+                format!(
+                    "Port {} {} by {} (PID {})",
+                    port.port, verb, port.process_name, port.pid
+                ),
+            );
+        }
+        opened
+    }
+
+    /// Sends a desktop notification for every newly opened listening port.
+    fn notify_opened_ports(events: &[PortEvent]) {
+        for event in events {
+            if let PortEvent::PortOpened { port, .. } = event
+                && port.port_state == ProcessPortState::Hosting
+            {
+                desktop_notify(
+                    "New listening port",
+                    &format!(
+                        "{} is now listening on port {}",
+                        port.process_name, port.port
+                    ),
+                );
+            }
+        }
+    }
+
+    /// The filter described by the search box and the toggles.
+    fn port_filter(&self) -> PortFilter {
+        let mut filter = PortFilter {
+            case_sensitive: self.search.case_sensitive,
+            owner: self.current_user.clone().filter(|_| self.mine_only),
+            quick: self.quick_filters,
+            known_paths_only: self.hide_unknown_paths,
+            external_only: self.external_only,
+            exclude_pid: self.hide_own_process.then(std::process::id),
+            hide_system: self.hide_system_processes,
+            ..Default::default()
+        };
+        filter.set_query(&self.search.value);
+        filter
+    }
+
+    /// Re-filters once typing pauses for [`FILTER_DEBOUNCE`].
+    fn schedule_filter_update(&mut self) {
+        self.filter_dirty_since = Some(time::Instant::now());
+    }
+
+    /// Applies a pending search edit right away.
+    fn flush_filter_update(&mut self) {
+        if self.filter_dirty_since.is_some() {
+            self.update_filtered_processes();
+        }
+    }
+
+    /// Filters ports and updates filtered list.
+    fn update_filtered_processes(&mut self) {
+        self.filter_dirty_since = None;
+        let filter = self.port_filter();
+        self.table
+            .set_highlight(&filter.query, filter.case_sensitive);
+        let mut items: Vec<PortInfo> = filter.apply(&self.processes).cloned().collect();
+        if self.merge_dual_stack {
+            items = merge_dual_stack(items);
+        }
+        // The table owns the filtered view and sorts it in place
+        self.table.set_items(items);
+        self.table.empty_state = if !self.loaded {
+            EmptyState::Loading
+        } else if self.processes.is_empty() {
+            EmptyState::NoPorts
+        } else {
+            EmptyState::NoMatches
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::MockPortSource;
+
+    fn port(port: u16, pid: u32, name: &str) -> PortInfo {
+        PortInfo::fixture(port, pid, name, ProcessPortState::Hosting)
+    }
+
+    fn refresh(app: &mut App) {
+        let ports = app.source.fetch();
+        app.monitor_ports_loop(ports);
+    }
+
+    #[test]
+    fn refresh_filters_and_sorts_the_table() {
+        let source = MockPortSource::new(vec![
+            port(8080, 30, "node"),
+            port(22, 10, "sshd"),
+            port(3000, 20, "node"),
+        ]);
+        let mut app = App::new(Config::default(), Arc::new(source));
+        refresh(&mut app);
+
+        let ports: Vec<u16> = app.table.items.iter().map(|p| p.port).collect();
+        assert_eq!(ports, [22, 3000, 8080]);
+
+        app.search.value = "node".into();
+        app.update_filtered_processes();
+        app.table.set_or_toggle_sort(SortBy::Pid);
+        app.table.set_or_toggle_sort(SortBy::Pid);
+        let pids: Vec<u32> = app.table.items.iter().map(|p| p.pid).collect();
+        assert_eq!(pids, [30, 20]);
+
+        // Tab flips the direction but keeps the column
+        app.handle_normal_mode_key(KeyEvent::from(KeyCode::Tab))
+            .unwrap();
+        let pids: Vec<u32> = app.table.items.iter().map(|p| p.pid).collect();
+        assert_eq!(pids, [20, 30]);
+        assert_eq!(app.table.sort_by, SortBy::Pid);
+    }
+
+    #[test]
+    fn tracker_records_ports_opened_between_refreshes() {
+        let source = MockPortSource::sequence(vec![
+            Ok(vec![port(22, 10, "sshd")]),
+            Ok(vec![port(22, 10, "sshd"), port(5432, 40, "postgres")]),
+        ]);
+        let mut app = App::new(Config::default(), Arc::new(source));
+        refresh(&mut app);
+        app.tracker.start(app.processes.clone());
+        refresh(&mut app);
+
+        let opened: Vec<u16> = app
+            .tracker
+            .events
+            .iter()
+            .filter_map(|e| match e {
+                PortEvent::PortOpened { port, .. } => Some(port.port),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(opened, [5432]);
+        assert_eq!(app.summary.total, 2);
+    }
+
+    #[test]
+    fn selection_follows_newly_opened_ports() {
+        let source = MockPortSource::sequence(vec![
+            Ok(vec![port(22, 10, "sshd"), port(8080, 30, "node")]),
+            Ok(vec![
+                port(22, 10, "sshd"),
+                port(5432, 40, "postgres"),
+                port(8080, 30, "node"),
+            ]),
+            Ok(vec![
+                port(22, 10, "sshd"),
+                port(5432, 40, "postgres"),
+                port(8080, 30, "node"),
+                port(9000, 50, "minio"),
+            ]),
+        ]);
+        let mut app = App::new(Config::default(), Arc::new(source));
+        refresh(&mut app);
+        app.table.state.select(Some(0));
+        app.tracker.start(app.processes.clone());
+        app.toggle_follow_new_ports();
+        refresh(&mut app);
+        assert_eq!(app.table.selected().map(|p| p.port), Some(5432));
+
+        // A port hidden by the filter leaves the selection alone
+        app.search.value = "postgres".into();
+        app.update_filtered_processes();
+        refresh(&mut app);
+        assert_eq!(app.table.selected().map(|p| p.port), Some(5432));
+    }
+
+    #[test]
+    fn help_scrolls_half_pages_both_ways() {
+        let mut app = App::new(Config::default(), Arc::new(MockPortSource::new(Vec::new())));
+        app.toggle_keybindings_display();
+        app.keybindings.visible_rows = 10;
+        let start = app.keybindings.state.selected();
+
+        let ctrl = |c| KeyEvent::new(KeyCode::Char(c), KeyModifiers::CONTROL);
+        app.handle_helping_mode_key(ctrl('d'));
+        assert_ne!(app.keybindings.state.selected(), start);
+        app.handle_helping_mode_key(ctrl('b'));
+        assert_eq!(app.keybindings.state.selected(), start);
+
+        app.handle_helping_mode_key(KeyEvent::from(KeyCode::Char('q')));
+        app.handle_helping_mode_key(ctrl('u'));
+        assert!(app.keybindings.filter.value.is_empty());
+    }
+
+    #[test]
+    fn ports_opened_under_a_popup_are_followed_once_it_closes() {
+        let source = MockPortSource::sequence(vec![
+            Ok(vec![port(22, 10, "sshd")]),
+            Ok(vec![port(22, 10, "sshd"), port(5432, 40, "postgres")]),
+            Ok(vec![port(22, 10, "sshd"), port(5432, 40, "postgres")]),
+        ]);
+        let mut app = App::new(Config::default(), Arc::new(source));
+        refresh(&mut app);
+        app.tracker.start(app.processes.clone());
+        app.toggle_follow_new_ports();
+        app.toggle_kill_display();
+
+        // The opening is recorded once, and a quiet refresh after it does not lose it
+        refresh(&mut app);
+        refresh(&mut app);
+        assert_eq!(app.tracker.events.len(), 2);
+
+        app.handle_killing_mode_key(KeyEvent::from(KeyCode::Esc));
+        app.apply_queued_update();
+        assert_eq!(app.tracker.events.len(), 2);
+        assert_eq!(app.table.selected().map(|p| p.port), Some(5432));
+    }
+
+    #[test]
+    fn snapshots_cannot_be_killed_from() {
+        #[derive(Debug)]
+        struct Saved;
+        impl PortSource for Saved {
+            fn fetch(&self) -> Result<Vec<PortInfo>, String> {
+                Ok(vec![port(3000, 20, "node")])
+            }
+            fn is_live(&self) -> bool {
+                false
+            }
+        }
+
+        let mut config = Config::default();
+        config.skip_confirm_ports.push(3000);
+        let mut app = App::new(config, Arc::new(Saved));
+        refresh(&mut app);
+        app.table.state.select(Some(0));
+        app.request_kill();
+        assert!(!app.kill_process.display);
+        assert!(matches!(app.application_mode, ApplicationMode::Normal));
+        assert_eq!(app.legend_items().last().unwrap(), "Offline snapshot");
+    }
+
+    #[test]
+    fn empty_table_tells_loading_from_no_matches() {
+        let source = MockPortSource::sequence(vec![Ok(Vec::new()), Ok(vec![port(22, 10, "sshd")])]);
+        let mut app = App::new(Config::default(), Arc::new(source));
+        assert_eq!(app.table.empty_state, EmptyState::Loading);
+
+        refresh(&mut app);
+        assert_eq!(app.table.empty_state, EmptyState::NoPorts);
+
+        refresh(&mut app);
+        app.search.value = "postgres".into();
+        app.update_filtered_processes();
+        assert!(app.table.items.is_empty());
+        assert_eq!(app.table.empty_state, EmptyState::NoMatches);
+    }
+
+    #[test]
+    fn refreshes_wait_until_the_kill_popup_closes() {
+        let source = MockPortSource::sequence(vec![
+            Ok(vec![port(22, 10, "sshd"), port(3000, 20, "node")]),
+            Ok(vec![port(80, 5, "nginx"), port(3000, 20, "node")]),
+        ]);
+        let mut app = App::new(Config::default(), Arc::new(source));
+        refresh(&mut app);
+        app.table.state.select(Some(1));
+        app.toggle_kill_display();
+
+        refresh(&mut app);
+        app.apply_queued_update();
+        let ports: Vec<u16> = app.table.items.iter().map(|p| p.port).collect();
+        assert_eq!(ports, [22, 3000]);
+        assert_eq!(app.table.selected().map(|p| p.pid), Some(20));
+
+        app.handle_killing_mode_key(KeyEvent::from(KeyCode::Esc));
+        app.apply_queued_update();
+        let ports: Vec<u16> = app.table.items.iter().map(|p| p.port).collect();
+        assert_eq!(ports, [80, 3000]);
+        assert!(app.queued_update.is_none());
+    }
+
+    #[test]
+    fn restart_hint_shows_the_killed_command() {
+        let mut app = App::new(Config::default(), Arc::new(MockPortSource::new(Vec::new())));
+        let mut item = port(3000, 20, "node");
+        app.offer_restart(&item);
+        assert!(app.activity.entries.is_empty());
+
+        item.cmdline = Some("node server.js --port 3000".into());
+        app.offer_restart(&item);
+        assert_eq!(
+            app.activity.entries[0].text,
+            "Restart with: node server.js --port 3000"
+        );
+
+        app.config.restart_hint = RestartHint::Off;
+        app.offer_restart(&item);
+        assert_eq!(app.activity.entries.len(), 1);
+    }
+
+    #[test]
+    fn activity_log_keeps_exports_and_audited_changes() {
+        let source = MockPortSource::sequence(vec![
+            Ok(vec![port(22, 10, "sshd")]),
+            Ok(vec![port(22, 10, "sshd"), port(5432, 40, "postgres")]),
+        ]);
+        let mut app = App::new(Config::default(), Arc::new(source));
+        refresh(&mut app);
+        app.tracker.start(app.processes.clone());
+        refresh(&mut app);
+        app.report_export(Err(io::Error::other("disk full")));
+
+        let texts: Vec<&str> = app
+            .activity
+            .entries
+            .iter()
+            .map(|e| e.text.as_str())
+            .collect();
+        assert_eq!(
+            texts,
+            [
+                "Export failed: disk full",
+                "Port 5432 opened by postgres (PID 40)"
+            ]
+        );
+        assert_eq!(app.activity.entries[0].kind, StatusKind::Error);
+
+        app.handle_normal_mode_key(KeyEvent::new(KeyCode::Char('L'), KeyModifiers::SHIFT))
+            .unwrap();
+        assert!(matches!(app.application_mode, ApplicationMode::Activity));
+        assert_eq!(app.activity.state.selected(), Some(0));
+    }
+
+    #[test]
+    fn pausing_stops_monitoring_unless_auditing() {
+        let source = MockPortSource::new(vec![port(22, 10, "sshd")]);
+        let mut app = App::new(Config::default(), Arc::new(source));
+        let (refresh_tx, refresh_rx) = mpsc::channel();
+        app.refresh_tx = Some(refresh_tx);
+        refresh(&mut app);
+
+        app.handle_normal_mode_key(KeyEvent::from(KeyCode::Char('p')))
+            .unwrap();
+        assert!(!app.state.is_monitoring());
+        app.handle_normal_mode_key(KeyEvent::from(KeyCode::Char('S')))
+            .unwrap();
+        assert!(app.state.is_monitoring());
+        // Monitoring resumed, so the background thread is woken to fetch
+        assert!(refresh_rx.try_recv().is_ok());
+
+        app.handle_normal_mode_key(KeyEvent::from(KeyCode::Char('+')))
+            .unwrap();
+        assert_eq!(app.state.interval_secs(), 3);
+    }
+
+    #[test]
+    fn quitting_while_auditing_asks_first() {
+        let source = MockPortSource::new(vec![port(22, 10, "sshd")]);
+        let mut app = App::new(Config::default(), Arc::new(source));
+        refresh(&mut app);
+        let q = KeyEvent::from(KeyCode::Char('q'));
+
+        app.tracker.start(app.processes.clone());
+        let flow = app.handle_normal_mode_key(q).unwrap();
+        assert!(matches!(flow, AppControlFlow::Continue));
+        assert!(matches!(app.application_mode, ApplicationMode::Quitting));
+
+        // Cancel keeps the audit running
+        app.handle_quitting_mode_key(KeyEvent::from(KeyCode::Right));
+        app.handle_quitting_mode_key(KeyEvent::from(KeyCode::Right));
+        assert_eq!(app.quit.action, QuitAction::Cancel);
+        let flow = app.handle_quitting_mode_key(KeyEvent::from(KeyCode::Enter));
+        assert!(matches!(flow, AppControlFlow::Continue));
+        assert!(matches!(app.application_mode, ApplicationMode::Normal));
+        assert!(app.tracker.is_active);
+
+        app.handle_normal_mode_key(q).unwrap();
+        app.handle_quitting_mode_key(KeyEvent::from(KeyCode::Right));
+        let flow = app.handle_quitting_mode_key(KeyEvent::from(KeyCode::Enter));
+        assert!(matches!(flow, AppControlFlow::Exit));
+        assert!(!app.export_audit_on_quit);
+
+        // Nothing to lose once the audit is stopped, or when export_on_exit saves it
+        app.tracker.is_active = false;
+        let flow = app.handle_normal_mode_key(q).unwrap();
+        assert!(matches!(flow, AppControlFlow::Exit));
+        app.tracker.is_active = true;
+        app.config.export_on_exit = true;
+        let flow = app.handle_normal_mode_key(q).unwrap();
+        assert!(matches!(flow, AppControlFlow::Exit));
+    }
+
+    #[test]
+    fn kill_ignores_a_selection_past_the_rows() {
+        let source =
+            MockPortSource::sequence(vec![Ok(vec![port(3000, 20, "node")]), Ok(Vec::new())]);
+        let mut app = App::new(Config::default(), Arc::new(source));
+        refresh(&mut app);
+        app.table.state.select(Some(0));
+        refresh(&mut app);
+        // A keypress can still carry the index drawn before the list emptied
+        app.table.state.select(Some(0));
+
+        app.handle_normal_mode_key(KeyEvent::from(KeyCode::Char('k')))
+            .unwrap();
+        assert!(!app.kill_process.display);
+        assert!(matches!(app.application_mode, ApplicationMode::Normal));
+
+        app.toggle_kill_display();
+        assert!(!app.kill_process.display);
+    }
+
+    #[test]
+    fn pids_on_port_are_distinct() {
+        let source = MockPortSource::new(vec![
+            port(8080, 20, "gunicorn"),
+            port(8080, 21, "gunicorn"),
+            port(8080, 20, "gunicorn"),
+            port(9090, 30, "prometheus"),
+        ]);
+        let mut app = App::new(Config::default(), Arc::new(source));
+        refresh(&mut app);
+        assert_eq!(app.pids_on_port(8080), [20, 21]);
+    }
+
+    #[test]
+    fn legend_lists_active_filters() {
+        let mut app = App::new(Config::default(), Arc::new(MockPortSource::new(Vec::new())));
+        assert_eq!(app.legend_items(), ["Sort: Port ▲"]);
+
+        app.search.value = "node ".into();
+        app.quick_filters.toggle(QuickFilter::Udp);
+        app.table.set_or_toggle_sort(SortBy::Port);
+        assert_eq!(
+            app.legend_items(),
+            ["Sort: Port ▼", "Search: \"node\"", "Hidden: UDP"]
+        );
+    }
+
+    #[test]
+    fn esc_aborts_the_kill_countdown() {
+        let mut app = App::new(Config::default(), Arc::new(MockPortSource::new(Vec::new())));
+        app.kill_process.show(port(3000, u32::MAX, "node"));
+        app.application_mode = ApplicationMode::Killing;
+
+        app.handle_killing_mode_key(KeyEvent::from(KeyCode::Enter));
+        assert!(app.kill_process.countdown_until.is_some());
+        assert!(!app.kill_process.countdown_due());
+
+        app.handle_killing_mode_key(KeyEvent::from(KeyCode::Esc));
+        assert!(!app.kill_process.display);
+        assert!(app.kill_process.item.is_none());
+        assert!(matches!(app.application_mode, ApplicationMode::Normal));
+    }
+
+    #[test]
+    fn protected_processes_need_an_override() {
+        let source = MockPortSource::new(vec![port(22, u32::MAX, "sshd")]);
+        let mut app = App::new(Config::default(), Arc::new(source));
+        refresh(&mut app);
+        app.table.state.select(Some(0));
+        app.toggle_kill_display();
+        assert!(app.kill_process.protected);
+
+        app.handle_killing_mode_key(KeyEvent::from(KeyCode::Enter));
+        assert!(app.kill_process.countdown_until.is_none());
+        assert!(app.kill_process.display);
+
+        app.handle_killing_mode_key(KeyEvent::from(KeyCode::Char('o')));
+        app.handle_killing_mode_key(KeyEvent::from(KeyCode::Enter));
+        assert!(app.kill_process.countdown_until.is_some());
+    }
+
+    #[test]
+    fn page_keys_follow_the_configured_step() {
+        let ports = (0..40).map(|i| port(1000 + i, 10, "node")).collect();
+        let config = Config {
+            page_step: PageStep::Half,
+            ..Config::default()
+        };
+        let mut app = App::new(config, Arc::new(MockPortSource::new(ports)));
+        refresh(&mut app);
+        app.table.visible_rows = 10;
+        app.table.state.select(Some(0));
 
-        self.table.set_items(self.processes_filtered.clone());
+        app.handle_normal_mode_key(KeyEvent::from(KeyCode::PageDown))
+            .unwrap();
+        assert_eq!(app.table.state.selected(), Some(5));
+        app.handle_normal_mode_key(KeyEvent::new(KeyCode::Char('u'), KeyModifiers::CONTROL))
+            .unwrap();
+        assert_eq!(app.table.state.selected(), Some(0));
     }
 }
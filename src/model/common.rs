@@ -1,46 +1,279 @@
-#[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Serialize)]
+use chrono::{DateTime, Utc};
+use std::{
+    hash::{Hash, Hasher},
+    net::{IpAddr, SocketAddr},
+};
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
 pub enum ProcessPortState {
+    #[default]
     Using,
     Hosting,
 }
 
-#[derive(serde::Serialize, Debug, Clone, Eq, Hash, PartialEq)]
+/// Transport protocol of a socket
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize,
+)]
+pub enum TransportProtocol {
+    #[default]
+    Tcp,
+    Udp,
+}
+
+/// IP version of a socket
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize,
+)]
+pub enum AddressFamily {
+    #[default]
+    V4,
+    V6,
+    /// An IPv4 and an IPv6 row of the same socket merged into one
+    Dual,
+}
+
+impl AddressFamily {
+    /// Whether a socket of this family is reachable over `family`
+    pub fn covers(self, family: AddressFamily) -> bool {
+        self == family || self == AddressFamily::Dual
+    }
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Default)]
 pub struct PortInfo {
     pub id: String,
     pub port: u16,
     pub pid: u32,
+    /// Parent process id, `0` when it could not be determined
+    #[serde(default)]
+    pub ppid: u32,
     pub process_name: String,
+    /// Executable path, empty when it could not be read
     pub process_path: String,
+    /// The process could not be inspected with the current privileges,
+    /// so killing it will likely need elevation
+    #[serde(default)]
+    pub restricted: bool,
+    /// Account name owning the process
+    #[serde(default)]
+    pub user: String,
     pub port_state: ProcessPortState,
+    /// TCP or UDP; snapshots from older versions default to TCP
+    #[serde(default)]
+    pub protocol: TransportProtocol,
+    /// IPv4 or IPv6; snapshots from older versions default to IPv4
+    #[serde(default)]
+    pub address_family: AddressFamily,
+    /// Local address the socket is bound to, unspecified (`0.0.0.0` / `::`)
+    /// for wildcard binds
+    #[serde(default)]
+    pub local_address: Option<IpAddr>,
+    /// Remote endpoint of an established connection
+    pub remote_address: Option<SocketAddr>,
+    /// Reverse DNS name of the remote endpoint, once resolved
+    pub remote_host: Option<String>,
+    /// Full command line, looked up on demand e.g. for the kill confirmation
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cmdline: Option<String>,
+    /// CPU usage since the previous refresh, `100.0` being one full core
+    pub cpu_percent: Option<f32>,
+    /// Resident memory of the owning process
+    pub memory_bytes: Option<u64>,
+    /// When the owning process started
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub started_at: Option<DateTime<Utc>>,
+    /// Inode of the socket, as in `ss -e` or `/proc/net/tcp`. Linux only
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub socket_inode: Option<u64>,
+    /// Inode of the owning process's network namespace, shared by everything in
+    /// the same container. Linux only
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub net_namespace: Option<u64>,
+    /// A Linux kernel thread: its `/proc/<pid>` entry exists but has no executable
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub kernel_thread: bool,
 }
+
 impl PortInfo {
-    pub fn ref_array(&self) -> Vec<String> {
-        vec![
-            self.port.to_string(),
-            self.pid.to_string(),
-            self.process_name.clone(),
-            self.process_path.clone(),
-            format!("{:?}", self.port_state),
-        ]
+    /// Fields that identify a socket and its owner. Usage figures change on
+    /// every refresh and are deliberately left out so diffs stay meaningful.
+    #[allow(clippy::type_complexity)]
+    fn identity(
+        &self,
+    ) -> (
+        &str,
+        u16,
+        u32,
+        u32,
+        &str,
+        &str,
+        &ProcessPortState,
+        TransportProtocol,
+        AddressFamily,
+    ) {
+        (
+            &self.id,
+            self.port,
+            self.pid,
+            self.ppid,
+            &self.process_name,
+            &self.process_path,
+            &self.port_state,
+            self.protocol,
+            self.address_family,
+        )
+    }
+}
+
+impl PortInfo {
+    /// An IPv4 port of `/usr/bin/<name>`, run by alice with parent PID 1. Shared by
+    /// the tests of this crate and the binary, which is why it is not `cfg(test)`.
+    #[doc(hidden)]
+    pub fn fixture(port: u16, pid: u32, name: &str, state: ProcessPortState) -> Self {
+        let address_family = AddressFamily::V4;
+        Self {
+            id: crate::model::os::port_id(pid, port, name, address_family),
+            port,
+            pid,
+            ppid: 1,
+            process_name: name.to_string(),
+            process_path: format!("/usr/bin/{}", name),
+            user: "alice".to_string(),
+            port_state: state,
+            address_family,
+            ..Self::default()
+        }
+    }
+
+    /// Bound to a loopback address, so only reachable from this machine
+    pub fn is_loopback(&self) -> bool {
+        self.local_address.is_some_and(|addr| addr.is_loopback())
+    }
+
+    /// Bound to every interface (`0.0.0.0` / `::`)
+    pub fn is_wildcard(&self) -> bool {
+        self.local_address.is_some_and(|addr| addr.is_unspecified())
+    }
+
+    /// Bound to an address other machines can reach, wildcard binds included
+    pub fn is_external(&self) -> bool {
+        self.local_address.is_some_and(|addr| !addr.is_loopback())
+    }
+
+    /// Whether the process path could be resolved, which fails for most system PIDs on Windows
+    pub fn has_known_path(&self) -> bool {
+        !self.restricted && !self.process_path.is_empty() && self.process_path != "Unknown"
+    }
+
+    /// A kernel or OS pseudo-process rather than a program that can be inspected or
+    /// killed: PID 0 (the System Idle Process on Windows), the Windows System process
+    /// (PID 4) or a Linux kernel thread. A path that merely could not be read, e.g. on
+    /// macOS, does not count.
+    pub fn is_system(&self) -> bool {
+        self.pid == 0 || (cfg!(target_family = "windows") && self.pid == 4) || self.kernel_thread
+    }
+
+    /// Protocol and IP version in the usual short form, e.g. `tcp` or `udp6`
+    pub fn protocol_label(&self) -> String {
+        let protocol = match self.protocol {
+            TransportProtocol::Tcp => "tcp",
+            TransportProtocol::Udp => "udp",
+        };
+        match self.address_family {
+            AddressFamily::V4 => protocol.to_string(),
+            AddressFamily::V6 => format!("{}6", protocol),
+            AddressFamily::Dual => format!("{}46", protocol),
+        }
+    }
+}
+
+impl PartialEq for PortInfo {
+    fn eq(&self, other: &Self) -> bool {
+        self.identity() == other.identity()
+    }
+}
+
+impl Eq for PortInfo {}
+
+impl Hash for PortInfo {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.identity().hash(state);
     }
 }
 
 #[derive(serde::Serialize, Debug)]
 pub struct KillProcessResponse {
     pub success: bool,
+    /// The kill failed because the process belongs to another user or needs elevation
+    pub access_denied: bool,
     pub message: String,
 }
 
-#[derive(serde::Serialize, Debug)]
+#[allow(dead_code)]
+#[derive(serde::Serialize, serde::Deserialize, Debug)]
 pub struct ProcessInfoResponse {
     pub port_state: ProcessPortState,
     pub data: Option<ProcessInfo>,
 }
 
-#[derive(serde::Serialize, Debug, Clone)]
+#[allow(dead_code)]
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
 pub struct ProcessInfo {
     pub pid: u32,
     pub port: u16,
     pub process_name: String,
     pub process_path: String,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn ports_round_trip_through_json() {
+        let ports = vec![
+            PortInfo {
+                id: "20-3000".into(),
+                port: 3000,
+                pid: 20,
+                ppid: 1,
+                process_name: "node".into(),
+                process_path: "/usr/bin/node".into(),
+                user: "alice".into(),
+                port_state: ProcessPortState::Hosting,
+                address_family: AddressFamily::V6,
+                local_address: Some("::1".parse().unwrap()),
+                cmdline: Some("node server.js".into()),
+                cpu_percent: Some(1.5),
+                memory_bytes: Some(4096),
+                started_at: Some(Utc.with_ymd_and_hms(2025, 3, 14, 9, 26, 53).unwrap()),
+                socket_inode: Some(24011),
+                net_namespace: Some(4026531840),
+                ..PortInfo::default()
+            },
+            PortInfo {
+                id: "21-51000".into(),
+                port: 51000,
+                pid: 21,
+                process_name: "curl".into(),
+                restricted: true,
+                protocol: TransportProtocol::Udp,
+                remote_address: Some("93.184.216.34:443".parse().unwrap()),
+                remote_host: Some("example.com".into()),
+                ..PortInfo::default()
+            },
+        ];
+
+        let json = serde_json::to_string(&ports).unwrap();
+        let read: Vec<PortInfo> = serde_json::from_str(&json).unwrap();
+        assert_eq!(read, ports);
+        // Fields outside the identity have to survive too
+        assert_eq!(read[0].cmdline, ports[0].cmdline);
+        assert_eq!(read[0].started_at, ports[0].started_at);
+        assert_eq!(read[0].memory_bytes, Some(4096));
+        assert_eq!(read[0].net_namespace, Some(4026531840));
+        assert_eq!(read[1].remote_host.as_deref(), Some("example.com"));
+    }
+}
@@ -0,0 +1,55 @@
+use crate::model::os;
+
+use std::{
+    collections::HashMap,
+    net::IpAddr,
+    sync::{Arc, Mutex, mpsc},
+    thread,
+};
+
+/// Reverse DNS lookups done on a worker thread, so slow resolvers never block
+/// a refresh. Names show up on the refresh after they have been resolved.
+#[derive(Debug)]
+pub struct HostResolver {
+    /// `None` means the lookup is pending or found no name
+    cache: Arc<Mutex<HashMap<IpAddr, Option<String>>>>,
+    queue: mpsc::Sender<IpAddr>,
+}
+
+impl Default for HostResolver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HostResolver {
+    pub fn new() -> Self {
+        let cache = Arc::new(Mutex::new(HashMap::new()));
+        let (queue, pending) = mpsc::channel::<IpAddr>();
+
+        let worker_cache = Arc::clone(&cache);
+        thread::spawn(move || {
+            for ip in pending {
+                let name = os::reverse_lookup(ip);
+                if let Ok(mut cache) = worker_cache.lock() {
+                    cache.insert(ip, name);
+                }
+            }
+        });
+
+        Self { cache, queue }
+    }
+
+    /// Returns the cached host name, scheduling a lookup the first time an address is seen.
+    pub fn lookup(&self, ip: IpAddr) -> Option<String> {
+        let mut cache = self.cache.lock().ok()?;
+        match cache.get(&ip) {
+            Some(name) => name.clone(),
+            None => {
+                cache.insert(ip, None);
+                let _ = self.queue.send(ip);
+                None
+            }
+        }
+    }
+}
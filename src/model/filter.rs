@@ -0,0 +1,446 @@
+use crate::model::{AddressFamily, PortInfo, ProcessPortState, TransportProtocol};
+
+use std::{borrow::Cow, collections::HashMap};
+
+/// Criteria a port must meet to be listed, shared by the TUI and the one-shot CLI.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PortFilter {
+    /// Whitespace separated terms, each of which must match the PID, port,
+    /// process name, user, protocol or state
+    pub query: String,
+    /// Match `query` letter case exactly
+    pub case_sensitive: bool,
+    /// Only ports owned by this user
+    pub owner: Option<String>,
+    /// Only listening ports
+    pub listening_only: bool,
+    /// Only this exact port
+    pub port: Option<u16>,
+    /// Only this exact PID
+    pub pid: Option<u32>,
+    /// Only IPv4 or only IPv6 sockets
+    pub family: Option<AddressFamily>,
+    /// Protocol, family and state toggles from the quick-filter bar
+    pub quick: QuickFilters,
+    /// Skip ports whose process path is unknown or restricted
+    pub known_paths_only: bool,
+    /// Skip ports bound to loopback, keeping those other machines can reach
+    pub external_only: bool,
+    /// Skip every port of this PID, used to hide the tool's own process
+    pub exclude_pid: Option<u32>,
+    /// Skip kernel and OS pseudo-processes, see [`PortInfo::is_system`]
+    pub hide_system: bool,
+}
+
+/// One on/off chip of the quick-filter bar
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuickFilter {
+    Tcp,
+    Udp,
+    Ipv4,
+    Ipv6,
+    Listening,
+    Established,
+}
+
+impl QuickFilter {
+    /// Every chip, in display order
+    pub const ALL: [QuickFilter; 6] = [
+        QuickFilter::Tcp,
+        QuickFilter::Udp,
+        QuickFilter::Ipv4,
+        QuickFilter::Ipv6,
+        QuickFilter::Listening,
+        QuickFilter::Established,
+    ];
+
+    /// Chip label
+    pub fn label(self) -> &'static str {
+        match self {
+            QuickFilter::Tcp => "TCP",
+            QuickFilter::Udp => "UDP",
+            QuickFilter::Ipv4 => "IPv4",
+            QuickFilter::Ipv6 => "IPv6",
+            QuickFilter::Listening => "Listening",
+            QuickFilter::Established => "Established",
+        }
+    }
+
+    fn bit(self) -> u8 {
+        1 << self as u8
+    }
+
+    /// Whether `port` belongs to the group this chip stands for
+    fn covers(self, port: &PortInfo) -> bool {
+        match self {
+            QuickFilter::Tcp => port.protocol == TransportProtocol::Tcp,
+            QuickFilter::Udp => port.protocol == TransportProtocol::Udp,
+            QuickFilter::Ipv4 => port.address_family.covers(AddressFamily::V4),
+            QuickFilter::Ipv6 => port.address_family.covers(AddressFamily::V6),
+            QuickFilter::Listening => port.port_state == ProcessPortState::Hosting,
+            QuickFilter::Established => port.port_state == ProcessPortState::Using,
+        }
+    }
+}
+
+/// Bit mask of the enabled [`QuickFilter`] chips. A port is listed when the
+/// chips for its protocol, its family and its state are all on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QuickFilters(u8);
+
+impl Default for QuickFilters {
+    fn default() -> Self {
+        Self(QuickFilter::ALL.iter().fold(0, |mask, f| mask | f.bit()))
+    }
+}
+
+impl QuickFilters {
+    /// Whether the chip is on
+    pub fn is_on(self, filter: QuickFilter) -> bool {
+        self.0 & filter.bit() != 0
+    }
+
+    /// Flips the chip on or off
+    pub fn toggle(&mut self, filter: QuickFilter) {
+        self.0 ^= filter.bit();
+    }
+
+    /// Whether every chip is on, so nothing is hidden
+    pub fn is_all(self) -> bool {
+        self == Self::default()
+    }
+
+    fn allows(self, port: &PortInfo) -> bool {
+        QuickFilter::ALL
+            .iter()
+            .all(|&f| self.is_on(f) || !f.covers(port))
+    }
+}
+
+impl PortFilter {
+    /// Sets the free text from what was typed in the search box, pulling out
+    /// `family:v4` / `family:v6` and `port:<number>` tokens into their own criteria.
+    pub fn set_query(&mut self, input: &str) {
+        let mut words = Vec::new();
+        for word in input.split_whitespace() {
+            let lower = word.to_lowercase();
+            if let Some(family) = lower.strip_prefix("family:") {
+                match family {
+                    "v4" | "ipv4" | "4" => self.family = Some(AddressFamily::V4),
+                    "v6" | "ipv6" | "6" => self.family = Some(AddressFamily::V6),
+                    _ => words.push(word),
+                }
+            } else if let Some(port) = lower.strip_prefix("port:") {
+                match port.parse() {
+                    Ok(port) => self.port = Some(port),
+                    Err(_) => words.push(word),
+                }
+            } else {
+                words.push(word);
+            }
+        }
+        self.query = words.join(" ");
+    }
+
+    /// Whether `port` passes every criteria of the filter
+    pub fn matches(&self, port: &PortInfo) -> bool {
+        self.owner.as_ref().is_none_or(|owner| &port.user == owner)
+            && (!self.listening_only || port.port_state == ProcessPortState::Hosting)
+            && self.port.is_none_or(|p| port.port == p)
+            && self.pid.is_none_or(|pid| port.pid == pid)
+            && self.exclude_pid != Some(port.pid)
+            && self
+                .family
+                .is_none_or(|family| port.address_family.covers(family))
+            && self.quick.allows(port)
+            && (!self.known_paths_only || port.has_known_path())
+            && (!self.hide_system || !port.is_system())
+            && (!self.external_only || port.is_external())
+            && self.matches_query(port)
+    }
+
+    /// Every term of the query has to match at least one field
+    fn matches_query(&self, port: &PortInfo) -> bool {
+        let state = match port.port_state {
+            ProcessPortState::Hosting => "listening",
+            ProcessPortState::Using => "established",
+        };
+        let protocol = port.protocol_label();
+
+        self.query.split_whitespace().all(|term| {
+            let q = if self.case_sensitive {
+                Cow::Borrowed(term)
+            } else {
+                Cow::Owned(term.to_lowercase())
+            };
+            let contains = |field: &str| {
+                if self.case_sensitive {
+                    field.contains(q.as_ref())
+                } else {
+                    field.to_lowercase().contains(q.as_ref())
+                }
+            };
+
+            port.pid.to_string().contains(q.as_ref())
+                || port.port.to_string().contains(q.as_ref())
+                || contains(&port.process_name)
+                || contains(&port.user)
+                || contains(&protocol)
+                || contains(state)
+        })
+    }
+
+    /// Keeps the ports passing the filter
+    pub fn apply<'a>(&'a self, ports: &'a [PortInfo]) -> impl Iterator<Item = &'a PortInfo> {
+        ports.iter().filter(|p| self.matches(p))
+    }
+}
+
+/// Collapses the IPv4 and IPv6 rows of one dual-stack socket, e.g. a server listening on
+/// both `0.0.0.0` and `::`, into a single [`AddressFamily::Dual`] row shown as `tcp46`.
+/// Only unconnected sockets of the same process, port, protocol and state merge, and only
+/// when both are equally reachable, so a port exposed on one family still stands out.
+pub fn merge_dual_stack(ports: Vec<PortInfo>) -> Vec<PortInfo> {
+    let mut merged: Vec<PortInfo> = Vec::with_capacity(ports.len());
+    // Rows still waiting for their other family, by socket
+    let mut unpaired: HashMap<_, usize> = HashMap::new();
+    for port in ports {
+        if port.remote_address.is_some() {
+            merged.push(port);
+            continue;
+        }
+        let key = (
+            port.pid,
+            port.port,
+            port.process_name.clone(),
+            port.protocol,
+            port.port_state.clone(),
+            port.is_external(),
+        );
+        match unpaired.get(&key) {
+            Some(&idx) if merged[idx].address_family != port.address_family => {
+                merged[idx].address_family = AddressFamily::Dual;
+                unpaired.remove(&key);
+            }
+            _ => {
+                unpaired.insert(key, merged.len());
+                merged.push(port);
+            }
+        }
+    }
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn query_ignores_case_unless_asked() {
+        let node = PortInfo::fixture(3000, 10, "Node", ProcessPortState::Hosting);
+        let mut filter = PortFilter {
+            query: "node".into(),
+            ..Default::default()
+        };
+        assert!(filter.matches(&node));
+
+        filter.case_sensitive = true;
+        assert!(!filter.matches(&node));
+        filter.query = "Node".into();
+        assert!(filter.matches(&node));
+    }
+
+    #[test]
+    fn exact_port_pid_and_listening() {
+        let ports = [
+            PortInfo::fixture(8080, 10, "nginx", ProcessPortState::Hosting),
+            PortInfo::fixture(18080, 11, "curl", ProcessPortState::Using),
+            PortInfo::fixture(8080, 12, "curl", ProcessPortState::Using),
+        ];
+
+        let filter = PortFilter {
+            port: Some(8080),
+            ..Default::default()
+        };
+        assert_eq!(filter.apply(&ports).count(), 2);
+
+        let filter = PortFilter {
+            port: Some(8080),
+            listening_only: true,
+            ..Default::default()
+        };
+        let pids: Vec<u32> = filter.apply(&ports).map(|p| p.pid).collect();
+        assert_eq!(pids, [10]);
+
+        let filter = PortFilter {
+            pid: Some(11),
+            ..Default::default()
+        };
+        assert_eq!(filter.apply(&ports).next().map(|p| p.port), Some(18080));
+    }
+
+    #[test]
+    fn family_token_filters_by_ip_version() {
+        let mut v6 = PortInfo::fixture(443, 10, "nginx", ProcessPortState::Hosting);
+        v6.address_family = AddressFamily::V6;
+        let ports = [
+            PortInfo::fixture(443, 10, "nginx", ProcessPortState::Hosting),
+            v6,
+        ];
+
+        let mut filter = PortFilter::default();
+        filter.set_query("nginx family:v6");
+        assert_eq!(filter.query, "nginx");
+        let families: Vec<_> = filter.apply(&ports).map(|p| p.address_family).collect();
+        assert_eq!(families, [AddressFamily::V6]);
+    }
+
+    #[test]
+    fn hides_kernel_and_pid_zero_entries() {
+        let mut kernel = PortInfo::fixture(2049, 900, "nfsd", ProcessPortState::Hosting);
+        kernel.process_path.clear();
+        kernel.kernel_thread = true;
+        let mut other_user = PortInfo::fixture(5432, 901, "postgres", ProcessPortState::Hosting);
+        other_user.process_path.clear();
+        other_user.restricted = true;
+        let ports = [
+            PortInfo::fixture(135, 0, "System Idle Process", ProcessPortState::Hosting),
+            kernel,
+            other_user,
+            PortInfo::fixture(80, 10, "nginx", ProcessPortState::Hosting),
+        ];
+
+        let filter = PortFilter {
+            hide_system: true,
+            ..Default::default()
+        };
+        let pids: Vec<u32> = filter.apply(&ports).map(|p| p.pid).collect();
+        assert_eq!(pids, [901, 10]);
+    }
+
+    #[test]
+    fn merges_the_two_families_of_one_socket() {
+        let bound = |family, address: &str| {
+            let mut p = PortInfo::fixture(8080, 10, "node", ProcessPortState::Hosting);
+            p.address_family = family;
+            p.local_address = Some(address.parse().unwrap());
+            p
+        };
+        let ports = vec![
+            bound(AddressFamily::V4, "0.0.0.0"),
+            PortInfo::fixture(22, 5, "sshd", ProcessPortState::Hosting),
+            bound(AddressFamily::V6, "::"),
+        ];
+        let merged = merge_dual_stack(ports);
+        let labels: Vec<_> = merged.iter().map(|p| p.protocol_label()).collect();
+        assert_eq!(labels, ["tcp46", "tcp"]);
+        assert!(merged[0].address_family.covers(AddressFamily::V6));
+
+        // Loopback on one family and exposed on the other stays two rows
+        let ports = vec![
+            bound(AddressFamily::V4, "127.0.0.1"),
+            bound(AddressFamily::V6, "::"),
+        ];
+        assert_eq!(merge_dual_stack(ports).len(), 2);
+    }
+
+    #[test]
+    fn quick_filters_hide_switched_off_groups() {
+        let mut udp = PortInfo::fixture(53, 11, "dnsmasq", ProcessPortState::Hosting);
+        udp.protocol = TransportProtocol::Udp;
+        let ports = [
+            PortInfo::fixture(80, 10, "nginx", ProcessPortState::Hosting),
+            PortInfo::fixture(50000, 12, "curl", ProcessPortState::Using),
+            udp,
+        ];
+
+        let mut filter = PortFilter::default();
+        assert_eq!(filter.apply(&ports).count(), 3);
+
+        filter.quick.toggle(QuickFilter::Udp);
+        filter.quick.toggle(QuickFilter::Established);
+        let pids: Vec<u32> = filter.apply(&ports).map(|p| p.pid).collect();
+        assert_eq!(pids, [10]);
+        assert!(!filter.quick.is_all());
+
+        filter.quick.toggle(QuickFilter::Udp);
+        filter.quick.toggle(QuickFilter::Established);
+        assert!(filter.quick.is_all());
+    }
+
+    #[test]
+    fn every_term_has_to_match() {
+        let ports = [
+            PortInfo::fixture(3000, 10, "node", ProcessPortState::Hosting),
+            PortInfo::fixture(3000, 11, "curl", ProcessPortState::Using),
+            PortInfo::fixture(4000, 12, "node", ProcessPortState::Using),
+        ];
+
+        let mut filter = PortFilter::default();
+        filter.set_query("node 3000");
+        let pids: Vec<u32> = filter.apply(&ports).map(|p| p.pid).collect();
+        assert_eq!(pids, [10]);
+
+        let mut filter = PortFilter::default();
+        filter.set_query("node listen");
+        let pids: Vec<u32> = filter.apply(&ports).map(|p| p.pid).collect();
+        assert_eq!(pids, [10]);
+
+        let mut filter = PortFilter::default();
+        filter.set_query("port:3000 curl");
+        assert_eq!(filter.port, Some(3000));
+        assert_eq!(filter.query, "curl");
+        let pids: Vec<u32> = filter.apply(&ports).map(|p| p.pid).collect();
+        assert_eq!(pids, [11]);
+
+        let filter = PortFilter {
+            exclude_pid: Some(10),
+            ..Default::default()
+        };
+        let pids: Vec<u32> = filter.apply(&ports).map(|p| p.pid).collect();
+        assert_eq!(pids, [11, 12]);
+    }
+
+    #[test]
+    fn known_paths_only_hides_unresolved_processes() {
+        let mut restricted = PortInfo::fixture(135, 4, "svchost", ProcessPortState::Hosting);
+        restricted.restricted = true;
+        let mut unknown = PortInfo::fixture(445, 5, "Unknown", ProcessPortState::Hosting);
+        unknown.process_path = String::new();
+        let ports = [
+            PortInfo::fixture(80, 10, "nginx", ProcessPortState::Hosting),
+            restricted,
+            unknown,
+        ];
+
+        let filter = PortFilter {
+            known_paths_only: true,
+            ..Default::default()
+        };
+        let pids: Vec<u32> = filter.apply(&ports).map(|p| p.pid).collect();
+        assert_eq!(pids, [10]);
+    }
+
+    #[test]
+    fn external_only_hides_loopback_binds() {
+        let bound = |pid, address: &str| {
+            let mut p = PortInfo::fixture(8080, pid, "node", ProcessPortState::Hosting);
+            p.local_address = Some(address.parse().unwrap());
+            p
+        };
+        let ports = [
+            bound(10, "127.0.0.1"),
+            bound(11, "0.0.0.0"),
+            bound(12, "::1"),
+            bound(13, "192.168.1.20"),
+            PortInfo::fixture(8080, 14, "node", ProcessPortState::Hosting),
+        ];
+
+        let filter = PortFilter {
+            external_only: true,
+            ..Default::default()
+        };
+        let pids: Vec<u32> = filter.apply(&ports).map(|p| p.pid).collect();
+        assert_eq!(pids, [11, 13]);
+    }
+}
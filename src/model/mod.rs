@@ -1,20 +1,52 @@
 pub mod common;
 pub use common::{
-    KillProcessResponse, PortInfo, ProcessInfo, ProcessInfoResponse, ProcessPortState,
+    AddressFamily, KillProcessResponse, PortInfo, ProcessInfo, ProcessInfoResponse,
+    ProcessPortState, TransportProtocol,
 };
 
+mod dns;
+pub use dns::HostResolver;
+
+mod filter;
+pub use filter::{PortFilter, QuickFilter, QuickFilters, merge_dual_stack};
+
+mod source;
+pub use source::{MockPortSource, OsPortSource, PortSource};
+
+mod services;
+pub use services::{is_sensitive_service, service_name};
+
+mod usage;
+pub use usage::UsageSampler;
+
 #[cfg(target_family = "unix")]
 mod unix;
 
 #[cfg(target_family = "unix")]
-pub(crate) mod os {
-    pub use super::unix::{fetch_ports, kill_process};
+pub mod os {
+    /// Signal sent by a plain `kill`, asking the process to exit
+    pub const SIGTERM: i32 = libc::SIGTERM;
+
+    pub use super::unix::{
+        current_user, fetch_ports, hostname, kill_process, kill_process_elevated,
+        kill_process_escalate, kill_process_group, kill_process_tree, manual_kill_command, port_id,
+        process_cmdline, process_cpu_time, process_group_id, process_memory_bytes,
+        process_start_time, reverse_lookup, system_cpu_time,
+    };
 }
 
 #[cfg(target_family = "windows")]
 mod windows;
 
 #[cfg(target_family = "windows")]
-pub(crate) mod os {
-    pub use super::windows::{fetch_ports, kill_process};
+pub mod os {
+    /// Only passed through to [`kill_process_group`], which Windows does not support
+    pub const SIGTERM: i32 = 15;
+
+    pub use super::windows::{
+        current_user, fetch_ports, hostname, kill_process, kill_process_elevated,
+        kill_process_escalate, kill_process_group, kill_process_tree, manual_kill_command, port_id,
+        process_cmdline, process_cpu_time, process_group_id, process_memory_bytes,
+        process_start_time, reverse_lookup, system_cpu_time,
+    };
 }
@@ -0,0 +1,101 @@
+use std::{collections::HashMap, fs, sync::OnceLock};
+
+/// Common IANA assignments and widely used defaults, checked before `/etc/services`.
+const WELL_KNOWN_PORTS: &[(u16, &str)] = &[
+    (20, "ftp-data"),
+    (21, "ftp"),
+    (22, "ssh"),
+    (23, "telnet"),
+    (25, "smtp"),
+    (53, "dns"),
+    (67, "dhcp"),
+    (68, "dhcp"),
+    (80, "http"),
+    (110, "pop3"),
+    (123, "ntp"),
+    (137, "netbios"),
+    (138, "netbios"),
+    (139, "netbios"),
+    (143, "imap"),
+    (161, "snmp"),
+    (389, "ldap"),
+    (443, "https"),
+    (445, "smb"),
+    (465, "smtps"),
+    (514, "syslog"),
+    (587, "submission"),
+    (631, "ipp"),
+    (636, "ldaps"),
+    (993, "imaps"),
+    (995, "pop3s"),
+    (1433, "mssql"),
+    (1521, "oracle"),
+    (1883, "mqtt"),
+    (2049, "nfs"),
+    (2375, "docker"),
+    (2376, "docker-tls"),
+    (3306, "mysql"),
+    (3389, "rdp"),
+    (5353, "mdns"),
+    (5432, "postgres"),
+    (5672, "amqp"),
+    (5900, "vnc"),
+    (6379, "redis"),
+    (6443, "kube-api"),
+    (8000, "http-alt"),
+    (8080, "http-alt"),
+    (8443, "https-alt"),
+    (9090, "prometheus"),
+    (9092, "kafka"),
+    (9200, "elasticsearch"),
+    (11211, "memcached"),
+    (27017, "mongodb"),
+];
+
+/// Databases, caches, brokers and remote access services that are rarely meant to be
+/// reachable from other machines.
+const SENSITIVE_PORTS: &[u16] = &[
+    23, 445, 1433, 1521, 2375, 2379, 3306, 3389, 5432, 5672, 5900, 5984, 6379, 9042, 9200, 11211,
+    27017,
+];
+
+/// Whether the port belongs to a service that is risky to expose, e.g. Redis on 6379.
+pub fn is_sensitive_service(port: u16) -> bool {
+    SENSITIVE_PORTS.contains(&port)
+}
+
+/// Returns a short service name for a port, e.g. `https` for 443.
+pub fn service_name(port: u16) -> Option<&'static str> {
+    WELL_KNOWN_PORTS
+        .iter()
+        .find(|(p, _)| *p == port)
+        .map(|(_, name)| *name)
+        .or_else(|| system_services().get(&port).map(String::as_str))
+}
+
+/// Services listed in `/etc/services`, loaded once on first use.
+fn system_services() -> &'static HashMap<u16, String> {
+    static SERVICES: OnceLock<HashMap<u16, String>> = OnceLock::new();
+    SERVICES.get_or_init(|| {
+        fs::read_to_string("/etc/services")
+            .map(|content| parse_services(&content))
+            .unwrap_or_default()
+    })
+}
+
+/// Parses lines such as `http  80/tcp  www  # WorldWideWeb HTTP`.
+fn parse_services(content: &str) -> HashMap<u16, String> {
+    let mut services = HashMap::new();
+    for line in content.lines() {
+        let line = line.split('#').next().unwrap_or_default();
+        let mut fields = line.split_whitespace();
+        let (Some(name), Some(port_proto)) = (fields.next(), fields.next()) else {
+            continue;
+        };
+        let Some(Ok(port)) = port_proto.split('/').next().map(str::parse::<u16>) else {
+            continue;
+        };
+        services.entry(port).or_insert_with(|| name.to_string());
+    }
+    services
+}
@@ -0,0 +1,70 @@
+use crate::model::{PortInfo, os};
+
+use std::{fmt, sync::Mutex};
+
+/// Where the list of ports comes from. The TUI reads the operating system,
+/// tests feed fixed lists through [`MockPortSource`].
+pub trait PortSource: fmt::Debug + Send + Sync {
+    fn fetch(&self) -> Result<Vec<PortInfo>, String>;
+
+    /// Whether the ports belong to this machine right now. A saved snapshot is
+    /// not polled, sampled for usage or killed from.
+    fn is_live(&self) -> bool {
+        true
+    }
+}
+
+/// Ports of the running system
+#[derive(Debug, Default)]
+pub struct OsPortSource;
+
+impl PortSource for OsPortSource {
+    fn fetch(&self) -> Result<Vec<PortInfo>, String> {
+        os::fetch_ports()
+    }
+}
+
+/// Returns the given lists one fetch after another, repeating the last one
+#[derive(Debug)]
+pub struct MockPortSource {
+    batches: Mutex<Vec<Result<Vec<PortInfo>, String>>>,
+}
+
+impl MockPortSource {
+    /// Always returns `ports`
+    pub fn new(ports: Vec<PortInfo>) -> Self {
+        Self::sequence(vec![Ok(ports)])
+    }
+
+    /// Returns each entry of `batches` in turn
+    pub fn sequence(mut batches: Vec<Result<Vec<PortInfo>, String>>) -> Self {
+        batches.reverse();
+        Self {
+            batches: Mutex::new(batches),
+        }
+    }
+}
+
+impl PortSource for MockPortSource {
+    fn fetch(&self) -> Result<Vec<PortInfo>, String> {
+        let mut batches = self.batches.lock().map_err(|e| e.to_string())?;
+        if batches.len() > 1 {
+            batches.pop().unwrap_or_else(|| Ok(Vec::new()))
+        } else {
+            batches.last().cloned().unwrap_or_else(|| Ok(Vec::new()))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mock_repeats_the_last_batch() {
+        let source = MockPortSource::sequence(vec![Err("boom".into()), Ok(Vec::new())]);
+        assert_eq!(source.fetch().err(), Some("boom".to_string()));
+        assert_eq!(source.fetch().map(|p| p.len()), Ok(0));
+        assert_eq!(source.fetch().map(|p| p.len()), Ok(0));
+    }
+}
@@ -1,20 +1,35 @@
 use crate::model::{
-    KillProcessResponse, PortInfo, ProcessInfo, ProcessInfoResponse, ProcessPortState,
+    AddressFamily, KillProcessResponse, PortInfo, ProcessInfo, ProcessInfoResponse,
+    ProcessPortState, TransportProtocol,
 };
 
 use std::{
-    collections::{HashSet, hash_map::DefaultHasher},
+    collections::{HashMap, HashSet, hash_map::DefaultHasher},
+    ffi::CStr,
     fs,
     hash::{Hash, Hasher},
+    io, mem,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
     path::PathBuf,
     process::Command,
+    ptr,
+    sync::{LazyLock, Mutex},
+    thread,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
 };
 
+/// Shown instead of the raw spawn error on systems without `lsof`, e.g. minimal containers.
+const LSOF_MISSING: &str =
+    "lsof not found; install it with your package manager (e.g. `apt install lsof`) to list ports";
+
 pub fn fetch_ports() -> Result<Vec<PortInfo>, String> {
     let output = Command::new("lsof")
         .args(["-i", "-P", "-n"])
         .output()
-        .map_err(|e| format!("Failed to execute lsof: {}", e))?;
+        .map_err(|e| match e.kind() {
+            io::ErrorKind::NotFound => LSOF_MISSING.to_string(),
+            _ => format!("Failed to execute lsof: {}", e),
+        })?;
 
     if !output.status.success() {
         return if output.status.code() == Some(1) {
@@ -29,50 +44,235 @@ pub fn fetch_ports() -> Result<Vec<PortInfo>, String> {
     }
 
     let stdout = String::from_utf8_lossy(&output.stdout);
-    parse_lsof_output(&stdout)
+    let mut paths = PATH_CACHE.lock().unwrap_or_else(|e| e.into_inner());
+    let ports = parse_lsof_output(&stdout, &mut paths)?;
+
+    // Forget processes that are gone so a reused PID is looked up again
+    let live: HashSet<u32> = ports.iter().map(|p| p.pid).collect();
+    paths.retain(|pid, _| live.contains(pid));
+
+    Ok(ports)
+}
+
+/// Executable of a process as far as it could be resolved
+#[derive(Debug, Clone, Default, PartialEq)]
+struct ProcessPath {
+    /// Empty when it could not be read
+    path: String,
+    /// Reading it was not permitted
+    restricted: bool,
+    /// There is no executable because the process is a Linux kernel thread
+    kernel_thread: bool,
 }
 
-fn parse_lsof_output(output: &str) -> Result<Vec<PortInfo>, String> {
+impl ProcessPath {
+    fn resolve(pid: u32) -> Self {
+        match get_process_path(pid) {
+            Ok(path) => Self {
+                path,
+                ..Default::default()
+            },
+            Err(err) => Self {
+                path: String::new(),
+                restricted: err.kind() == io::ErrorKind::PermissionDenied,
+                kernel_thread: is_kernel_thread(cfg!(target_os = "linux"), pid, &err),
+            },
+        }
+    }
+}
+
+/// Executable paths by PID, kept across refreshes since a running process never
+/// changes its binary.
+type PathCache = HashMap<u32, ProcessPath>;
+
+static PATH_CACHE: LazyLock<Mutex<PathCache>> = LazyLock::new(Default::default);
+
+/// Column layout of `lsof` output, read from its header line.
+struct LsofColumns {
+    /// Number of columns before NAME
+    leading: usize,
+    pid: usize,
+    user: usize,
+    /// TYPE, `IPv4` or `IPv6`
+    kind: usize,
+    /// DEVICE, the socket inode on Linux
+    device: Option<usize>,
+    /// Byte offset where the NAME column starts
+    name_start: usize,
+}
+
+impl LsofColumns {
+    fn from_header(header: &str) -> Option<Self> {
+        let titles: Vec<&str> = header.split_whitespace().collect();
+        let leading = titles.iter().position(|&t| t == "NAME")?;
+        Some(Self {
+            leading,
+            pid: titles.iter().position(|&t| t == "PID")?,
+            user: titles.iter().position(|&t| t == "USER")?,
+            kind: titles.iter().position(|&t| t == "TYPE")?,
+            device: titles.iter().position(|&t| t == "DEVICE"),
+            name_start: header.find("NAME")?,
+        })
+    }
+
+    /// Splits a row into the values we use. NAME is the first field starting at
+    /// or after the header's NAME offset (wider values only ever push it right);
+    /// any extra fields before it belong to COMMAND.
+    fn split<'a>(&self, line: &'a str) -> Option<LsofRow<'a>> {
+        let fields: Vec<(usize, &str)> = line
+            .split_whitespace()
+            .map(|field| (field.as_ptr() as usize - line.as_ptr() as usize, field))
+            .collect();
+        let name_idx = fields
+            .iter()
+            .position(|&(offset, _)| offset >= self.name_start)?;
+        let (prefix, _) = fields.split_at(name_idx);
+
+        // Blank columns (e.g. an empty SIZE/OFF) make the prefix shorter, never longer
+        let command_len = (prefix.len() + 1).saturating_sub(self.leading).max(1);
+        let shift = command_len - 1;
+        let command = prefix
+            .get(..command_len)?
+            .iter()
+            .map(|&(_, field)| field)
+            .collect::<Vec<_>>()
+            .join(" ");
+        let pid = prefix.get(self.pid + shift)?.1;
+        let user = prefix.get(self.user + shift)?.1;
+        let kind = prefix.get(self.kind + shift)?.1;
+        let device = self
+            .device
+            .and_then(|device| prefix.get(device + shift))
+            .map(|&(_, field)| field);
+        // NODE is the protocol, always right before NAME
+        let node = prefix.last()?.1;
+        let name = &line[fields[name_idx].0..];
+
+        Some(LsofRow {
+            command,
+            pid,
+            user,
+            kind,
+            device,
+            node,
+            name,
+        })
+    }
+}
+
+/// Values of one `lsof` row
+struct LsofRow<'a> {
+    command: String,
+    pid: &'a str,
+    user: &'a str,
+    kind: &'a str,
+    device: Option<&'a str>,
+    node: &'a str,
+    name: &'a str,
+}
+
+fn parse_lsof_output(output: &str, paths: &mut PathCache) -> Result<Vec<PortInfo>, String> {
+    let mut lines = output.lines();
+    let Some(header) = lines.next() else {
+        return Ok(Vec::new());
+    };
+    let columns = LsofColumns::from_header(header)
+        .ok_or_else(|| format!("Unexpected lsof header: {}", header))?;
+
     let mut seen = HashSet::new();
     let mut ports = Vec::new();
+    // Parent and network namespace by PID, looked up once per refresh rather than
+    // for every socket; unlike the path, the parent changes when a process is reparented
+    let mut lineage = HashMap::new();
 
-    for line in output.lines().skip(1) {
-        let parts: Vec<&str> = line.split_whitespace().collect();
-        if parts.len() < 9 {
+    for line in lines {
+        let Some(LsofRow {
+            command: process_name,
+            pid,
+            user,
+            kind,
+            device,
+            node,
+            name,
+        }) = columns.split(line)
+        else {
             continue;
-        }
+        };
+
+        let protocol = if node.eq_ignore_ascii_case("UDP") {
+            TransportProtocol::Udp
+        } else {
+            TransportProtocol::Tcp
+        };
+
+        let address_family = if kind.eq_ignore_ascii_case("IPv6") {
+            AddressFamily::V6
+        } else {
+            AddressFamily::V4
+        };
 
-        let pid: u32 = match parts[1].parse() {
+        let pid: u32 = match pid.parse() {
             Ok(pid) => pid,
             Err(_) => continue,
         };
 
-        let port: u16 = parts[8]
-            .split(':')
-            .last()
-            .unwrap_or("0")
-            .parse::<u16>()
-            .unwrap_or(0);
+        // NAME is the address, optionally followed by the state, e.g. `*:80 (LISTEN)`
+        let (address, state) = match name.split_once(char::is_whitespace) {
+            Some((address, state)) => (address, state.trim()),
+            None => (name, ""),
+        };
 
-        let process_path = match get_process_path(pid) {
-            Ok(path) => path,
-            Err(err) => err,
+        // Established connections are printed as `local->remote`
+        let (local, remote) = match address.split_once("->") {
+            Some((local, remote)) => (local, remote.parse::<SocketAddr>().ok()),
+            None => (address, None),
         };
 
-        let port_state = if parts.get(9).map_or(false, |state| state.contains("LISTEN")) {
+        let port = parse_port(local).unwrap_or(0);
+        let local_address = parse_host(local, address_family);
+
+        let ProcessPath {
+            path: process_path,
+            restricted,
+            kernel_thread,
+        } = paths
+            .entry(pid)
+            .or_insert_with(|| ProcessPath::resolve(pid))
+            .clone();
+
+        let port_state = if state == "(LISTEN)" {
             ProcessPortState::Hosting
         } else {
             ProcessPortState::Using
         };
 
-        if seen.insert((pid, port)) {
+        if seen.insert((pid, port, protocol, address_family)) {
+            let (ppid, net_namespace) = *lineage
+                .entry(pid)
+                .or_insert_with(|| (get_parent_pid(pid).unwrap_or(0), process_net_namespace(pid)));
             ports.push(PortInfo {
-                id: generate_unique_id(pid, port, parts[0]),
+                id: generate_unique_id(pid, port, &process_name, address_family),
                 pid,
-                process_name: parts[0].to_string(),
+                ppid,
+                process_name,
                 port,
                 process_path,
+                restricted,
+                user: user.to_string(),
                 port_state,
+                protocol,
+                address_family,
+                local_address,
+                remote_address: remote,
+                remote_host: None,
+                cmdline: None,
+                cpu_percent: None,
+                memory_bytes: None,
+                started_at: None,
+                // Linux prints the inode here, macOS a kernel address like `0x1234`
+                socket_inode: device.and_then(|device| device.parse().ok()),
+                net_namespace,
+                kernel_thread,
             });
         }
     }
@@ -80,28 +280,403 @@ fn parse_lsof_output(output: &str) -> Result<Vec<PortInfo>, String> {
     Ok(ports)
 }
 
-fn generate_unique_id(pid: u32, port: u16, process_name: &str) -> String {
+/// Extracts the port from an lsof address such as `*:80`, `127.0.0.1:80` or `[::1]:80`.
+/// IPv6 hosts are bracketed, so only a `:port` after the closing bracket counts.
+fn parse_port(address: &str) -> Option<u16> {
+    let port = match address.strip_prefix('[') {
+        Some(rest) => rest.split_once("]:")?.1,
+        None => address.rsplit_once(':')?.1,
+    };
+    port.parse().ok()
+}
+
+/// Extracts the bound address from an lsof address, `*` being the wildcard of `family`.
+fn parse_host(address: &str, family: AddressFamily) -> Option<IpAddr> {
+    let host = match address.strip_prefix('[') {
+        Some(rest) => rest.split_once(']')?.0,
+        None => address.rsplit_once(':')?.0,
+    };
+    if host == "*" {
+        return Some(match family {
+            AddressFamily::V4 => IpAddr::V4(Ipv4Addr::UNSPECIFIED),
+            AddressFamily::V6 | AddressFamily::Dual => IpAddr::V6(Ipv6Addr::UNSPECIFIED),
+        });
+    }
+    // Link-local addresses carry a zone suffix such as `fe80::1%eth0`
+    let host = host.split_once('%').map_or(host, |(addr, _)| addr);
+    host.parse().ok()
+}
+
+/// Id of a socket as [`fetch_ports`] computes it, for rebuilding entries read from files.
+pub fn port_id(pid: u32, port: u16, process_name: &str, address_family: AddressFamily) -> String {
+    generate_unique_id(pid, port, process_name, address_family)
+}
+
+fn generate_unique_id(
+    pid: u32,
+    port: u16,
+    process_name: &str,
+    address_family: AddressFamily,
+) -> String {
     let mut hasher = DefaultHasher::new();
     pid.hash(&mut hasher);
     port.hash(&mut hasher);
     process_name.hash(&mut hasher);
+    // IPv4 ids stay the same as before the family was tracked
+    if address_family == AddressFamily::V6 {
+        address_family.hash(&mut hasher);
+    }
     format!("{:x}", hasher.finish())
 }
 
-fn get_process_path(pid: u32) -> Result<String, String> {
-    let exe_path = format!("/proc/{}/exe", pid);
-    match std::fs::read_link(&exe_path) {
-        Ok(path) => Ok(path.to_string_lossy().to_string()),
-        Err(err) => {
-            if err.kind() == std::io::ErrorKind::PermissionDenied {
-                Err("Permission Denied".to_string())
-            } else if err.kind() == std::io::ErrorKind::NotFound {
-                Err("Process not found".to_string())
-            } else {
-                Err("Unknown error".to_string())
+#[cfg(not(target_os = "macos"))]
+fn get_process_path(pid: u32) -> io::Result<String> {
+    let path = fs::read_link(format!("/proc/{}/exe", pid))?;
+    Ok(path.to_string_lossy().to_string())
+}
+
+/// macOS has no `/proc`, the kernel reports the executable through `proc_pidpath`.
+#[cfg(target_os = "macos")]
+fn get_process_path(pid: u32) -> io::Result<String> {
+    let mut buf = vec![0u8; libc::PROC_PIDPATHINFO_MAXSIZE as usize];
+    let len = unsafe {
+        libc::proc_pidpath(
+            pid as libc::c_int,
+            buf.as_mut_ptr() as *mut libc::c_void,
+            buf.len() as u32,
+        )
+    };
+    if len <= 0 {
+        return Err(io::Error::last_os_error());
+    }
+    buf.truncate(len as usize);
+    Ok(String::from_utf8_lossy(&buf).into_owned())
+}
+
+/// Whether an unreadable executable means a kernel thread: only on Linux, where
+/// `/proc/<pid>/exe` is missing for kernel threads while `/proc/<pid>` exists.
+fn is_kernel_thread(on_linux: bool, pid: u32, err: &io::Error) -> bool {
+    on_linux
+        && err.kind() == io::ErrorKind::NotFound
+        && fs::metadata(format!("/proc/{}", pid)).is_ok()
+}
+
+/// Inode of the network namespace a process runs in, from the `net:[4026531840]`
+/// link at `/proc/<pid>/ns/net`. Processes in the same container share it.
+pub fn process_net_namespace(pid: u32) -> Option<u64> {
+    let link = fs::read_link(format!("/proc/{}/ns/net", pid)).ok()?;
+    parse_namespace_link(&link.to_string_lossy())
+}
+
+fn parse_namespace_link(link: &str) -> Option<u64> {
+    link.strip_prefix("net:[")?.strip_suffix(']')?.parse().ok()
+}
+
+/// Full command line from `/proc/<pid>/cmdline`, arguments joined by spaces.
+/// `None` for kernel threads and processes that cannot be inspected.
+pub fn process_cmdline(pid: u32) -> Option<String> {
+    let raw = fs::read(format!("/proc/{}/cmdline", pid)).ok()?;
+    let args: Vec<_> = raw
+        .split(|&b| b == 0)
+        .filter(|arg| !arg.is_empty())
+        .map(String::from_utf8_lossy)
+        .collect();
+    (!args.is_empty()).then(|| args.join(" "))
+}
+
+/// Reads the fields of `/proc/<pid>/stat` that follow the command name, so
+/// index 0 is field 3 (`state`) as numbered in proc(5).
+fn read_stat_fields(pid: u32) -> Option<Vec<String>> {
+    let stat = fs::read_to_string(format!("/proc/{}/stat", pid)).ok()?;
+    // The command name is wrapped in parentheses and may itself contain spaces
+    // or parentheses, so start parsing after the last closing one.
+    let after_comm = &stat[stat.rfind(')')? + 1..];
+    Some(after_comm.split_whitespace().map(String::from).collect())
+}
+
+/// Reads the parent PID from field 4 of `/proc/<pid>/stat`.
+fn get_parent_pid(pid: u32) -> Option<u32> {
+    read_stat_fields(pid)?.get(1)?.parse().ok()
+}
+
+/// Process group of a process, from field 5 of `/proc/<pid>/stat` where
+/// available and `getpgid` elsewhere, e.g. on macOS.
+pub fn process_group_id(pid: u32) -> Option<u32> {
+    read_stat_fields(pid)
+        .and_then(|fields| fields.get(2)?.parse().ok())
+        .or_else(|| {
+            let pgid = unsafe { libc::getpgid(pid as libc::pid_t) };
+            (pgid > 0).then_some(pgid as u32)
+        })
+}
+
+/// CPU time spent by a process (`utime` + `stime`), in clock ticks.
+pub fn process_cpu_time(pid: u32) -> Option<u64> {
+    let fields = read_stat_fields(pid)?;
+    let utime: u64 = fields.get(11)?.parse().ok()?;
+    let stime: u64 = fields.get(12)?.parse().ok()?;
+    Some(utime + stime)
+}
+
+/// When a process started: `starttime` (field 22 of `/proc/<pid>/stat`, in
+/// clock ticks since boot) added to the boot time from `/proc/stat`.
+pub fn process_start_time(pid: u32) -> Option<SystemTime> {
+    let ticks: u64 = read_stat_fields(pid)?.get(19)?.parse().ok()?;
+    let stat = fs::read_to_string("/proc/stat").ok()?;
+    let boot: u64 = stat
+        .lines()
+        .find_map(|line| line.strip_prefix("btime "))?
+        .trim()
+        .parse()
+        .ok()?;
+    let ticks_per_sec = match unsafe { libc::sysconf(libc::_SC_CLK_TCK) } {
+        n if n > 0 => n as u64,
+        _ => 100,
+    };
+    let since_boot = Duration::from_millis(ticks * 1000 / ticks_per_sec);
+    Some(UNIX_EPOCH + Duration::from_secs(boot) + since_boot)
+}
+
+/// CPU time spent by all cores together, in clock ticks.
+pub fn system_cpu_time() -> Option<u64> {
+    let stat = fs::read_to_string("/proc/stat").ok()?;
+    let line = stat.lines().next()?;
+    if !line.starts_with("cpu ") {
+        return None;
+    }
+    Some(
+        line.split_whitespace()
+            .skip(1)
+            .filter_map(|v| v.parse::<u64>().ok())
+            .sum(),
+    )
+}
+
+/// Resident memory of a process, read from `VmRSS` in `/proc/<pid>/status`.
+pub fn process_memory_bytes(pid: u32) -> Option<u64> {
+    let status = fs::read_to_string(format!("/proc/{}/status", pid)).ok()?;
+    let line = status.lines().find(|l| l.starts_with("VmRSS:"))?;
+    let kib: u64 = line.split_whitespace().nth(1)?.parse().ok()?;
+    Some(kib * 1024)
+}
+
+/// Collects all descendants of `root`, ordered so that children come before their parents.
+fn collect_descendants(root: u32) -> Vec<u32> {
+    let mut children: HashMap<u32, Vec<u32>> = HashMap::new();
+    if let Ok(entries) = fs::read_dir("/proc") {
+        for entry in entries.flatten() {
+            let Some(pid) = entry
+                .file_name()
+                .to_str()
+                .and_then(|s| s.parse::<u32>().ok())
+            else {
+                continue;
+            };
+            if let Some(ppid) = get_parent_pid(pid) {
+                children.entry(ppid).or_default().push(pid);
             }
         }
     }
+
+    let mut ordered = Vec::new();
+    let mut stack = vec![root];
+    while let Some(pid) = stack.pop() {
+        if let Some(kids) = children.get(&pid) {
+            for &kid in kids {
+                ordered.push(kid);
+                stack.push(kid);
+            }
+        }
+    }
+    ordered.reverse();
+    ordered
+}
+
+/// Kills a process together with every process it spawned, deepest children first.
+pub fn kill_process_tree(pid: u32) -> KillProcessResponse {
+    let descendants = collect_descendants(pid);
+    let killed_children = descendants
+        .iter()
+        .filter(|&&child| kill_process(child).success)
+        .count();
+
+    let response = kill_process(pid);
+    if !response.success {
+        return response;
+    }
+
+    KillProcessResponse {
+        success: true,
+        access_denied: false,
+        message: format!(
+            "Successfully killed process with PID {} and {} of {} child processes",
+            pid,
+            killed_children,
+            descendants.len()
+        ),
+    }
+}
+
+/// Retries a kill with elevated privileges: `sudo -n` first for cached or
+/// passwordless credentials, then the desktop's graphical prompt.
+pub fn kill_process_elevated(pid: u32, tree: bool) -> KillProcessResponse {
+    // Children first so the parent cannot respawn them
+    let mut pids = if tree {
+        collect_descendants(pid)
+    } else {
+        Vec::new()
+    };
+    pids.push(pid);
+    let pids: Vec<String> = pids.iter().map(u32::to_string).collect();
+
+    let mut last_error = String::from("no elevation helper available");
+    for mut command in elevated_kill_commands(&pids) {
+        match command.output() {
+            Ok(output) if output.status.success() => {
+                return KillProcessResponse {
+                    success: true,
+                    access_denied: false,
+                    message: format!(
+                        "Successfully killed process with PID {} as administrator",
+                        pid
+                    ),
+                };
+            }
+            Ok(output) => {
+                last_error = String::from_utf8_lossy(&output.stderr).trim().to_string();
+            }
+            Err(e) => last_error = e.to_string(),
+        }
+    }
+
+    KillProcessResponse {
+        success: false,
+        access_denied: true,
+        message: format!(
+            "Failed to kill process {} as administrator: {}",
+            pid, last_error
+        ),
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn elevated_kill_commands(pids: &[String]) -> Vec<Command> {
+    let mut sudo = Command::new("sudo");
+    sudo.args(["-n", "kill"]).args(pids);
+
+    let script = format!(
+        "do shell script \"kill {}\" with administrator privileges",
+        pids.join(" ")
+    );
+    let mut osascript = Command::new("osascript");
+    osascript.args(["-e", &script]);
+
+    vec![sudo, osascript]
+}
+
+#[cfg(not(target_os = "macos"))]
+fn elevated_kill_commands(pids: &[String]) -> Vec<Command> {
+    let mut sudo = Command::new("sudo");
+    sudo.args(["-n", "kill"]).args(pids);
+
+    let mut pkexec = Command::new("pkexec");
+    pkexec.arg("kill").args(pids);
+
+    vec![sudo, pkexec]
+}
+
+/// Buffer size for host names returned by `getnameinfo`.
+const NI_MAXHOST: usize = 1025;
+
+/// Resolves the host name of an address via `getnameinfo`.
+pub fn reverse_lookup(ip: IpAddr) -> Option<String> {
+    let mut host = [0 as libc::c_char; NI_MAXHOST];
+
+    let result = unsafe {
+        match ip {
+            IpAddr::V4(v4) => {
+                let mut addr: libc::sockaddr_in = mem::zeroed();
+                addr.sin_family = libc::AF_INET as libc::sa_family_t;
+                addr.sin_addr.s_addr = u32::from_ne_bytes(v4.octets());
+                #[cfg(target_os = "macos")]
+                {
+                    addr.sin_len = mem::size_of::<libc::sockaddr_in>() as u8;
+                }
+                libc::getnameinfo(
+                    &addr as *const _ as *const libc::sockaddr,
+                    mem::size_of::<libc::sockaddr_in>() as libc::socklen_t,
+                    host.as_mut_ptr(),
+                    host.len() as libc::socklen_t,
+                    ptr::null_mut(),
+                    0,
+                    libc::NI_NAMEREQD,
+                )
+            }
+            IpAddr::V6(v6) => {
+                let mut addr: libc::sockaddr_in6 = mem::zeroed();
+                addr.sin6_family = libc::AF_INET6 as libc::sa_family_t;
+                addr.sin6_addr.s6_addr = v6.octets();
+                #[cfg(target_os = "macos")]
+                {
+                    addr.sin6_len = mem::size_of::<libc::sockaddr_in6>() as u8;
+                }
+                libc::getnameinfo(
+                    &addr as *const _ as *const libc::sockaddr,
+                    mem::size_of::<libc::sockaddr_in6>() as libc::socklen_t,
+                    host.as_mut_ptr(),
+                    host.len() as libc::socklen_t,
+                    ptr::null_mut(),
+                    0,
+                    libc::NI_NAMEREQD,
+                )
+            }
+        }
+    };
+
+    if result != 0 {
+        return None;
+    }
+
+    let name = unsafe { CStr::from_ptr(host.as_ptr()) };
+    Some(name.to_string_lossy().into_owned())
+}
+
+/// Login name of the effective user, as `lsof` prints it in the USER column.
+pub fn current_user() -> Option<String> {
+    let mut passwd: libc::passwd = unsafe { mem::zeroed() };
+    let mut buffer = [0 as libc::c_char; 1024];
+    let mut result: *mut libc::passwd = ptr::null_mut();
+
+    let status = unsafe {
+        libc::getpwuid_r(
+            libc::geteuid(),
+            &mut passwd,
+            buffer.as_mut_ptr(),
+            buffer.len(),
+            &mut result,
+        )
+    };
+    if status != 0 || result.is_null() {
+        // lsof falls back to the numeric id for users without a name
+        return Some(unsafe { libc::geteuid() }.to_string());
+    }
+
+    let name = unsafe { CStr::from_ptr(passwd.pw_name) };
+    Some(name.to_string_lossy().into_owned())
+}
+
+/// Name of this machine via `gethostname`.
+pub fn hostname() -> Option<String> {
+    let mut name = [0 as libc::c_char; 256];
+    let result = unsafe { libc::gethostname(name.as_mut_ptr(), name.len()) };
+    if result != 0 {
+        return None;
+    }
+    // Truncated names are not guaranteed to be null terminated
+    name[name.len() - 1] = 0;
+
+    let name = unsafe { CStr::from_ptr(name.as_ptr()) };
+    Some(name.to_string_lossy().into_owned())
 }
 
 pub fn kill_process(pid: u32) -> KillProcessResponse {
@@ -110,6 +685,7 @@ pub fn kill_process(pid: u32) -> KillProcessResponse {
     match output {
         Ok(output) if output.status.success() => KillProcessResponse {
             success: true,
+            access_denied: false,
             message: format!("Successfully killed process with PID {}", pid),
         },
         Ok(output) => {
@@ -117,6 +693,7 @@ pub fn kill_process(pid: u32) -> KillProcessResponse {
             let exit_code = output.status.code().unwrap_or(-1);
             KillProcessResponse {
                 success: false,
+                access_denied: stderr.contains("Operation not permitted"),
                 message: format!(
                     "Failed to kill process {} (Exit code: {}): {}",
                     pid,
@@ -127,11 +704,98 @@ pub fn kill_process(pid: u32) -> KillProcessResponse {
         }
         Err(e) => KillProcessResponse {
             success: false,
+            access_denied: false,
             message: format!("Failed to execute kill command: {}", e),
         },
     }
 }
 
+/// A command to kill the process by hand with root privileges, e.g. from another shell.
+pub fn manual_kill_command(pid: u32) -> String {
+    format!("sudo kill -9 {}", pid)
+}
+
+/// Sends SIGTERM and waits up to `timeout` for the process to exit, following
+/// up with SIGKILL only when it is still around, so well-behaved processes get
+/// to shut down cleanly.
+pub fn kill_process_escalate(pid: u32, timeout: Duration) -> KillProcessResponse {
+    let target = pid as libc::pid_t;
+    if unsafe { libc::kill(target, libc::SIGTERM) } != 0 {
+        let err = io::Error::last_os_error();
+        return KillProcessResponse {
+            success: false,
+            access_denied: err.kind() == io::ErrorKind::PermissionDenied,
+            message: format!("Failed to kill process {}: {}", pid, err),
+        };
+    }
+
+    // Signal 0 only checks whether the PID still exists
+    let alive = || unsafe { libc::kill(target, 0) } == 0;
+    let started = Instant::now();
+    while started.elapsed() < timeout {
+        if !alive() {
+            return KillProcessResponse {
+                success: true,
+                access_denied: false,
+                message: format!("Process {} exited after SIGTERM", pid),
+            };
+        }
+        thread::sleep(Duration::from_millis(50));
+    }
+
+    if unsafe { libc::kill(target, libc::SIGKILL) } != 0 && alive() {
+        return KillProcessResponse {
+            success: false,
+            access_denied: false,
+            message: format!(
+                "Process {} ignored SIGTERM and SIGKILL failed: {}",
+                pid,
+                io::Error::last_os_error()
+            ),
+        };
+    }
+
+    KillProcessResponse {
+        success: true,
+        access_denied: false,
+        message: format!(
+            "Process {} ignored SIGTERM for {} ms and was killed with SIGKILL",
+            pid,
+            timeout.as_millis()
+        ),
+    }
+}
+
+/// Sends `signal` to every process in the group, like `kill -<signal> -<pgid>`.
+/// Refuses init's group and the one this program runs in, which a shell may
+/// share with servers started from the same terminal.
+pub fn kill_process_group(pgid: u32, signal: i32) -> KillProcessResponse {
+    let own_group = unsafe { libc::getpgrp() } as u32;
+    if pgid <= 1 || pgid == own_group {
+        return KillProcessResponse {
+            success: false,
+            access_denied: false,
+            message: format!("Refusing to kill process group {}", pgid),
+        };
+    }
+
+    if unsafe { libc::kill(-(pgid as libc::pid_t), signal) } != 0 {
+        let err = io::Error::last_os_error();
+        return KillProcessResponse {
+            success: false,
+            access_denied: err.kind() == io::ErrorKind::PermissionDenied,
+            message: format!("Failed to kill process group {}: {}", pgid, err),
+        };
+    }
+
+    KillProcessResponse {
+        success: true,
+        access_denied: false,
+        message: format!("Successfully killed process group {}", pgid),
+    }
+}
+
+#[allow(dead_code)]
 pub fn get_processes_using_port(port: u16, item_pid: u32) -> Result<ProcessInfoResponse, String> {
     let output = Command::new("lsof")
         .arg("-i")
@@ -175,7 +839,12 @@ pub fn get_processes_using_port(port: u16, item_pid: u32) -> Result<ProcessInfoR
             continue;
         }
 
-        let parsed_port: u16 = match address_port.split(':').last().unwrap_or_default().parse() {
+        let parsed_port: u16 = match address_port
+            .split(':')
+            .next_back()
+            .unwrap_or_default()
+            .parse()
+        {
             Ok(port) => port,
             Err(_) => continue,
         };
@@ -202,6 +871,7 @@ pub fn get_processes_using_port(port: u16, item_pid: u32) -> Result<ProcessInfoR
     Err(format!("No processes found listening on port {}", port))
 }
 
+#[allow(dead_code)]
 fn get_process_info(pid: u32, port: u16) -> Option<ProcessInfo> {
     let proc_path = PathBuf::from(format!("/proc/{}/", pid));
 
@@ -222,3 +892,237 @@ fn get_process_info(pid: u32, port: u16) -> Option<ProcessInfo> {
         process_path,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HEADER: &str = "COMMAND     PID   USER   FD   TYPE DEVICE SIZE/OFF NODE NAME";
+
+    #[test]
+    fn parses_ipv4_and_wildcard_ports() {
+        assert_eq!(parse_port("127.0.0.1:8080"), Some(8080));
+        assert_eq!(parse_port("*:53"), Some(53));
+    }
+
+    #[test]
+    fn parses_bracketed_ipv6_ports() {
+        assert_eq!(parse_port("[::1]:8080"), Some(8080));
+        assert_eq!(parse_port("[fe80::1%lo0]:5353"), Some(5353));
+        assert_eq!(parse_port("[::]:443"), Some(443));
+        assert_eq!(parse_port("[::1]"), None);
+    }
+
+    #[test]
+    fn parses_bind_addresses() {
+        let host = |address| parse_host(address, AddressFamily::V4);
+        assert_eq!(host("127.0.0.1:8080"), Some("127.0.0.1".parse().unwrap()));
+        assert_eq!(host("*:53"), Some("0.0.0.0".parse().unwrap()));
+        assert_eq!(
+            parse_host("*:53", AddressFamily::V6),
+            Some("::".parse().unwrap())
+        );
+        assert_eq!(host("[fe80::1%lo0]:5353"), Some("fe80::1".parse().unwrap()));
+        assert_eq!(host("localhost:80"), None);
+    }
+
+    #[test]
+    fn parses_linux_fixture() {
+        let ports = parse_lsof_output(
+            include_str!("../../tests/fixtures/lsof_linux.txt"),
+            &mut PathCache::new(),
+        )
+        .unwrap();
+
+        assert_eq!(ports.len(), 4);
+        assert_eq!(ports[0].process_name, "sshd");
+        assert_eq!(ports[0].user, "root");
+        assert_eq!(ports[0].port, 22);
+        assert_eq!(ports[0].port_state, ProcessPortState::Hosting);
+        assert_eq!(ports[1].port, 22);
+        assert_eq!(ports[1].pid, 812);
+        assert_eq!(ports[2].process_name, "systemd-r");
+        assert_eq!(ports[2].port, 53);
+        assert_eq!(ports[2].port_state, ProcessPortState::Using);
+        assert_eq!(ports[2].protocol, TransportProtocol::Udp);
+        assert_eq!(ports[3].protocol, TransportProtocol::Tcp);
+        assert_eq!(ports[3].port, 5432);
+        assert_eq!(ports[3].user, "postgres");
+        assert_eq!(ports[0].address_family, AddressFamily::V4);
+        assert_eq!(ports[3].address_family, AddressFamily::V6);
+        assert_eq!(ports[3].protocol_label(), "tcp6");
+        assert!(ports[0].is_wildcard() && ports[0].is_external());
+        assert!(ports[2].is_loopback() && !ports[2].is_external());
+        assert!(ports[3].is_loopback());
+        assert_eq!(ports[0].socket_inode, Some(21346));
+        assert_eq!(ports[3].socket_inode, Some(24011));
+    }
+
+    #[test]
+    fn parses_namespace_links() {
+        assert_eq!(parse_namespace_link("net:[4026531840]"), Some(4026531840));
+        assert_eq!(parse_namespace_link("mnt:[4026531841]"), None);
+        assert_eq!(parse_namespace_link("net:[]"), None);
+    }
+
+    #[test]
+    fn parses_macos_fixture() {
+        let ports = parse_lsof_output(
+            include_str!("../../tests/fixtures/lsof_macos.txt"),
+            &mut PathCache::new(),
+        )
+        .unwrap();
+
+        assert_eq!(ports.len(), 4);
+        assert_eq!(ports[0].process_name, "mDNSRespo");
+        assert_eq!(ports[0].user, "_mdnsresponder");
+        assert_eq!(ports[0].port, 5353);
+        assert_eq!(ports[1].process_name, "Google Chrome");
+        assert_eq!(ports[1].pid, 1733);
+        assert_eq!(ports[1].user, "alice");
+        assert_eq!(ports[1].port, 51234);
+        assert_eq!(ports[2].process_name, "rapportd");
+        assert_eq!(ports[2].port, 49152);
+        assert_eq!(ports[2].port_state, ProcessPortState::Hosting);
+        assert_eq!(ports[3].port, 49153);
+        assert_eq!(ports[3].port_state, ProcessPortState::Hosting);
+        // Kernel addresses, not inodes
+        assert!(ports.iter().all(|p| p.socket_inode.is_none()));
+    }
+
+    #[test]
+    fn reuses_cached_process_paths() {
+        let sshd = ProcessPath {
+            path: "/usr/sbin/sshd".into(),
+            ..Default::default()
+        };
+        let mut paths = PathCache::from([(731, sshd)]);
+        let ports = parse_lsof_output(
+            include_str!("../../tests/fixtures/lsof_linux.txt"),
+            &mut paths,
+        )
+        .unwrap();
+
+        assert_eq!(ports[0].process_path, "/usr/sbin/sshd");
+        // Newly seen PIDs are resolved and remembered
+        assert!(paths.contains_key(&1024));
+    }
+
+    #[test]
+    fn only_linux_reads_a_missing_executable_as_a_kernel_thread() {
+        let missing = io::Error::from(io::ErrorKind::NotFound);
+        let own = std::process::id();
+        assert!(is_kernel_thread(true, own, &missing));
+        // macOS and the BSDs have no /proc to tell a kernel thread apart
+        assert!(!is_kernel_thread(false, own, &missing));
+        // The process is gone rather than threadless
+        assert!(!is_kernel_thread(true, u32::MAX, &missing));
+        let denied = io::Error::from(io::ErrorKind::PermissionDenied);
+        assert!(!is_kernel_thread(true, own, &denied));
+
+        // An unresolved path on a non-Linux unix row stays visible
+        let row = PortInfo {
+            pid: 4242,
+            kernel_thread: is_kernel_thread(false, own, &missing),
+            ..PortInfo::default()
+        };
+        assert!(!row.is_system());
+    }
+
+    #[test]
+    fn rejects_unknown_header() {
+        assert!(parse_lsof_output("garbage\n", &mut PathCache::new()).is_err());
+        assert!(
+            parse_lsof_output("", &mut PathCache::new())
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn parses_ipv6_lsof_lines() {
+        let output = format!(
+            "{HEADER}\n\
+             nginx      4242   root    6u  IPv6 0x1234      0t0  TCP [::1]:8080 (LISTEN)\n\
+             curl       4343  alice    5u  IPv6 0x5678      0t0  TCP [fe80::1]:51000->[2001:db8::1]:443 (ESTABLISHED)\n"
+        );
+        let ports = parse_lsof_output(&output, &mut PathCache::new()).unwrap();
+
+        assert_eq!(ports.len(), 2);
+        assert_eq!(ports[0].port, 8080);
+        assert_eq!(ports[0].port_state, ProcessPortState::Hosting);
+        assert_eq!(ports[1].port, 51000);
+        assert_eq!(ports[1].port_state, ProcessPortState::Using);
+        assert_eq!(
+            ports[1].remote_address,
+            Some("[2001:db8::1]:443".parse().unwrap())
+        );
+    }
+
+    fn spawn_reaped(script: &str) -> u32 {
+        let mut child = Command::new("sh").args(["-c", script]).spawn().unwrap();
+        let pid = child.id();
+        // Reap the child once it dies so it does not linger as a zombie
+        thread::spawn(move || child.wait());
+        thread::sleep(Duration::from_millis(200));
+        pid
+    }
+
+    #[test]
+    fn escalation_stops_at_sigterm_when_it_is_enough() {
+        let pid = spawn_reaped("exec sleep 30");
+        let response = kill_process_escalate(pid, Duration::from_secs(5));
+        assert!(response.success);
+        assert!(
+            response.message.contains("after SIGTERM"),
+            "{}",
+            response.message
+        );
+    }
+
+    #[test]
+    fn escalation_falls_back_to_sigkill() {
+        let pid = spawn_reaped("trap '' TERM; exec sleep 30");
+        let response = kill_process_escalate(pid, Duration::from_millis(300));
+        assert!(response.success);
+        assert!(response.message.contains("SIGKILL"), "{}", response.message);
+    }
+
+    #[test]
+    fn reads_own_command_line() {
+        let cmdline = process_cmdline(std::process::id()).unwrap();
+        let exe = std::env::args().next().unwrap();
+        assert!(cmdline.starts_with(&exe), "{}", cmdline);
+    }
+
+    #[test]
+    fn reads_own_start_time() {
+        let started = process_start_time(std::process::id()).unwrap();
+        let age = SystemTime::now().duration_since(started).unwrap();
+        assert!(age < Duration::from_secs(600), "{:?}", age);
+    }
+
+    #[test]
+    fn kills_a_whole_process_group() {
+        use std::os::unix::process::{CommandExt, ExitStatusExt};
+
+        let mut child = Command::new("sh")
+            .args(["-c", "sleep 30 & wait"])
+            .process_group(0)
+            .spawn()
+            .unwrap();
+        let pid = child.id();
+        thread::sleep(Duration::from_millis(200));
+        assert_eq!(process_group_id(pid), Some(pid));
+
+        assert!(kill_process_group(pid, libc::SIGTERM).success);
+        assert_eq!(child.wait().unwrap().signal(), Some(libc::SIGTERM));
+    }
+
+    #[test]
+    fn refuses_to_kill_its_own_process_group() {
+        let own = process_group_id(std::process::id()).unwrap();
+        assert!(!kill_process_group(own, libc::SIGTERM).success);
+        assert!(!kill_process_group(1, libc::SIGTERM).success);
+    }
+}
@@ -0,0 +1,60 @@
+use crate::model::{PortInfo, os};
+
+use chrono::{DateTime, Utc};
+
+use std::{collections::HashMap, thread};
+
+/// Computes per-process CPU and memory usage, and start times, across consecutive refreshes.
+///
+/// CPU usage is a rate, so it needs the counters from the previous refresh;
+/// the first sample of a process therefore has no CPU figure.
+#[derive(Debug, Default)]
+pub struct UsageSampler {
+    previous_system: Option<u64>,
+    previous_processes: HashMap<u32, u64>,
+}
+
+impl UsageSampler {
+    /// Fills `cpu_percent`, `memory_bytes` and `started_at` on every entry.
+    pub fn sample(&mut self, ports: &mut [PortInfo]) {
+        let cores = thread::available_parallelism().map_or(1, |n| n.get()) as f32;
+        let system = os::system_cpu_time();
+        let system_delta = match (system, self.previous_system) {
+            (Some(now), Some(before)) if now > before => Some(now - before),
+            _ => None,
+        };
+
+        let mut current: HashMap<u32, u64> = HashMap::new();
+        let mut memory: HashMap<u32, Option<u64>> = HashMap::new();
+        let mut started: HashMap<u32, Option<DateTime<Utc>>> = HashMap::new();
+
+        for port in ports.iter_mut() {
+            let pid = port.pid;
+
+            if let Some(now) = current
+                .get(&pid)
+                .copied()
+                .or_else(|| os::process_cpu_time(pid))
+            {
+                current.insert(pid, now);
+                port.cpu_percent = match (self.previous_processes.get(&pid), system_delta) {
+                    (Some(&before), Some(total)) => {
+                        let used = now.saturating_sub(before) as f32;
+                        Some(used / total as f32 * 100.0 * cores)
+                    }
+                    _ => None,
+                };
+            }
+
+            port.memory_bytes = *memory
+                .entry(pid)
+                .or_insert_with(|| os::process_memory_bytes(pid));
+            port.started_at = *started
+                .entry(pid)
+                .or_insert_with(|| os::process_start_time(pid).map(DateTime::from));
+        }
+
+        self.previous_system = system;
+        self.previous_processes = current;
+    }
+}
@@ -1,27 +1,53 @@
+use windows::Wdk::System::Threading::{NtQueryInformationProcess, ProcessCommandLineInformation};
 use windows::Win32::{
-    Foundation::{CloseHandle, ERROR_ACCESS_DENIED, NO_ERROR},
+    Foundation::{CloseHandle, ERROR_ACCESS_DENIED, FILETIME, HANDLE, NO_ERROR, UNICODE_STRING},
     NetworkManagement::IpHelper::{
         GetExtendedTcpTable, GetExtendedUdpTable, MIB_TCP6TABLE_OWNER_PID, MIB_TCPTABLE_OWNER_PID,
         MIB_UDP6TABLE_OWNER_PID, MIB_UDPTABLE_OWNER_PID, TCP_TABLE_OWNER_PID_ALL,
         UDP_TABLE_OWNER_PID,
     },
+    Networking::WinSock::{
+        AF_INET, AF_INET6, GetNameInfoW, IN_ADDR, IN_ADDR_0, IN6_ADDR, IN6_ADDR_0, NI_NAMEREQD,
+        SOCKADDR, SOCKADDR_IN, SOCKADDR_IN6, WSADATA, WSAStartup, socklen_t,
+    },
+    Security::{
+        GetTokenInformation, LookupAccountSidW, SID_NAME_USE, TOKEN_QUERY, TOKEN_USER, TokenUser,
+    },
     System::{
-        ProcessStatus::{K32GetModuleBaseNameW, K32GetModuleFileNameExW},
+        Diagnostics::ToolHelp::{
+            CreateToolhelp32Snapshot, PROCESSENTRY32W, Process32FirstW, Process32NextW,
+            TH32CS_SNAPPROCESS,
+        },
+        ProcessStatus::{
+            K32GetModuleBaseNameW, K32GetModuleFileNameExW, K32GetProcessMemoryInfo,
+            PROCESS_MEMORY_COUNTERS,
+        },
         Threading::{
-            OpenProcess, PROCESS_QUERY_INFORMATION, PROCESS_TERMINATE, PROCESS_VM_READ,
-            TerminateProcess,
+            GetProcessTimes, GetSystemTimes, OpenProcess, OpenProcessToken,
+            PROCESS_QUERY_INFORMATION, PROCESS_QUERY_LIMITED_INFORMATION, PROCESS_TERMINATE,
+            PROCESS_VM_READ, TerminateProcess,
         },
     },
 };
+use windows::core::{PCWSTR, PWSTR};
 
 use std::{
-    collections::hash_map::DefaultHasher,
+    collections::{HashMap, HashSet, hash_map::DefaultHasher},
     ffi::OsString,
     hash::{Hash, Hasher},
+    mem,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
     os::windows::ffi::OsStringExt,
+    process::Command,
+    ptr,
+    sync::Once,
+    thread,
+    time::{Duration, SystemTime, UNIX_EPOCH},
 };
 
-use crate::model::{KillProcessResponse, PortInfo, ProcessPortState};
+use crate::model::{
+    AddressFamily, KillProcessResponse, PortInfo, ProcessPortState, TransportProtocol,
+};
 
 const TCP_STATE_LISTEN: u32 = 2;
 
@@ -118,14 +144,24 @@ fn fetch_table(protocol: &Protocol, buffer_size: u32) -> Option<Vec<u8>> {
     }
 }
 
-fn generate_unique_id(pid: u32, port: u16) -> String {
+/// Id of a socket as [`fetch_ports`] computes it, for rebuilding entries read from files.
+/// Windows ids do not depend on the process name.
+pub fn port_id(pid: u32, port: u16, _process_name: &str, address_family: AddressFamily) -> String {
+    generate_unique_id(pid, port, address_family)
+}
+
+fn generate_unique_id(pid: u32, port: u16, address_family: AddressFamily) -> String {
     let mut hasher = DefaultHasher::new();
     pid.hash(&mut hasher);
     port.hash(&mut hasher);
+    // IPv4 ids stay the same as before the family was tracked
+    if address_family == AddressFamily::V6 {
+        address_family.hash(&mut hasher);
+    }
     format!("{:x}", hasher.finish())
 }
 
-fn parse_tcp_ipv4(buffer: &[u8]) -> Vec<PortInfo> {
+fn parse_tcp_ipv4(buffer: &[u8], parents: &HashMap<u32, u32>) -> Vec<PortInfo> {
     let mut results = Vec::new();
 
     unsafe {
@@ -138,25 +174,43 @@ fn parse_tcp_ipv4(buffer: &[u8]) -> Vec<PortInfo> {
 
             let port = u16::from_be(row.dwLocalPort as u16);
 
-            let id = generate_unique_id(row.dwOwningPid, port);
+            let id = generate_unique_id(row.dwOwningPid, port, AddressFamily::V4);
 
-            let (process_name, process_path) = match get_process_info(row.dwOwningPid) {
-                Some((process_name, process_path)) => (process_name, process_path),
-                None => (String::from("Unknown"), String::from("Unknown")),
-            };
             let port_state = if row.dwState == TCP_STATE_LISTEN {
                 ProcessPortState::Hosting
             } else {
                 ProcessPortState::Using
             };
+            let remote_address = (row.dwState != TCP_STATE_LISTEN).then(|| {
+                SocketAddr::from((
+                    Ipv4Addr::from(row.dwRemoteAddr.to_ne_bytes()),
+                    u16::from_be(row.dwRemotePort as u16),
+                ))
+            });
 
             let port_info = PortInfo {
                 id,
                 port,
-                process_name,
-                process_path,
+                // Filled in by `resolve_processes` once per PID
+                process_name: String::new(),
+                process_path: String::new(),
+                restricted: false,
                 pid: row.dwOwningPid,
+                ppid: parents.get(&row.dwOwningPid).copied().unwrap_or(0),
+                user: String::new(),
+                cpu_percent: None,
+                memory_bytes: None,
+                started_at: None,
+                socket_inode: None,
+                net_namespace: None,
+                kernel_thread: false,
                 port_state,
+                local_address: Some(IpAddr::V4(Ipv4Addr::from(row.dwLocalAddr.to_ne_bytes()))),
+                remote_address,
+                remote_host: None,
+                cmdline: None,
+                protocol: TransportProtocol::Tcp,
+                address_family: AddressFamily::V4,
             };
 
             if !results
@@ -171,7 +225,7 @@ fn parse_tcp_ipv4(buffer: &[u8]) -> Vec<PortInfo> {
     results
 }
 
-fn parse_tcp_ipv6(buffer: &[u8]) -> Vec<PortInfo> {
+fn parse_tcp_ipv6(buffer: &[u8], parents: &HashMap<u32, u32>) -> Vec<PortInfo> {
     let mut results = Vec::new();
 
     unsafe {
@@ -184,25 +238,43 @@ fn parse_tcp_ipv6(buffer: &[u8]) -> Vec<PortInfo> {
 
             let port = u16::from_be(row.dwLocalPort as u16);
 
-            let id = generate_unique_id(row.dwOwningPid, port);
+            let id = generate_unique_id(row.dwOwningPid, port, AddressFamily::V6);
 
-            let (process_name, process_path) = match get_process_info(row.dwOwningPid) {
-                Some((process_name, process_path)) => (process_name, process_path),
-                None => (String::from("Unknown"), String::from("Unknown")),
-            };
             let port_state = if row.dwState == TCP_STATE_LISTEN {
                 ProcessPortState::Hosting
             } else {
                 ProcessPortState::Using
             };
+            let remote_address = (row.dwState != TCP_STATE_LISTEN).then(|| {
+                SocketAddr::from((
+                    Ipv6Addr::from(row.ucRemoteAddr),
+                    u16::from_be(row.dwRemotePort as u16),
+                ))
+            });
 
             let port_info = PortInfo {
                 id,
                 port,
-                process_name,
-                process_path,
+                // Filled in by `resolve_processes` once per PID
+                process_name: String::new(),
+                process_path: String::new(),
+                restricted: false,
                 pid: row.dwOwningPid,
+                ppid: parents.get(&row.dwOwningPid).copied().unwrap_or(0),
+                user: String::new(),
+                cpu_percent: None,
+                memory_bytes: None,
+                started_at: None,
+                socket_inode: None,
+                net_namespace: None,
+                kernel_thread: false,
                 port_state,
+                local_address: Some(IpAddr::V6(Ipv6Addr::from(row.ucLocalAddr))),
+                remote_address,
+                remote_host: None,
+                cmdline: None,
+                protocol: TransportProtocol::Tcp,
+                address_family: AddressFamily::V6,
             };
 
             if !results
@@ -217,7 +289,7 @@ fn parse_tcp_ipv6(buffer: &[u8]) -> Vec<PortInfo> {
     results
 }
 
-fn parse_udp_ipv4(buffer: &[u8]) -> Vec<PortInfo> {
+fn parse_udp_ipv4(buffer: &[u8], parents: &HashMap<u32, u32>) -> Vec<PortInfo> {
     let mut results = Vec::new();
 
     unsafe {
@@ -230,20 +302,31 @@ fn parse_udp_ipv4(buffer: &[u8]) -> Vec<PortInfo> {
 
             let port = u16::from_be(row.dwLocalPort as u16);
 
-            let id = generate_unique_id(row.dwOwningPid, port);
-
-            let (process_name, process_path) = match get_process_info(row.dwOwningPid) {
-                Some((process_name, process_path)) => (process_name, process_path),
-                None => (String::from("Unknown"), String::from("Unknown")),
-            };
+            let id = generate_unique_id(row.dwOwningPid, port, AddressFamily::V4);
 
             let port_info = PortInfo {
                 id,
                 port,
-                process_name,
-                process_path,
+                // Filled in by `resolve_processes` once per PID
+                process_name: String::new(),
+                process_path: String::new(),
+                restricted: false,
                 pid: row.dwOwningPid,
+                ppid: parents.get(&row.dwOwningPid).copied().unwrap_or(0),
+                user: String::new(),
+                cpu_percent: None,
+                memory_bytes: None,
+                started_at: None,
+                socket_inode: None,
+                net_namespace: None,
+                kernel_thread: false,
                 port_state: ProcessPortState::Using,
+                local_address: Some(IpAddr::V4(Ipv4Addr::from(row.dwLocalAddr.to_ne_bytes()))),
+                remote_address: None,
+                remote_host: None,
+                cmdline: None,
+                protocol: TransportProtocol::Udp,
+                address_family: AddressFamily::V4,
             };
 
             if !results
@@ -258,7 +341,7 @@ fn parse_udp_ipv4(buffer: &[u8]) -> Vec<PortInfo> {
     results
 }
 
-fn parse_udp_ipv6(buffer: &[u8]) -> Vec<PortInfo> {
+fn parse_udp_ipv6(buffer: &[u8], parents: &HashMap<u32, u32>) -> Vec<PortInfo> {
     let mut results = Vec::new();
 
     unsafe {
@@ -271,20 +354,31 @@ fn parse_udp_ipv6(buffer: &[u8]) -> Vec<PortInfo> {
 
             let port = u16::from_be(row.dwLocalPort as u16);
 
-            let id = generate_unique_id(row.dwOwningPid, port);
-
-            let (process_name, process_path) = match get_process_info(row.dwOwningPid) {
-                Some((process_name, process_path)) => (process_name, process_path),
-                None => (String::from("Unknown"), String::from("Unknown")),
-            };
+            let id = generate_unique_id(row.dwOwningPid, port, AddressFamily::V6);
 
             let port_info = PortInfo {
                 id,
                 port,
-                process_name,
-                process_path,
+                // Filled in by `resolve_processes` once per PID
+                process_name: String::new(),
+                process_path: String::new(),
+                restricted: false,
                 pid: row.dwOwningPid,
+                ppid: parents.get(&row.dwOwningPid).copied().unwrap_or(0),
+                user: String::new(),
+                cpu_percent: None,
+                memory_bytes: None,
+                started_at: None,
+                socket_inode: None,
+                net_namespace: None,
+                kernel_thread: false,
                 port_state: ProcessPortState::Using,
+                local_address: Some(IpAddr::V6(Ipv6Addr::from(row.ucLocalAddr))),
+                remote_address: None,
+                remote_host: None,
+                cmdline: None,
+                protocol: TransportProtocol::Udp,
+                address_family: AddressFamily::V6,
             };
 
             if !results
@@ -307,6 +401,7 @@ pub fn fetch_ports() -> Result<Vec<PortInfo>, String> {
         Protocol::UdpIpv6,
     ];
 
+    let parents = get_parent_pids();
     let mut all_connections = Vec::new();
 
     for protocol in protocols {
@@ -315,16 +410,16 @@ pub fn fetch_ports() -> Result<Vec<PortInfo>, String> {
                 if let Some(buffer) = fetch_table(&protocol, buffer_size) {
                     match protocol {
                         Protocol::TcpIpv4 => {
-                            all_connections.extend(parse_tcp_ipv4(&buffer));
+                            all_connections.extend(parse_tcp_ipv4(&buffer, &parents));
                         }
                         Protocol::TcpIpv6 => {
-                            all_connections.extend(parse_tcp_ipv6(&buffer));
+                            all_connections.extend(parse_tcp_ipv6(&buffer, &parents));
                         }
                         Protocol::UdpIpv4 => {
-                            all_connections.extend(parse_udp_ipv4(&buffer));
+                            all_connections.extend(parse_udp_ipv4(&buffer, &parents));
                         }
                         Protocol::UdpIpv6 => {
-                            all_connections.extend(parse_udp_ipv6(&buffer));
+                            all_connections.extend(parse_udp_ipv6(&buffer, &parents));
                         }
                     }
                 } else {
@@ -343,9 +438,159 @@ pub fn fetch_ports() -> Result<Vec<PortInfo>, String> {
         }
     }
 
+    // Ports of the same process share its details, so each PID is resolved once per refresh
+    let pids: HashSet<u32> = all_connections.iter().map(|p| p.pid).collect();
+    let processes = resolve_processes(pids);
+    for port in all_connections.iter_mut() {
+        if let Some(details) = processes.get(&port.pid) {
+            details.apply_to(port);
+        }
+    }
+
     Ok(all_connections)
 }
 
+/// Number of threads resolving process details in parallel
+const RESOLVE_THREADS: usize = 4;
+
+/// Name, path and owner of a process
+#[derive(Debug, Clone)]
+struct ProcessDetails {
+    process_name: String,
+    process_path: String,
+    restricted: bool,
+    user: String,
+}
+
+impl ProcessDetails {
+    fn resolve(pid: u32) -> Self {
+        let (process_name, process_path, restricted) = match get_process_info(pid) {
+            Some((process_name, process_path)) => (process_name, process_path, false),
+            None => (String::from("Unknown"), String::new(), true),
+        };
+        Self {
+            process_name,
+            process_path,
+            restricted,
+            user: get_process_user(pid).unwrap_or_else(|| String::from("Unknown")),
+        }
+    }
+
+    fn apply_to(&self, port: &mut PortInfo) {
+        port.process_name = self.process_name.clone();
+        port.process_path = self.process_path.clone();
+        port.restricted = self.restricted;
+        port.user = self.user.clone();
+    }
+}
+
+/// Looks up every PID on a few worker threads, since opening process handles
+/// and querying tokens is what makes a refresh slow on busy machines.
+fn resolve_processes(pids: HashSet<u32>) -> HashMap<u32, ProcessDetails> {
+    let pids: Vec<u32> = pids.into_iter().collect();
+    if pids.is_empty() {
+        return HashMap::new();
+    }
+    let chunk_size = pids.len().div_ceil(RESOLVE_THREADS);
+
+    thread::scope(|scope| {
+        let workers: Vec<_> = pids
+            .chunks(chunk_size)
+            .map(|chunk| {
+                scope.spawn(move || {
+                    chunk
+                        .iter()
+                        .map(|&pid| (pid, ProcessDetails::resolve(pid)))
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+
+        workers
+            .into_iter()
+            .flat_map(|worker| worker.join().unwrap_or_default())
+            .collect()
+    })
+}
+
+/// Builds a PID -> parent PID map from a Toolhelp process snapshot.
+fn get_parent_pids() -> HashMap<u32, u32> {
+    let mut parents = HashMap::new();
+
+    unsafe {
+        let Ok(snapshot) = CreateToolhelp32Snapshot(TH32CS_SNAPPROCESS, 0) else {
+            return parents;
+        };
+
+        let mut entry = PROCESSENTRY32W {
+            dwSize: std::mem::size_of::<PROCESSENTRY32W>() as u32,
+            ..Default::default()
+        };
+
+        if Process32FirstW(snapshot, &mut entry).is_ok() {
+            loop {
+                parents.insert(entry.th32ProcessID, entry.th32ParentProcessID);
+                if Process32NextW(snapshot, &mut entry).is_err() {
+                    break;
+                }
+            }
+        }
+
+        let _ = CloseHandle(snapshot);
+    }
+
+    parents
+}
+
+/// Collects all descendants of `root`, ordered so that children come before their parents.
+fn collect_descendants(root: u32) -> Vec<u32> {
+    let mut children: HashMap<u32, Vec<u32>> = HashMap::new();
+    for (pid, ppid) in get_parent_pids() {
+        // PID 0 is its own parent on Windows, skip it to avoid a cycle
+        if pid != ppid {
+            children.entry(ppid).or_default().push(pid);
+        }
+    }
+
+    let mut ordered = Vec::new();
+    let mut stack = vec![root];
+    while let Some(pid) = stack.pop() {
+        if let Some(kids) = children.get(&pid) {
+            for &kid in kids {
+                ordered.push(kid);
+                stack.push(kid);
+            }
+        }
+    }
+    ordered.reverse();
+    ordered
+}
+
+/// Kills a process together with every process it spawned, deepest children first.
+pub fn kill_process_tree(pid: u32) -> KillProcessResponse {
+    let descendants = collect_descendants(pid);
+    let killed_children = descendants
+        .iter()
+        .filter(|&&child| kill_process(child).success)
+        .count();
+
+    let response = kill_process(pid);
+    if !response.success {
+        return response;
+    }
+
+    KillProcessResponse {
+        success: true,
+        access_denied: false,
+        message: format!(
+            "Successfully killed process with PID {} and {} of {} child processes",
+            pid,
+            killed_children,
+            descendants.len()
+        ),
+    }
+}
+
 pub fn kill_process(pid: u32) -> KillProcessResponse {
     unsafe {
         match OpenProcess(PROCESS_TERMINATE, false, pid) {
@@ -356,16 +601,19 @@ pub fn kill_process(pid: u32) -> KillProcessResponse {
                 match terminate_result {
                     Ok(()) => KillProcessResponse {
                         success: true,
+                        access_denied: false,
                         message: format!("Successfully killed process with PID {}", pid),
                     },
                     Err(error) => {
-                        let message = if error.code() == ERROR_ACCESS_DENIED.into() {
+                        let access_denied = error.code() == ERROR_ACCESS_DENIED.into();
+                        let message = if access_denied {
                             "Access denied".to_string()
                         } else {
                             format!("Error code: {:?}", error.code())
                         };
                         KillProcessResponse {
                             success: false,
+                            access_denied,
                             message: format!(
                                 "Failed to terminate process with PID {}: {}",
                                 pid, message
@@ -376,6 +624,7 @@ pub fn kill_process(pid: u32) -> KillProcessResponse {
             }
             Err(error) => KillProcessResponse {
                 success: false,
+                access_denied: error.code() == ERROR_ACCESS_DENIED.into(),
                 message: format!(
                     "Failed to open process with PID {}: {}",
                     pid,
@@ -386,6 +635,114 @@ pub fn kill_process(pid: u32) -> KillProcessResponse {
     }
 }
 
+/// Full command line, asked from the kernel so opening the kill popup does not wait
+/// on a child process. Needs Windows 8.1 or later.
+pub fn process_cmdline(pid: u32) -> Option<String> {
+    unsafe {
+        let process_handle = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid).ok()?;
+
+        // The first call only reports the size of the UNICODE_STRING and the text after it
+        let mut needed = 0u32;
+        let _ = NtQueryInformationProcess(
+            process_handle,
+            ProcessCommandLineInformation,
+            ptr::null_mut(),
+            0,
+            &mut needed,
+        );
+        // u64 words keep the UNICODE_STRING at the start aligned
+        let mut buffer = vec![0u64; (needed as usize).div_ceil(8)];
+        let status = NtQueryInformationProcess(
+            process_handle,
+            ProcessCommandLineInformation,
+            buffer.as_mut_ptr().cast(),
+            (buffer.len() * 8) as u32,
+            &mut needed,
+        );
+
+        let _ = CloseHandle(process_handle);
+
+        if status.is_err() || buffer.is_empty() {
+            return None;
+        }
+        let text = &*(buffer.as_ptr() as *const UNICODE_STRING);
+        if text.Buffer.is_null() {
+            return None;
+        }
+        let chars = std::slice::from_raw_parts(text.Buffer.0, text.Length as usize / 2);
+        let cmdline = String::from_utf16_lossy(chars).trim().to_string();
+        (!cmdline.is_empty()).then_some(cmdline)
+    }
+}
+
+/// Windows has no Unix process groups; job objects are not exposed per PID.
+pub fn process_group_id(_pid: u32) -> Option<u32> {
+    None
+}
+
+/// Never offered since [`process_group_id`] finds no groups.
+pub fn kill_process_group(pgid: u32, _signal: i32) -> KillProcessResponse {
+    KillProcessResponse {
+        success: false,
+        access_denied: false,
+        message: format!(
+            "Cannot kill process group {}: not supported on Windows",
+            pgid
+        ),
+    }
+}
+
+/// A command to kill the process by hand, e.g. from an administrator prompt.
+pub fn manual_kill_command(pid: u32) -> String {
+    format!("taskkill /PID {} /F", pid)
+}
+
+/// `TerminateProcess` cannot be caught, so there is nothing to escalate from.
+pub fn kill_process_escalate(pid: u32, _timeout: Duration) -> KillProcessResponse {
+    kill_process(pid)
+}
+
+/// Retries a kill through `taskkill` launched with a UAC prompt.
+pub fn kill_process_elevated(pid: u32, tree: bool) -> KillProcessResponse {
+    let arguments = if tree {
+        format!("/PID {} /T /F", pid)
+    } else {
+        format!("/PID {} /F", pid)
+    };
+    let script = format!(
+        "Start-Process taskkill -ArgumentList '{}' -Verb RunAs -WindowStyle Hidden -Wait",
+        arguments
+    );
+    let output = Command::new("powershell")
+        .args(["-NoProfile", "-NonInteractive", "-Command", &script])
+        .output();
+
+    match output {
+        Ok(output) if output.status.success() => KillProcessResponse {
+            success: true,
+            access_denied: false,
+            message: format!(
+                "Successfully killed process with PID {} as administrator",
+                pid
+            ),
+        },
+        Ok(output) => KillProcessResponse {
+            success: false,
+            access_denied: true,
+            message: format!(
+                "Failed to kill process {} as administrator: {}",
+                pid,
+                String::from_utf8_lossy(&output.stderr).trim()
+            ),
+        },
+        Err(e) => KillProcessResponse {
+            success: false,
+            access_denied: true,
+            message: format!("Failed to launch elevated taskkill: {}", e),
+        },
+    }
+}
+
 pub fn get_process_info(pid: u32) -> Option<(String, String)> {
     unsafe {
         let process_handle =
@@ -419,3 +776,208 @@ pub fn get_process_info(pid: u32) -> Option<(String, String)> {
         Some((process_name, process_path))
     }
 }
+
+fn filetime_to_u64(time: FILETIME) -> u64 {
+    ((time.dwHighDateTime as u64) << 32) | time.dwLowDateTime as u64
+}
+
+/// CPU time spent by a process (kernel + user), in 100ns units.
+pub fn process_cpu_time(pid: u32) -> Option<u64> {
+    unsafe {
+        let process_handle = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid).ok()?;
+
+        let mut creation = FILETIME::default();
+        let mut exit = FILETIME::default();
+        let mut kernel = FILETIME::default();
+        let mut user = FILETIME::default();
+        let result = GetProcessTimes(
+            process_handle,
+            &mut creation,
+            &mut exit,
+            &mut kernel,
+            &mut user,
+        );
+
+        let _ = CloseHandle(process_handle);
+
+        result.ok()?;
+        Some(filetime_to_u64(kernel) + filetime_to_u64(user))
+    }
+}
+
+/// When a process started, from the creation time reported by `GetProcessTimes`.
+pub fn process_start_time(pid: u32) -> Option<SystemTime> {
+    // FILETIMEs count 100ns intervals since 1601-01-01
+    const UNIX_EPOCH_AS_FILETIME: u64 = 116_444_736_000_000_000;
+
+    unsafe {
+        let process_handle = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid).ok()?;
+
+        let mut creation = FILETIME::default();
+        let mut exit = FILETIME::default();
+        let mut kernel = FILETIME::default();
+        let mut user = FILETIME::default();
+        let result = GetProcessTimes(
+            process_handle,
+            &mut creation,
+            &mut exit,
+            &mut kernel,
+            &mut user,
+        );
+
+        let _ = CloseHandle(process_handle);
+
+        result.ok()?;
+        let since_epoch = filetime_to_u64(creation).checked_sub(UNIX_EPOCH_AS_FILETIME)?;
+        Some(UNIX_EPOCH + Duration::from_nanos(since_epoch * 100))
+    }
+}
+
+/// CPU time spent by all cores together, in 100ns units.
+pub fn system_cpu_time() -> Option<u64> {
+    let mut idle = FILETIME::default();
+    let mut kernel = FILETIME::default();
+    let mut user = FILETIME::default();
+
+    unsafe {
+        GetSystemTimes(Some(&mut idle), Some(&mut kernel), Some(&mut user)).ok()?;
+    }
+
+    // Kernel time already includes idle time
+    Some(filetime_to_u64(kernel) + filetime_to_u64(user))
+}
+
+/// Working set size of a process.
+pub fn process_memory_bytes(pid: u32) -> Option<u64> {
+    unsafe {
+        let process_handle = OpenProcess(
+            PROCESS_QUERY_LIMITED_INFORMATION | PROCESS_VM_READ,
+            false,
+            pid,
+        )
+        .ok()?;
+
+        let mut counters = PROCESS_MEMORY_COUNTERS::default();
+        let size = std::mem::size_of::<PROCESS_MEMORY_COUNTERS>() as u32;
+        let result = K32GetProcessMemoryInfo(process_handle, &mut counters, size);
+
+        let _ = CloseHandle(process_handle);
+
+        result.as_bool().then_some(counters.WorkingSetSize as u64)
+    }
+}
+
+/// Resolves the account name from the token of the process owner.
+fn get_process_user(pid: u32) -> Option<String> {
+    unsafe {
+        let process_handle = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid).ok()?;
+        let mut token = HANDLE::default();
+        let opened = OpenProcessToken(process_handle, TOKEN_QUERY, &mut token);
+        let _ = CloseHandle(process_handle);
+        opened.ok()?;
+
+        let mut length = 0u32;
+        let _ = GetTokenInformation(token, TokenUser, None, 0, &mut length);
+        // u64 backing keeps the buffer aligned for TOKEN_USER
+        let mut buffer = vec![0u64; (length as usize).div_ceil(8)];
+        let result = GetTokenInformation(
+            token,
+            TokenUser,
+            Some(buffer.as_mut_ptr() as *mut _),
+            length,
+            &mut length,
+        );
+        let _ = CloseHandle(token);
+        result.ok()?;
+
+        let token_user = &*(buffer.as_ptr() as *const TOKEN_USER);
+        let mut name = vec![0u16; 256];
+        let mut name_len = name.len() as u32;
+        let mut domain = vec![0u16; 256];
+        let mut domain_len = domain.len() as u32;
+        let mut sid_use = SID_NAME_USE::default();
+        LookupAccountSidW(
+            PCWSTR::null(),
+            token_user.User.Sid,
+            Some(PWSTR(name.as_mut_ptr())),
+            &mut name_len,
+            Some(PWSTR(domain.as_mut_ptr())),
+            &mut domain_len,
+            &mut sid_use,
+        )
+        .ok()?;
+
+        Some(
+            OsString::from_wide(&name[..name_len as usize])
+                .to_string_lossy()
+                .into_owned(),
+        )
+    }
+}
+
+/// Account of the current process, formatted like [`get_process_user`].
+pub fn current_user() -> Option<String> {
+    get_process_user(std::process::id())
+}
+
+/// Name of this machine as reported by the `COMPUTERNAME` environment variable.
+pub fn hostname() -> Option<String> {
+    std::env::var("COMPUTERNAME").ok()
+}
+
+/// Resolves the host name of an address via `GetNameInfoW`.
+pub fn reverse_lookup(ip: IpAddr) -> Option<String> {
+    static WSA_INIT: Once = Once::new();
+    WSA_INIT.call_once(|| unsafe {
+        let mut data = WSADATA::default();
+        let _ = WSAStartup(0x0202, &mut data);
+    });
+
+    let mut host = vec![0u16; 1025];
+
+    let result = unsafe {
+        match ip {
+            IpAddr::V4(v4) => {
+                let addr = SOCKADDR_IN {
+                    sin_family: AF_INET,
+                    sin_addr: IN_ADDR {
+                        S_un: IN_ADDR_0 {
+                            S_addr: u32::from_ne_bytes(v4.octets()),
+                        },
+                    },
+                    ..Default::default()
+                };
+                GetNameInfoW(
+                    &addr as *const _ as *const SOCKADDR,
+                    socklen_t(mem::size_of::<SOCKADDR_IN>() as i32),
+                    Some(&mut host),
+                    None,
+                    NI_NAMEREQD as i32,
+                )
+            }
+            IpAddr::V6(v6) => {
+                let addr = SOCKADDR_IN6 {
+                    sin6_family: AF_INET6,
+                    sin6_addr: IN6_ADDR {
+                        u: IN6_ADDR_0 { Byte: v6.octets() },
+                    },
+                    ..Default::default()
+                };
+                GetNameInfoW(
+                    &addr as *const _ as *const SOCKADDR,
+                    socklen_t(mem::size_of::<SOCKADDR_IN6>() as i32),
+                    Some(&mut host),
+                    None,
+                    NI_NAMEREQD as i32,
+                )
+            }
+        }
+    };
+
+    if result != 0 {
+        return None;
+    }
+
+    let len = host.iter().position(|&c| c == 0).unwrap_or(host.len());
+    Some(String::from_utf16_lossy(&host[..len]))
+}
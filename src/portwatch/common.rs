@@ -1,6 +1,6 @@
 use crate::model::PortInfo;
 use chrono::{DateTime, Utc};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 /// Supported export formats
 #[derive(Debug, Clone, Copy, PartialEq, Default)]
@@ -29,7 +29,7 @@ impl ExportFormat {
     }
 }
 
-#[derive(Debug, Serialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(tag = "event")]
 pub enum PortEvent {
     #[serde(rename = "initial_state")]
@@ -48,3 +48,29 @@ pub enum PortEvent {
         port: PortInfo,
     },
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn events_round_trip_through_json() {
+        let json = r#"[
+            {"event": "initial_state", "timestamp": "2025-03-14T09:26:53Z", "ports": []},
+            {"event": "port_closed", "timestamp": "2025-03-14T09:27:00Z", "port": {
+                "id": "20-3000", "port": 3000, "pid": 20, "process_name": "node",
+                "process_path": "/usr/bin/node", "port_state": "Hosting",
+                "remote_address": null, "remote_host": null,
+                "cpu_percent": null, "memory_bytes": null
+            }}
+        ]"#;
+        let events: Vec<PortEvent> = serde_json::from_str(json).unwrap();
+        assert!(matches!(&events[0], PortEvent::InitialState { ports, .. } if ports.is_empty()));
+        assert!(matches!(&events[1], PortEvent::PortClosed { port, .. } if port.port == 3000));
+
+        let again: Vec<PortEvent> =
+            serde_json::from_str(&serde_json::to_string(&events).unwrap()).unwrap();
+        assert_eq!(again.len(), 2);
+        assert!(matches!(&again[1], PortEvent::PortClosed { port, .. } if port.pid == 20));
+    }
+}
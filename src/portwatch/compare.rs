@@ -0,0 +1,90 @@
+use crate::model::PortInfo;
+use crate::portwatch::tracker::Tracker;
+
+/// One line of a comparison between two snapshots
+#[derive(Debug, Clone)]
+pub enum SnapshotDiff {
+    /// Port only present in the first snapshot
+    OnlyInA(PortInfo),
+    /// Port only present in the second snapshot
+    OnlyInB(PortInfo),
+    /// Same port held by a different process
+    Changed {
+        before: Box<PortInfo>,
+        after: Box<PortInfo>,
+    },
+}
+
+impl SnapshotDiff {
+    pub fn port(&self) -> u16 {
+        match self {
+            SnapshotDiff::OnlyInA(p) | SnapshotDiff::OnlyInB(p) => p.port,
+            SnapshotDiff::Changed { before, .. } => before.port,
+        }
+    }
+}
+
+/// Compares two snapshots, pairing removed and added entries that share a port.
+pub fn diff_snapshots(a: &[PortInfo], b: &[PortInfo]) -> Vec<SnapshotDiff> {
+    let (added, mut removed) = Tracker::diff_ports(a, b);
+
+    let mut diffs = Vec::new();
+    for after in added {
+        match removed.iter().position(|before| before.port == after.port) {
+            Some(idx) => {
+                let before = removed.swap_remove(idx);
+                diffs.push(SnapshotDiff::Changed {
+                    before: Box::new(before),
+                    after: Box::new(after),
+                });
+            }
+            None => diffs.push(SnapshotDiff::OnlyInB(after)),
+        }
+    }
+    diffs.extend(removed.into_iter().map(SnapshotDiff::OnlyInA));
+
+    diffs.sort_by_key(SnapshotDiff::port);
+    diffs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::ProcessPortState;
+
+    fn port(port: u16, pid: u32, name: &str) -> PortInfo {
+        PortInfo::fixture(port, pid, name, ProcessPortState::Hosting)
+    }
+
+    #[test]
+    fn pairs_entries_on_the_same_port_and_sorts_by_port() {
+        let a = [
+            port(22, 10, "sshd"),
+            port(8080, 20, "node"),
+            port(5432, 30, "postgres"),
+        ];
+        let b = [
+            port(22, 10, "sshd"),
+            port(8080, 21, "node"),
+            port(443, 40, "nginx"),
+        ];
+
+        let diffs = diff_snapshots(&a, &b);
+        let ports: Vec<u16> = diffs.iter().map(SnapshotDiff::port).collect();
+        // sshd is unchanged and left out
+        assert_eq!(ports, [443, 5432, 8080]);
+
+        assert!(matches!(&diffs[0], SnapshotDiff::OnlyInB(p) if p.pid == 40));
+        assert!(matches!(&diffs[1], SnapshotDiff::OnlyInA(p) if p.pid == 30));
+        assert!(matches!(
+            &diffs[2],
+            SnapshotDiff::Changed { before, after } if before.pid == 20 && after.pid == 21
+        ));
+    }
+
+    #[test]
+    fn identical_snapshots_have_no_differences() {
+        let ports = [port(22, 10, "sshd"), port(80, 11, "nginx")];
+        assert!(diff_snapshots(&ports, &ports).is_empty());
+    }
+}
@@ -4,23 +4,92 @@ use std::{
     path::PathBuf,
 };
 
+use crate::model::os;
 use crate::portwatch::ExportFormat;
-use chrono::Local;
-use serde::Serialize;
+use crate::portwatch::time_format::{RFC3339, TimeFormat};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+
+/// Writes `T` entries as CSV rows into the given writer, formatting any timestamps.
+pub type CsvWriteFn<T> = fn(&mut dyn Write, &[T], &TimeFormat) -> io::Result<()>;
+
+/// Provenance of an export, written ahead of the data when enabled.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportMetadata {
+    pub hostname: Option<String>,
+    pub os: String,
+    pub os_family: String,
+    pub tool_version: String,
+    pub exported_at: String,
+}
+
+impl ExportMetadata {
+    /// Describes the current machine and time.
+    pub fn collect(time: &TimeFormat) -> Self {
+        Self {
+            hostname: os::hostname(),
+            os: std::env::consts::OS.to_string(),
+            os_family: std::env::consts::FAMILY.to_string(),
+            tool_version: env!("CARGO_PKG_VERSION").to_string(),
+            exported_at: time.format(Utc::now(), RFC3339),
+        }
+    }
+
+    /// Single-line form used as a `#` comment at the top of CSV files.
+    fn csv_comment(&self) -> String {
+        format!(
+            "# hostname={} os={} os_family={} tool_version={} exported_at={}\n",
+            self.hostname.as_deref().unwrap_or("-"),
+            self.os,
+            self.os_family,
+            self.tool_version,
+            self.exported_at
+        )
+    }
+}
+
+/// JSON/YAML layout of an export carrying metadata.
+#[derive(Serialize)]
+struct WithMetadata<'a, T> {
+    metadata: ExportMetadata,
+    data: &'a [T],
+}
+
+/// Serializes entries as pretty JSON, wrapped as `{ metadata, data }` with `with_metadata`.
+/// Shared by file exports and copying rows to the clipboard.
+pub fn to_json<T: Serialize>(
+    data: &[T],
+    with_metadata: bool,
+    time: &TimeFormat,
+) -> io::Result<String> {
+    json_string(data, with_metadata.then(|| ExportMetadata::collect(time)))
+}
+
+fn json_string<T: Serialize>(data: &[T], metadata: Option<ExportMetadata>) -> io::Result<String> {
+    match metadata {
+        Some(metadata) => serde_json::to_string_pretty(&WithMetadata { metadata, data }),
+        None => serde_json::to_string_pretty(data),
+    }
+    .map_err(io::Error::other)
+}
 
 /// Writes any serializable entries to a JSON/YAML/CSV file under the `/snapshots` folder.
+/// With `with_metadata`, JSON/YAML become `{ metadata, data }` and CSV gets a leading `#` line.
+/// The file name always carries a `%Y%m%d-%H%M%S` stamp so the snapshot browser can read it back.
 pub fn export_to_file<T: Serialize>(
     data: &[T],
     format: ExportFormat,
     file_prefix: &str,
     output_dir: Option<&PathBuf>,
-    write_csv_fn: Option<fn(&mut dyn Write, &[T]) -> io::Result<()>>,
+    write_csv_fn: Option<CsvWriteFn<T>>,
+    with_metadata: bool,
+    time: &TimeFormat,
 ) -> io::Result<PathBuf> {
     let base_dir = output_dir.cloned().unwrap_or_else(|| PathBuf::from("."));
     let snapshots_dir = base_dir.join("snapshots");
     std::fs::create_dir_all(&snapshots_dir)?;
 
-    let ts = Local::now().format("%Y%m%d-%H%M%S").to_string();
+    let ts = time.format_with(Utc::now(), "%Y%m%d-%H%M%S");
     let ext = match format {
         ExportFormat::Csv => "csv",
         ExportFormat::Json => "json",
@@ -29,11 +98,15 @@ pub fn export_to_file<T: Serialize>(
     let filename = format!("{file_prefix}-{ts}.{ext}");
     let path = snapshots_dir.join(filename);
     let mut file = File::create(&path)?;
+    let metadata = with_metadata.then(|| ExportMetadata::collect(time));
 
     match format {
         ExportFormat::Csv => {
             if let Some(write_fn) = write_csv_fn {
-                write_fn(&mut file, data)?;
+                if let Some(metadata) = &metadata {
+                    file.write_all(metadata.csv_comment().as_bytes())?;
+                }
+                write_fn(&mut file, data, time)?;
             } else {
                 return Err(io::Error::new(
                     io::ErrorKind::InvalidInput,
@@ -42,13 +115,15 @@ pub fn export_to_file<T: Serialize>(
             }
         }
         ExportFormat::Json => {
-            let json = serde_json::to_string_pretty(data)
-                .map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
+            let json = json_string(data, metadata)?;
             file.write_all(json.as_bytes())?;
         }
         ExportFormat::Yaml => {
-            let yaml =
-                serde_yaml::to_string(data).map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
+            let yaml = match metadata {
+                Some(metadata) => serde_yaml::to_string(&WithMetadata { metadata, data }),
+                None => serde_yaml::to_string(data),
+            }
+            .map_err(io::Error::other)?;
             file.write_all(yaml.as_bytes())?;
         }
     }
@@ -0,0 +1,146 @@
+use crate::model::{KillProcessResponse, PortInfo};
+
+use chrono::{DateTime, Utc};
+use csv::Writer;
+use serde::{Deserialize, Serialize};
+
+use crate::portwatch::{
+    ExportFormat,
+    export::export_to_file,
+    time_format::{RFC3339, TimeFormat},
+};
+use std::{
+    io::{Result, Write},
+    path::PathBuf,
+};
+
+/// One kill carried out during the session, successful or not
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct KillRecord {
+    pub timestamp: DateTime<Utc>,
+    pub pid: u32,
+    pub port: u16,
+    pub process_name: String,
+    pub process_path: String,
+    /// How the process was asked to exit, e.g. `SIGTERM` or `SIGTERM (elevated)`
+    pub signal: String,
+    pub success: bool,
+    /// Outcome as reported by the OS
+    pub message: String,
+}
+
+/// What the operator terminated and when, kept apart from the port events of
+/// the [`Tracker`](crate::portwatch::tracker::Tracker) so it can be exported on its own.
+#[derive(Debug, Default)]
+pub struct KillLog {
+    pub records: Vec<KillRecord>,
+}
+
+impl KillLog {
+    /// Adds the outcome of killing `target`.
+    pub fn record(&mut self, target: &PortInfo, signal: &str, response: &KillProcessResponse) {
+        self.records.push(KillRecord {
+            timestamp: Utc::now(),
+            pid: target.pid,
+            port: target.port,
+            process_name: target.process_name.clone(),
+            process_path: target.process_path.clone(),
+            signal: signal.to_string(),
+            success: response.success,
+            message: response.message.clone(),
+        });
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    /// Writes every record to `kills-<timestamp>.<ext>` in the snapshots folder.
+    pub fn export(
+        &self,
+        format: ExportFormat,
+        output_dir: Option<&PathBuf>,
+        with_metadata: bool,
+        time: &TimeFormat,
+    ) -> Result<PathBuf> {
+        export_to_file(
+            &self.records,
+            format,
+            "kills",
+            output_dir,
+            Some(write_kills_csv),
+            with_metadata,
+            time,
+        )
+    }
+}
+
+fn write_kills_csv(file: &mut dyn Write, records: &[KillRecord], time: &TimeFormat) -> Result<()> {
+    let mut wtr = Writer::from_writer(file);
+    wtr.write_record([
+        "timestamp",
+        "pid",
+        "port",
+        "process_name",
+        "process_path",
+        "signal",
+        "success",
+        "message",
+    ])?;
+    for r in records {
+        wtr.write_record([
+            time.format(r.timestamp, RFC3339),
+            r.pid.to_string(),
+            r.port.to_string(),
+            r.process_name.clone(),
+            r.process_path.clone(),
+            r.signal.clone(),
+            r.success.to_string(),
+            r.message.clone(),
+        ])?;
+    }
+    wtr.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::portwatch::time_format::Timezone;
+
+    #[test]
+    fn records_every_kill_as_a_csv_row() {
+        let target = PortInfo {
+            id: "20-3000".into(),
+            port: 3000,
+            pid: 20,
+            ppid: 1,
+            process_name: "node".into(),
+            process_path: "/usr/bin/node".into(),
+            user: "alice".into(),
+            port_state: crate::model::ProcessPortState::Hosting,
+            ..PortInfo::default()
+        };
+        let mut log = KillLog::default();
+        log.record(
+            &target,
+            "SIGTERM",
+            &KillProcessResponse {
+                success: false,
+                access_denied: true,
+                message: "Operation not permitted".into(),
+            },
+        );
+        log.records[0].timestamp = "2025-03-14T09:26:53Z".parse().unwrap();
+
+        let mut csv = Vec::new();
+        let time = TimeFormat::new(None, Timezone::Utc).unwrap();
+        write_kills_csv(&mut csv, &log.records, &time).unwrap();
+        let csv = String::from_utf8(csv).unwrap();
+        assert_eq!(
+            csv.lines().nth(1),
+            Some(
+                "2025-03-14T09:26:53+00:00,20,3000,node,/usr/bin/node,SIGTERM,false,Operation not permitted"
+            )
+        );
+    }
+}
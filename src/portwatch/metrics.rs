@@ -0,0 +1,80 @@
+use crate::model::{PortInfo, ProcessPortState, TransportProtocol};
+
+use std::{
+    fmt::Write as _,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+/// Port tallies in the Prometheus exposition format, one gauge per protocol
+/// and state so absent combinations still report `0`.
+pub fn prometheus_metrics(ports: &[PortInfo]) -> String {
+    let mut out = String::new();
+    out.push_str("# HELP harboor_ports_total Open sockets by transport protocol and state.\n");
+    out.push_str("# TYPE harboor_ports_total gauge\n");
+
+    for (protocol, protocol_label) in [
+        (TransportProtocol::Tcp, "tcp"),
+        (TransportProtocol::Udp, "udp"),
+    ] {
+        for (state, state_label) in [
+            (ProcessPortState::Hosting, "listening"),
+            (ProcessPortState::Using, "established"),
+        ] {
+            let count = ports
+                .iter()
+                .filter(|p| p.protocol == protocol && p.port_state == state)
+                .count();
+            let _ = writeln!(
+                out,
+                "harboor_ports_total{{protocol=\"{}\",state=\"{}\"}} {}",
+                protocol_label, state_label, count
+            );
+        }
+    }
+    out
+}
+
+/// Writes the metrics for node_exporter's textfile collector. The file is
+/// renamed into place so the collector never reads a half-written file.
+pub fn write_prometheus_textfile(ports: &[PortInfo], path: &Path) -> io::Result<()> {
+    let mut tmp = PathBuf::from(path);
+    tmp.as_mut_os_string().push(".tmp");
+    fs::write(&tmp, prometheus_metrics(ports))?;
+    fs::rename(&tmp, path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn port(protocol: TransportProtocol, state: ProcessPortState) -> PortInfo {
+        PortInfo {
+            port: 80,
+            pid: 1,
+            process_name: "nginx".into(),
+            port_state: state,
+            protocol,
+            ..PortInfo::default()
+        }
+    }
+
+    #[test]
+    fn counts_every_protocol_and_state() {
+        let ports = [
+            port(TransportProtocol::Tcp, ProcessPortState::Hosting),
+            port(TransportProtocol::Tcp, ProcessPortState::Hosting),
+            port(TransportProtocol::Udp, ProcessPortState::Using),
+        ];
+        let metrics = prometheus_metrics(&ports);
+
+        assert!(metrics.contains("# TYPE harboor_ports_total gauge\n"));
+        assert!(metrics.contains("harboor_ports_total{protocol=\"tcp\",state=\"listening\"} 2\n"));
+        assert!(
+            metrics.contains("harboor_ports_total{protocol=\"tcp\",state=\"established\"} 0\n")
+        );
+        assert!(
+            metrics.contains("harboor_ports_total{protocol=\"udp\",state=\"established\"} 1\n")
+        );
+    }
+}
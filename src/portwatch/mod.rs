@@ -1,6 +1,10 @@
 mod common;
-pub use common::ExportFormat;
+pub use common::{ExportFormat, PortEvent};
 
-pub(crate) mod export;
-pub(crate) mod snapshot;
-pub(crate) mod tracker;
+pub mod compare;
+pub mod export;
+pub mod kill_log;
+pub mod metrics;
+pub mod snapshot;
+pub mod time_format;
+pub mod tracker;
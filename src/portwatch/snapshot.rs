@@ -1,17 +1,73 @@
-use crate::model::PortInfo;
+use crate::model::{AddressFamily, PortInfo, PortSource, ProcessPortState, os};
 
-use crate::portwatch::{ExportFormat, export::export_to_file};
+use crate::portwatch::{ExportFormat, export::export_to_file, time_format::TimeFormat};
 
-use csv::Writer;
+use chrono::NaiveDateTime;
+use csv::{ReaderBuilder, Writer};
+use serde::Deserialize;
 use std::{
-    io::{Result, Write},
-    path::PathBuf,
+    fs,
+    io::{Error, ErrorKind, Read, Result, Write},
+    path::{Path, PathBuf},
+    time::SystemTime,
 };
 
+/// A file found in the `snapshots` folder.
+#[derive(Debug, Clone)]
+pub struct SnapshotFile {
+    pub path: PathBuf,
+    /// Export time parsed from the `<prefix>-%Y%m%d-%H%M%S.<ext>` file name
+    pub taken_at: Option<NaiveDateTime>,
+    pub size: u64,
+    pub modified: SystemTime,
+}
+
+impl SnapshotFile {
+    pub fn name(&self) -> String {
+        self.path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default()
+    }
+
+    /// File extension, e.g. `json`
+    pub fn format(&self) -> &str {
+        self.path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .unwrap_or("")
+    }
+
+    /// Whether the file is a port snapshot that [`load_snapshot`] can read.
+    pub fn is_loadable(&self) -> bool {
+        is_loadable_snapshot(&self.path)
+    }
+
+    fn parse_timestamp(path: &Path) -> Option<NaiveDateTime> {
+        let stem = path.file_stem()?.to_str()?;
+        let ts = stem.get(stem.len().checked_sub(15)?..)?;
+        NaiveDateTime::parse_from_str(ts, "%Y%m%d-%H%M%S").ok()
+    }
+}
+
+/// Whether `path` names a port snapshot that [`load_snapshot`] can read:
+/// a `ports-` export in JSON, YAML or CSV.
+pub fn is_loadable_snapshot(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| name.starts_with("ports-"))
+        && matches!(
+            path.extension().and_then(|ext| ext.to_str()),
+            Some("json" | "yaml" | "yml" | "csv")
+        )
+}
+
 pub fn export_snapshot(
     entries: &[PortInfo],
     format: ExportFormat,
     output_dir: Option<&PathBuf>,
+    with_metadata: bool,
+    time: &TimeFormat,
 ) -> Result<PathBuf> {
     export_to_file(
         entries,
@@ -19,20 +75,262 @@ pub fn export_snapshot(
         "ports",
         output_dir,
         Some(write_snapshot_csv),
+        with_metadata,
+        time,
     )
 }
 
-fn write_snapshot_csv(file: &mut dyn Write, entries: &[PortInfo]) -> Result<()> {
+/// Exports a single process, naming the file after its PID and port,
+/// e.g. `ports-pid1234-port8080-<timestamp>.json`.
+pub fn export_process_snapshot(
+    entry: &PortInfo,
+    format: ExportFormat,
+    output_dir: Option<&PathBuf>,
+    with_metadata: bool,
+    time: &TimeFormat,
+) -> Result<PathBuf> {
+    export_to_file(
+        std::slice::from_ref(entry),
+        format,
+        &format!("ports-pid{}-port{}", entry.pid, entry.port),
+        output_dir,
+        Some(write_snapshot_csv),
+        with_metadata,
+        time,
+    )
+}
+
+fn write_snapshot_csv(file: &mut dyn Write, entries: &[PortInfo], _: &TimeFormat) -> Result<()> {
     let mut wtr = Writer::from_writer(file);
-    wtr.write_record(&["Port", "PID", "Process Name", "Process Path", "State"])?;
+    wtr.write_record([
+        "Port",
+        "PID",
+        "PPID",
+        "Process Name",
+        "Process Path",
+        "User",
+        "State",
+    ])?;
     for p in entries {
-        wtr.write_record(&[
+        wtr.write_record([
             p.port.to_string(),
             p.pid.to_string(),
+            p.ppid.to_string(),
             p.process_name.clone(),
             p.process_path.clone(),
+            p.user.clone(),
             format!("{:?}", p.port_state),
         ])?;
     }
     wtr.flush()
 }
+
+/// One line of a CSV snapshot. `PPID` and `User` are optional, older exports lack them.
+#[derive(Deserialize)]
+struct CsvRow {
+    #[serde(rename = "Port")]
+    port: u16,
+    #[serde(rename = "PID")]
+    pid: u32,
+    #[serde(rename = "PPID", default)]
+    ppid: u32,
+    #[serde(rename = "Process Name")]
+    process_name: String,
+    #[serde(rename = "Process Path")]
+    process_path: String,
+    #[serde(rename = "User", default)]
+    user: String,
+    #[serde(rename = "State")]
+    state: ProcessPortState,
+}
+
+impl From<CsvRow> for PortInfo {
+    fn from(row: CsvRow) -> Self {
+        // CSV exports carry no protocol or family, so the defaults apply
+        let address_family = AddressFamily::default();
+        PortInfo {
+            id: os::port_id(row.pid, row.port, &row.process_name, address_family),
+            port: row.port,
+            pid: row.pid,
+            ppid: row.ppid,
+            process_name: row.process_name,
+            process_path: row.process_path,
+            user: row.user,
+            port_state: row.state,
+            address_family,
+            ..PortInfo::default()
+        }
+    }
+}
+
+/// Reads a CSV snapshot written by [`export_snapshot`], skipping the `#` metadata line.
+pub fn read_snapshot_csv(path: &Path) -> Result<Vec<PortInfo>> {
+    parse_snapshot_csv(fs::File::open(path)?)
+}
+
+fn parse_snapshot_csv(reader: impl Read) -> Result<Vec<PortInfo>> {
+    ReaderBuilder::new()
+        .comment(Some(b'#'))
+        .from_reader(reader)
+        .deserialize::<CsvRow>()
+        .map(|row| row.map(PortInfo::from).map_err(Error::other))
+        .collect()
+}
+
+/// A snapshot file, with or without the metadata header.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum SnapshotContent {
+    Plain(Vec<PortInfo>),
+    WithMetadata { data: Vec<PortInfo> },
+}
+
+impl From<SnapshotContent> for Vec<PortInfo> {
+    fn from(content: SnapshotContent) -> Self {
+        match content {
+            SnapshotContent::Plain(data) | SnapshotContent::WithMetadata { data } => data,
+        }
+    }
+}
+
+/// Reads a snapshot previously written by [`export_snapshot`] in JSON, YAML or CSV format.
+pub fn load_snapshot(path: &Path) -> Result<Vec<PortInfo>> {
+    if path.extension().is_some_and(|ext| ext == "csv") {
+        return read_snapshot_csv(path);
+    }
+    let content = fs::read_to_string(path)?;
+    let content: SnapshotContent = match path.extension().and_then(|ext| ext.to_str()) {
+        Some("json") => serde_json::from_str(&content).map_err(Error::other)?,
+        Some("yaml") | Some("yml") => serde_yaml::from_str(&content).map_err(Error::other)?,
+        _ => {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("Unsupported snapshot format: {}", path.display()),
+            ));
+        }
+    };
+    Ok(content.into())
+}
+
+/// Serves the ports of a saved snapshot instead of the running system,
+/// e.g. to explore a state captured on another machine.
+#[derive(Debug)]
+pub struct SnapshotPortSource {
+    ports: Vec<PortInfo>,
+}
+
+impl SnapshotPortSource {
+    /// Reads the snapshot once, see [`load_snapshot`] for the accepted formats
+    pub fn open(path: &Path) -> Result<Self> {
+        Ok(Self {
+            ports: load_snapshot(path)?,
+        })
+    }
+}
+
+impl PortSource for SnapshotPortSource {
+    fn fetch(&self) -> std::result::Result<Vec<PortInfo>, String> {
+        Ok(self.ports.clone())
+    }
+
+    fn is_live(&self) -> bool {
+        false
+    }
+}
+
+/// Lists snapshot files under `<base>/snapshots`, newest first.
+pub fn list_snapshots(output_dir: Option<&PathBuf>) -> Result<Vec<PathBuf>> {
+    let base_dir = output_dir.cloned().unwrap_or_else(|| PathBuf::from("."));
+    let mut files: Vec<_> = fs::read_dir(base_dir.join("snapshots"))?
+        .flatten()
+        .filter(|entry| {
+            entry
+                .file_name()
+                .to_str()
+                .is_some_and(|name| name.starts_with("ports-"))
+        })
+        .map(|entry| entry.path())
+        .collect();
+    files.sort();
+    files.reverse();
+    Ok(files)
+}
+
+/// Lists every file under `<base>/snapshots`, most recently modified first.
+pub fn browse_snapshots(output_dir: Option<&PathBuf>) -> Result<Vec<SnapshotFile>> {
+    let base_dir = output_dir.cloned().unwrap_or_else(|| PathBuf::from("."));
+    let mut files: Vec<_> = fs::read_dir(base_dir.join("snapshots"))?
+        .flatten()
+        .filter_map(|entry| {
+            let metadata = entry.metadata().ok().filter(|m| m.is_file())?;
+            let path = entry.path();
+            Some(SnapshotFile {
+                taken_at: SnapshotFile::parse_timestamp(&path),
+                size: metadata.len(),
+                modified: metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH),
+                path,
+            })
+        })
+        .collect();
+    files.sort_by_key(|file| std::cmp::Reverse(file.modified));
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::portwatch::export::to_json;
+
+    #[test]
+    fn csv_snapshots_round_trip() {
+        let ports = vec![
+            PortInfo::fixture(3000, 20, "node, dev", ProcessPortState::Hosting),
+            PortInfo::fixture(51000, 21, "curl", ProcessPortState::Using),
+        ];
+        let mut csv = b"# hostname=box os=linux\n".to_vec();
+        write_snapshot_csv(&mut csv, &ports, &TimeFormat::default()).unwrap();
+
+        let read = parse_snapshot_csv(csv.as_slice()).unwrap();
+        assert_eq!(read, ports);
+        assert_eq!(read[0].id, ports[0].id);
+    }
+
+    #[test]
+    fn copied_json_reads_back_as_a_snapshot() {
+        let ports = vec![PortInfo::fixture(
+            3000,
+            20,
+            "node",
+            ProcessPortState::Hosting,
+        )];
+        let time = TimeFormat::default();
+        for with_metadata in [false, true] {
+            let json = to_json(&ports, with_metadata, &time).unwrap();
+            let content: SnapshotContent = serde_json::from_str(&json).unwrap();
+            assert_eq!(Vec::<PortInfo>::from(content), ports);
+        }
+    }
+
+    #[test]
+    fn csv_without_ppid_and_user_still_loads() {
+        let csv =
+            "Port,PID,Process Name,Process Path,State\n8080,42,nginx,/usr/sbin/nginx,Hosting\n";
+        let read = parse_snapshot_csv(csv.as_bytes()).unwrap();
+        assert_eq!(read[0].port, 8080);
+        assert_eq!(read[0].ppid, 0);
+        assert_eq!(read[0].port_state, ProcessPortState::Hosting);
+
+        assert!(parse_snapshot_csv("Port,PID\nhttp,1\n".as_bytes()).is_err());
+    }
+
+    #[test]
+    fn csv_snapshots_are_loadable() {
+        let loadable =
+            |name: &str| is_loadable_snapshot(Path::new("snapshots").join(name).as_path());
+        assert!(loadable("ports-20250314-092653.json"));
+        assert!(loadable("ports-20250314-092653.yml"));
+        assert!(loadable("ports-20250314-092653.csv"));
+        assert!(!loadable("ports-20250314-092653.txt"));
+        assert!(!loadable("kills-20250314-092653.csv"));
+    }
+}
@@ -0,0 +1,74 @@
+use chrono::{DateTime, Local, Utc, format::StrftimeItems};
+use serde::{Deserialize, Serialize};
+
+/// RFC 3339 as a strftime pattern, the default for machine-readable timestamps
+pub const RFC3339: &str = "%+";
+
+/// Clock that timestamps are written in
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Timezone {
+    /// The machine's wall clock
+    #[default]
+    Local,
+    Utc,
+}
+
+/// How timestamps are written in exports and shown in the footer. Every call site
+/// formats through [`TimeFormat::format`] so they all follow the same settings.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TimeFormat {
+    /// strftime pattern used everywhere instead of each call site's own default
+    pub pattern: Option<String>,
+    pub timezone: Timezone,
+}
+
+impl TimeFormat {
+    /// Checks `pattern` up front, since formatting with a bad one panics
+    pub fn new(pattern: Option<String>, timezone: Timezone) -> Result<Self, String> {
+        if let Some(pattern) = &pattern
+            && StrftimeItems::new(pattern).parse().is_err()
+        {
+            return Err(format!("Invalid time format \"{}\"", pattern));
+        }
+        Ok(Self { pattern, timezone })
+    }
+
+    /// Formats `time` with the configured pattern, or `default` when none is set
+    pub fn format(&self, time: DateTime<Utc>, default: &str) -> String {
+        self.format_with(time, self.pattern.as_deref().unwrap_or(default))
+    }
+
+    /// Formats `time` with a fixed `pattern`, only applying the timezone.
+    /// Used where the layout must stay parseable, such as file names.
+    pub fn format_with(&self, time: DateTime<Utc>, pattern: &str) -> String {
+        match self.timezone {
+            Timezone::Local => time.with_timezone(&Local).format(pattern).to_string(),
+            Timezone::Utc => time.format(pattern).to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn custom_pattern_replaces_the_default() {
+        let time = Utc.with_ymd_and_hms(2025, 3, 14, 9, 26, 53).unwrap();
+
+        let utc = TimeFormat::new(None, Timezone::Utc).unwrap();
+        assert_eq!(utc.format(time, RFC3339), "2025-03-14T09:26:53+00:00");
+        assert_eq!(utc.format(time, "%H:%M:%S"), "09:26:53");
+
+        let custom = TimeFormat::new(Some("%d/%m %H:%M".into()), Timezone::Utc).unwrap();
+        assert_eq!(custom.format(time, RFC3339), "14/03 09:26");
+        assert_eq!(custom.format_with(time, "%Y%m%d"), "20250314");
+    }
+
+    #[test]
+    fn rejects_invalid_patterns() {
+        assert!(TimeFormat::new(Some("%Q".into()), Timezone::Local).is_err());
+    }
+}
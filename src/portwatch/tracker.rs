@@ -3,11 +3,17 @@ use crate::model::PortInfo;
 use chrono::{DateTime, Utc};
 use csv::Writer;
 
-use crate::portwatch::{ExportFormat, common::PortEvent, export::export_to_file};
+use crate::portwatch::{
+    ExportFormat,
+    common::PortEvent,
+    export::export_to_file,
+    time_format::{RFC3339, TimeFormat},
+};
 use std::{
     collections::HashSet,
+    fs::{File, OpenOptions},
     io::{Result, Write},
-    path::PathBuf,
+    path::{Path, PathBuf},
 };
 
 #[derive(Debug, Default)]
@@ -17,6 +23,12 @@ pub struct Tracker {
     pub started_at: Option<DateTime<Utc>>,
     pub is_active: bool,
     pub export_format: ExportFormat,
+    /// JSON Lines file every new event is appended to, if enabled
+    pub stream: Option<File>,
+    /// Prepend host/OS metadata to exports
+    pub include_metadata: bool,
+    /// How timestamps are written in CSV exports
+    pub time_format: TimeFormat,
 }
 
 impl Tracker {
@@ -27,52 +39,75 @@ impl Tracker {
             started_at: None,
             is_active: false,
             export_format: ExportFormat::Json,
+            stream: None,
+            include_metadata: false,
+            time_format: TimeFormat::default(),
         }
     }
 
+    /// Appends every future event as one JSON object per line to `path`.
+    pub fn enable_streaming(&mut self, path: &Path) -> Result<()> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        self.stream = Some(file);
+        Ok(())
+    }
+
     /// Starts the tracker and takes a baseline snapshot of current ports.
     pub fn start(&mut self, current_ports: Vec<PortInfo>) {
         self.started_at = Some(Utc::now());
         self.is_active = true;
         self.events.clear();
         self.baseline = current_ports.clone();
-        self.events.push(PortEvent::InitialState {
+        self.record(PortEvent::InitialState {
             timestamp: Utc::now(),
             ports: current_ports,
         });
     }
 
-    /// Stops the tracker and immediately exports all collected events as JSON.
-    pub fn stop(&mut self) {
+    /// Stops the tracker and immediately exports all collected events.
+    pub fn stop(&mut self) -> Result<PathBuf> {
         self.is_active = false;
-        match self.export(None) {
-            _ => {}
-        }
+        self.export(None)
     }
 
-    /// Tracks differences between the baseline and current state.
-    pub fn track_once(&mut self, current_ports: Vec<PortInfo>) {
+    /// Tracks differences between the baseline and current state,
+    /// returning the events recorded by this call.
+    pub fn track_once(&mut self, current_ports: Vec<PortInfo>) -> &[PortEvent] {
+        let first_new = self.events.len();
         if !self.is_active {
-            return;
+            return &self.events[first_new..];
         }
 
         let (added, removed) = Self::diff_ports(&self.baseline, &current_ports);
 
         for port in added {
-            self.events.push(PortEvent::PortOpened {
+            self.record(PortEvent::PortOpened {
                 timestamp: Utc::now(),
                 port,
             });
         }
 
         for port in removed {
-            self.events.push(PortEvent::PortClosed {
+            self.record(PortEvent::PortClosed {
                 timestamp: Utc::now(),
                 port,
             });
         }
 
         self.baseline = current_ports;
+        &self.events[first_new..]
+    }
+
+    /// Stores an event and, when streaming, writes it out straight away.
+    fn record(&mut self, event: PortEvent) {
+        if let Some(file) = self.stream.as_mut()
+            && let Ok(mut line) = serde_json::to_string(&event)
+        {
+            line.push('\n');
+            // Streaming is best effort; the in-memory events are still exported on stop
+            let _ = file.write_all(line.as_bytes());
+        }
+        self.events.push(event);
     }
 
     pub fn export(&self, output_dir: Option<&PathBuf>) -> Result<PathBuf> {
@@ -82,12 +117,18 @@ impl Tracker {
             "changes",
             output_dir,
             Some(Self::write_events_csv),
+            self.include_metadata,
+            &self.time_format,
         )
     }
 
-    fn write_events_csv(file: &mut dyn Write, events: &[PortEvent]) -> Result<()> {
+    fn write_events_csv(
+        file: &mut dyn Write,
+        events: &[PortEvent],
+        time: &TimeFormat,
+    ) -> Result<()> {
         let mut wtr = Writer::from_writer(file);
-        wtr.write_record(&[
+        wtr.write_record([
             "timestamp",
             "event",
             "port",
@@ -100,8 +141,8 @@ impl Tracker {
             match event {
                 PortEvent::InitialState { timestamp, ports } => {
                     for p in ports {
-                        wtr.write_record(&[
-                            timestamp.to_rfc3339(),
+                        wtr.write_record([
+                            time.format(*timestamp, RFC3339),
                             "initial_state".parse().unwrap(),
                             p.port.to_string(),
                             p.pid.to_string(),
@@ -111,8 +152,8 @@ impl Tracker {
                     }
                 }
                 PortEvent::PortOpened { timestamp, port } => {
-                    wtr.write_record(&[
-                        timestamp.to_rfc3339(),
+                    wtr.write_record([
+                        time.format(*timestamp, RFC3339),
                         "port_opened".parse().unwrap(),
                         port.port.to_string(),
                         port.pid.to_string(),
@@ -121,8 +162,8 @@ impl Tracker {
                     ])?;
                 }
                 PortEvent::PortClosed { timestamp, port } => {
-                    wtr.write_record(&[
-                        timestamp.to_rfc3339(),
+                    wtr.write_record([
+                        time.format(*timestamp, RFC3339),
                         "port_closed".parse().unwrap(),
                         port.port.to_string(),
                         port.pid.to_string(),
@@ -136,8 +177,8 @@ impl Tracker {
         wtr.flush()
     }
 
-    /// Internal helper to compute diff between two sets of ports.
-    fn diff_ports(old: &[PortInfo], new: &[PortInfo]) -> (Vec<PortInfo>, Vec<PortInfo>) {
+    /// Computes which ports were added and removed between two sets of ports.
+    pub fn diff_ports(old: &[PortInfo], new: &[PortInfo]) -> (Vec<PortInfo>, Vec<PortInfo>) {
         let old_set: HashSet<_> = old.iter().cloned().collect();
         let new_set: HashSet<_> = new.iter().cloned().collect();
 
@@ -0,0 +1,101 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+/// Fewest seconds allowed between automatic refreshes
+pub const MIN_INTERVAL_SECS: u64 = 1;
+/// Most seconds allowed between automatic refreshes
+pub const MAX_INTERVAL_SECS: u64 = 60;
+/// Seconds between automatic refreshes unless configured otherwise
+pub const DEFAULT_INTERVAL_SECS: u64 = 2;
+
+/// Refresh settings shared between the UI and the background thread fetching ports.
+/// Clones share the same flags, so a change in the UI applies to the next wait.
+#[derive(Debug, Clone)]
+pub struct AppState {
+    /// Whether ports are fetched on their own, rather than only on request
+    monitoring: Arc<Mutex<bool>>,
+    /// Seconds between automatic fetches
+    interval: Arc<Mutex<u64>>,
+}
+
+impl Default for AppState {
+    fn default() -> Self {
+        Self {
+            monitoring: Arc::new(Mutex::new(true)),
+            interval: Arc::new(Mutex::new(DEFAULT_INTERVAL_SECS)),
+        }
+    }
+}
+
+impl AppState {
+    pub fn is_monitoring(&self) -> bool {
+        self.monitoring.lock().map(|m| *m).unwrap_or(true)
+    }
+
+    /// Starts or stops automatic fetches. Returns whether the flag changed.
+    pub fn set_monitoring(&self, monitoring: bool) -> bool {
+        match self.monitoring.lock() {
+            Ok(mut current) if *current != monitoring => {
+                *current = monitoring;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    pub fn interval_secs(&self) -> u64 {
+        self.interval
+            .lock()
+            .map(|secs| *secs)
+            .unwrap_or(DEFAULT_INTERVAL_SECS)
+    }
+
+    /// Time to wait before the next automatic fetch
+    pub fn interval(&self) -> Duration {
+        Duration::from_secs(self.interval_secs())
+    }
+
+    /// Changes the seconds between automatic fetches, rejecting values outside
+    /// [`MIN_INTERVAL_SECS`]..=[`MAX_INTERVAL_SECS`].
+    pub fn update_interval(&self, secs: u64) -> Result<(), String> {
+        if !(MIN_INTERVAL_SECS..=MAX_INTERVAL_SECS).contains(&secs) {
+            return Err(format!(
+                "Refresh interval must be {} to {} seconds, got {}",
+                MIN_INTERVAL_SECS, MAX_INTERVAL_SECS, secs
+            ));
+        }
+        if let Ok(mut interval) = self.interval.lock() {
+            *interval = secs;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn interval_stays_within_bounds() {
+        let state = AppState::default();
+        let shared = state.clone();
+        assert_eq!(shared.interval(), Duration::from_secs(2));
+
+        state.update_interval(10).unwrap();
+        assert_eq!(shared.interval_secs(), 10);
+        assert!(state.update_interval(0).is_err());
+        assert!(state.update_interval(61).is_err());
+        assert_eq!(shared.interval_secs(), 10);
+    }
+
+    #[test]
+    fn monitoring_reports_changes() {
+        let state = AppState::default();
+        assert!(state.is_monitoring());
+        assert!(!state.set_monitoring(true));
+        assert!(state.set_monitoring(false));
+        assert!(!state.clone().is_monitoring());
+    }
+}
@@ -0,0 +1,135 @@
+use crate::ui::status_component::StatusKind;
+use crate::ui::theme::TableColors;
+use crate::util::popup_area;
+
+use chrono::{DateTime, Local};
+use ratatui::{
+    Frame,
+    layout::{Constraint, Rect},
+    prelude::{Color, Style},
+    style::{Modifier, Stylize},
+    widgets::{Block, BorderType, Cell, Clear, HighlightSpacing, Row, Table, TableState},
+};
+use std::collections::VecDeque;
+
+/// Entries kept before the oldest are dropped
+const CAPACITY: usize = 200;
+
+/// One thing that happened during the session
+#[derive(Debug, Clone)]
+pub struct ActivityEntry {
+    pub at: DateTime<Local>,
+    pub kind: StatusKind,
+    pub text: String,
+}
+
+/// A popup listing recent kills, exports and audited port changes, newest first
+#[derive(Debug, Default)]
+pub struct ActivityLogComponent {
+    /// Whether the popup is displayed
+    pub display: bool,
+    /// Newest entry first
+    pub entries: VecDeque<ActivityEntry>,
+    /// Table selection state
+    pub state: TableState,
+}
+
+impl ActivityLogComponent {
+    /// Toggle display on/off, selecting the newest entry when opening
+    pub fn toggle(&mut self) {
+        self.display = !self.display;
+        if self.display {
+            self.state.select((!self.entries.is_empty()).then_some(0));
+        }
+    }
+
+    /// Adds an entry, dropping the oldest once [`CAPACITY`] is reached
+    pub fn record(&mut self, kind: StatusKind, text: impl Into<String>) {
+        if self.entries.len() == CAPACITY {
+            self.entries.pop_back();
+        }
+        self.entries.push_front(ActivityEntry {
+            at: Local::now(),
+            kind,
+            text: text.into(),
+        });
+        // Keep the highlighted entry in place while new ones arrive on top
+        if let Some(selected) = self.state.selected() {
+            self.state
+                .select(Some((selected + 1).min(self.entries.len() - 1)));
+        }
+    }
+
+    /// Move selection to an older entry
+    pub fn next_row(&mut self) {
+        if let Some(i) = self.state.selected()
+            && i + 1 < self.entries.len()
+        {
+            self.state.select(Some(i + 1));
+        }
+    }
+
+    /// Move selection to a newer entry
+    pub fn previous_row(&mut self) {
+        if let Some(i) = self.state.selected() {
+            self.state.select(Some(i.saturating_sub(1)));
+        }
+    }
+
+    /// Jump to the newest entry
+    pub fn first_row(&mut self) {
+        self.state.select((!self.entries.is_empty()).then_some(0));
+    }
+
+    /// Jump to the oldest entry
+    pub fn last_row(&mut self) {
+        self.state.select(self.entries.len().checked_sub(1));
+    }
+
+    /// Renders the popup
+    pub fn render(&mut self, frame: &mut Frame, area: Rect, colors: &TableColors) {
+        if !self.display {
+            return;
+        }
+
+        let selected_row_style = Style::default()
+            .add_modifier(Modifier::REVERSED)
+            .fg(colors.selected_row_style_fg);
+
+        let header = Row::new(["Time", "Event"])
+            .style(Style::default().fg(colors.header_fg).bg(colors.header_bg));
+
+        let rows = self.entries.iter().map(|entry| {
+            let fg = match entry.kind {
+                StatusKind::Info => colors.row_fg,
+                StatusKind::Error => Color::Red,
+            };
+            Row::new([
+                Cell::from(entry.at.format("%H:%M:%S").to_string()),
+                Cell::from(entry.text.as_str()),
+            ])
+            .style(Style::default().fg(fg))
+        });
+
+        let title = if self.entries.is_empty() {
+            " Activity — nothing yet "
+        } else {
+            " Activity "
+        };
+        let table = Table::new(rows, [Constraint::Length(8), Constraint::Min(10)])
+            .header(header)
+            .row_highlight_style(selected_row_style)
+            .bg(colors.buffer_bg)
+            .highlight_spacing(HighlightSpacing::Always)
+            .block(
+                Block::bordered()
+                    .border_type(BorderType::Plain)
+                    .border_style(Style::new().fg(colors.footer_border_color))
+                    .title(title),
+            );
+
+        let area = popup_area(area, 7, 5);
+        frame.render_widget(Clear, area);
+        frame.render_stateful_widget(table, area, &mut self.state);
+    }
+}
@@ -0,0 +1,103 @@
+use crate::ui::process_table_component::ColumnSetting;
+use crate::ui::theme::TableColors;
+use crate::util::popup_area;
+
+use ratatui::{
+    Frame,
+    layout::{Constraint, Rect},
+    prelude::Style,
+    style::{Modifier, Stylize},
+    widgets::{Block, BorderType, Cell, Clear, HighlightSpacing, Row, Table, TableState},
+};
+
+/// A popup for choosing which table columns are shown
+#[derive(Debug, Default)]
+pub struct ColumnsComponent {
+    /// Whether the popup is displayed
+    pub display: bool,
+    /// Table selection state
+    pub state: TableState,
+}
+
+impl ColumnsComponent {
+    /// Toggle display on/off, selecting the first column when opening
+    pub fn toggle(&mut self) {
+        self.display = !self.display;
+        if self.display {
+            self.state.select(Some(0));
+        }
+    }
+
+    /// Index of the highlighted column
+    pub fn selected(&self) -> Option<usize> {
+        self.state.selected()
+    }
+
+    /// Move selection down by one row
+    pub fn next_row(&mut self, len: usize) {
+        if len == 0 {
+            return;
+        }
+        let idx = match self.state.selected() {
+            Some(i) if i + 1 < len => i + 1,
+            _ => 0,
+        };
+        self.state.select(Some(idx));
+    }
+
+    /// Move selection up by one row
+    pub fn previous_row(&mut self, len: usize) {
+        if len == 0 {
+            return;
+        }
+        let idx = match self.state.selected() {
+            Some(0) | None => len - 1,
+            Some(i) => i - 1,
+        };
+        self.state.select(Some(idx));
+    }
+
+    /// Renders the popup
+    pub fn render(
+        &mut self,
+        frame: &mut Frame,
+        area: Rect,
+        colors: &TableColors,
+        columns: &[ColumnSetting],
+    ) {
+        if !self.display {
+            return;
+        }
+
+        let selected_row_style = Style::default()
+            .add_modifier(Modifier::REVERSED)
+            .fg(colors.selected_row_style_fg);
+
+        let rows = columns.iter().map(|setting| {
+            let (symbol, style) = if setting.visible {
+                ("[x]", Style::default().fg(colors.footer_border_color))
+            } else {
+                ("[ ]", Style::default().fg(colors.row_fg))
+            };
+            Row::new([
+                Cell::from(symbol).style(style),
+                Cell::from(setting.column.title()).style(Style::default().fg(colors.row_fg)),
+            ])
+        });
+
+        let table = Table::new(rows, [Constraint::Length(4), Constraint::Min(10)])
+            .row_highlight_style(selected_row_style)
+            .bg(colors.buffer_bg)
+            .highlight_spacing(HighlightSpacing::Always)
+            .block(
+                Block::bordered()
+                    .border_type(BorderType::Plain)
+                    .border_style(Style::new().fg(colors.footer_border_color))
+                    .title(" Columns — Space to show/hide "),
+            );
+
+        let area = popup_area(area, 3, 5);
+        frame.render_widget(Clear, area);
+        frame.render_stateful_widget(table, area, &mut self.state);
+    }
+}
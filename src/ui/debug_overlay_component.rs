@@ -0,0 +1,113 @@
+use crate::ui::theme::TableColors;
+
+use ratatui::{
+    Frame,
+    layout::Rect,
+    prelude::Style,
+    text::Line,
+    widgets::{Block, BorderType, Clear, Paragraph},
+};
+use std::time::Duration;
+
+/// How long one port fetch of the background thread took
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FetchTiming {
+    pub took: Duration,
+    /// Ports returned, 0 when the fetch failed
+    pub rows: usize,
+}
+
+/// A corner box with the cost of the last fetch and frame, to tell a slow
+/// `lsof` or process lookup apart from slow rendering
+#[derive(Debug, Default)]
+pub struct DebugOverlayComponent {
+    /// Whether the overlay is displayed
+    pub display: bool,
+    pub last_fetch: Option<FetchTiming>,
+    /// Time the previous `terminal.draw` took
+    pub last_render: Option<Duration>,
+}
+
+impl DebugOverlayComponent {
+    /// Toggle display on/off
+    pub fn toggle(&mut self) {
+        self.display = !self.display;
+    }
+
+    fn lines(&self) -> Vec<Line<'_>> {
+        let fetch = match self.last_fetch {
+            Some(timing) => format!("fetch  {}", format_duration(timing.took)),
+            None => "fetch  pending".to_string(),
+        };
+        let rows = match self.last_fetch {
+            Some(timing) => format!("rows   {}", timing.rows),
+            None => "rows   -".to_string(),
+        };
+        let render = match self.last_render {
+            Some(took) => format!("render {}", format_duration(took)),
+            None => "render -".to_string(),
+        };
+        vec![Line::from(fetch), Line::from(rows), Line::from(render)]
+    }
+
+    /// Renders the overlay in the top right corner of `area`
+    pub fn render(&self, frame: &mut Frame, area: Rect, colors: &TableColors) {
+        if !self.display {
+            return;
+        }
+
+        let width = 22.min(area.width);
+        let height = 5.min(area.height);
+        let corner = Rect {
+            x: area.x + area.width - width,
+            y: area.y,
+            width,
+            height,
+        };
+
+        let overlay = Paragraph::new(self.lines())
+            .style(Style::default().fg(colors.row_fg).bg(colors.buffer_bg))
+            .block(
+                Block::bordered()
+                    .border_type(BorderType::Plain)
+                    .border_style(Style::new().fg(colors.footer_border_color))
+                    .title(" Debug "),
+            );
+
+        frame.render_widget(Clear, corner);
+        frame.render_widget(overlay, corner);
+    }
+}
+
+/// Milliseconds with one decimal below 10 ms, where the decimal still matters
+fn format_duration(took: Duration) -> String {
+    let ms = took.as_secs_f64() * 1000.0;
+    if ms < 10.0 {
+        format!("{:.1} ms", ms)
+    } else {
+        format!("{:.0} ms", ms)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shows_the_last_fetch_and_frame() {
+        let mut overlay = DebugOverlayComponent::default();
+        let text =
+            |o: &DebugOverlayComponent| o.lines().iter().map(|l| l.to_string()).collect::<Vec<_>>();
+        assert_eq!(text(&overlay), ["fetch  pending", "rows   -", "render -"]);
+
+        overlay.last_fetch = Some(FetchTiming {
+            took: Duration::from_millis(1234),
+            rows: 512,
+        });
+        overlay.last_render = Some(Duration::from_micros(2500));
+        assert_eq!(
+            text(&overlay),
+            ["fetch  1234 ms", "rows   512", "render 2.5 ms"]
+        );
+    }
+}
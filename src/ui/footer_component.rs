@@ -1,5 +1,4 @@
-use crate::portwatch::ExportFormat;
-use chrono::{DateTime, Utc};
+use crate::portwatch::{time_format::TimeFormat, tracker::Tracker};
 
 use crate::ui::theme::TableColors;
 
@@ -14,15 +13,10 @@ use ratatui::{
     widgets::Paragraph,
 };
 
-#[derive(Debug)]
+#[derive(Debug, Default)]
 pub struct FooterComponent {
     pub display: bool,
 }
-impl Default for FooterComponent {
-    fn default() -> Self {
-        Self { display: false }
-    }
-}
 
 impl FooterComponent {
     pub fn toggle(&mut self) {
@@ -34,23 +28,26 @@ impl FooterComponent {
         frame: &mut Frame,
         area: Rect,
         colors: &TableColors,
-        export_format: ExportFormat,
-        is_tracking: bool,
-        started_time: Option<DateTime<Utc>>,
-        events_count: usize,
+        tracker: &Tracker,
+        paused: bool,
+        time: &TimeFormat,
     ) {
-        let started_str = started_time
-            .map(|t| t.format("%H:%M:%S").to_string())
+        let started_str = tracker
+            .started_at
+            .map(|t| time.format(t, "%H:%M:%S"))
             .unwrap_or_else(|| "-".into());
 
-        let footer_text = if is_tracking {
+        let mut footer_text = if tracker.is_active {
             Line::from(vec![
-                Span::styled(format!("{} changes", events_count), Style::default()),
+                Span::styled(
+                    format!("{} changes", tracker.events.len()),
+                    Style::default(),
+                ),
                 Span::raw(" since "),
                 Span::styled(started_str, Style::default()),
                 Span::raw(" | Format: "),
                 Span::styled(
-                    format!("{:?}", export_format),
+                    format!("{:?}", tracker.export_format),
                     Style::default().fg(colors.footer_border_color),
                 ),
                 Span::raw(" | "),
@@ -58,7 +55,7 @@ impl FooterComponent {
                 Span::raw(" Export Format  "),
                 Span::styled("[E]", Style::default()),
                 Span::raw(" Export  "),
-                Span::styled("[Ctrl+S]", Style::default()),
+                Span::styled("[S]", Style::default()),
                 Span::raw(" Stop & Export"),
             ])
         } else {
@@ -75,6 +72,21 @@ impl FooterComponent {
             ])
         };
 
+        if paused {
+            footer_text.spans.splice(
+                0..0,
+                [
+                    Span::styled(
+                        "PAUSED",
+                        Style::default()
+                            .fg(Color::Yellow)
+                            .add_modifier(Modifier::BOLD),
+                    ),
+                    Span::raw(" ([p] resume) | "),
+                ],
+            );
+        }
+
         let footer = Paragraph::new(footer_text)
             .alignment(Alignment::Center)
             .style(Style::default().fg(colors.row_fg).bg(colors.buffer_bg))
@@ -0,0 +1,76 @@
+use crate::ui::theme::TableColors;
+
+use ratatui::{
+    Frame,
+    layout::{Position, Rect},
+    prelude::Style,
+    text::{Line, Span},
+    widgets::Paragraph,
+};
+
+const PROMPT: &str = "Jump to port: ";
+
+/// A one-line prompt that moves the selection to a typed port, leaving the list unfiltered
+#[derive(Debug, Default)]
+pub struct JumpComponent {
+    /// Whether the prompt is open
+    pub display: bool,
+    /// Digits typed so far
+    pub value: String,
+    /// Row selected when the prompt opened, restored when it is cancelled
+    pub origin: Option<usize>,
+    /// Whether a row matches what was typed
+    pub found: bool,
+}
+
+impl JumpComponent {
+    /// Opens an empty prompt, remembering the current selection
+    pub fn open(&mut self, origin: Option<usize>) {
+        self.display = true;
+        self.value.clear();
+        self.origin = origin;
+        self.found = true;
+    }
+
+    /// Closes the prompt
+    pub fn close(&mut self) {
+        self.display = false;
+        self.value.clear();
+    }
+
+    /// Appends a digit, ignoring anything that cannot be part of a port
+    pub fn push(&mut self, c: char) {
+        if c.is_ascii_digit() && self.value.len() < 5 {
+            self.value.push(c);
+        }
+    }
+
+    /// Removes the last digit
+    pub fn pop(&mut self) {
+        self.value.pop();
+    }
+
+    /// Renders the prompt with the cursor after the typed digits
+    pub fn render(&self, frame: &mut Frame, area: Rect, colors: &TableColors) {
+        if !self.display {
+            return;
+        }
+
+        let hint = if self.found {
+            "  (Enter: stay, Esc: go back)"
+        } else {
+            "  no such port"
+        };
+        let line = Line::from(vec![
+            Span::styled(PROMPT, Style::default().fg(colors.footer_border_color)),
+            Span::raw(self.value.as_str()),
+            Span::styled(hint, Style::default().fg(colors.selected_row_style_fg)),
+        ]);
+        let prompt =
+            Paragraph::new(line).style(Style::default().fg(colors.row_fg).bg(colors.buffer_bg));
+        frame.render_widget(prompt, area);
+
+        let cursor = (PROMPT.len() + self.value.len()) as u16;
+        frame.set_cursor_position(Position::new(area.x + cursor, area.y));
+    }
+}
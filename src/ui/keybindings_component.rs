@@ -1,14 +1,15 @@
 use crate::ApplicationMode;
+use crate::ui::process_search_component::ProcessSearchComponent;
 use crate::ui::theme::TableColors;
 use crate::util::{center_str, popup_area};
 use ratatui::{
     Frame,
-    layout::{Constraint, Margin, Rect},
+    layout::{Constraint, Layout, Margin, Position, Rect},
     prelude::Style,
     style::{Modifier, Stylize},
     widgets::{
-        Block, BorderType, Cell, Clear, HighlightSpacing, Row, Scrollbar, ScrollbarOrientation,
-        ScrollbarState, Table, TableState,
+        Block, BorderType, Cell, Clear, HighlightSpacing, Paragraph, Row, Scrollbar,
+        ScrollbarOrientation, ScrollbarState, Table, TableState,
     },
 };
 
@@ -36,13 +37,25 @@ pub fn default_keybindings() -> Vec<KeybindingsGroup> {
                     combo: "Esc, F1, ?",
                     description: "Exit help view",
                 },
+                Keybinding {
+                    combo: "Type",
+                    description: "Filter keybindings by key or description",
+                },
                 Keybinding {
                     combo: "Up, Down",
                     description: "Navigate help entries",
                 },
                 Keybinding {
                     combo: "Pg Up, Pg Down",
-                    description: "Page through help list",
+                    description: "Page through help list (half pages with page_step: half)",
+                },
+                Keybinding {
+                    combo: "Ctrl+D, Ctrl+B",
+                    description: "Scroll half a page down/up",
+                },
+                Keybinding {
+                    combo: "Ctrl+U",
+                    description: "Clear the filter",
                 },
                 Keybinding {
                     combo: "Shift+Pg Up, Shift+Pg Down",
@@ -69,6 +82,10 @@ pub fn default_keybindings() -> Vec<KeybindingsGroup> {
                     combo: "e",
                     description: "Enter editing mode (search)",
                 },
+                Keybinding {
+                    combo: "/",
+                    description: "Jump to a port without filtering the list",
+                },
                 Keybinding {
                     combo: "Up, Down",
                     description: "Move selection in table",
@@ -77,6 +94,10 @@ pub fn default_keybindings() -> Vec<KeybindingsGroup> {
                     combo: "Pg Up, Pg Down",
                     description: "Scroll one page in table",
                 },
+                Keybinding {
+                    combo: "Left, Right",
+                    description: "Scroll table columns horizontally",
+                },
                 Keybinding {
                     combo: "1",
                     description: "Sort by Port, press again to toggle direction",
@@ -93,6 +114,46 @@ pub fn default_keybindings() -> Vec<KeybindingsGroup> {
                     combo: "4",
                     description: "Sort by Process Path, press again to toggle direction",
                 },
+                Keybinding {
+                    combo: "5",
+                    description: "Sort by Parent PID, press again to toggle direction",
+                },
+                Keybinding {
+                    combo: "6",
+                    description: "Sort by CPU usage, press again to toggle direction",
+                },
+                Keybinding {
+                    combo: "7",
+                    description: "Sort by memory usage, press again to toggle direction",
+                },
+                Keybinding {
+                    combo: "8",
+                    description: "Sort by uptime, press again to toggle direction",
+                },
+                Keybinding {
+                    combo: "9",
+                    description: "Sort by user, press again to toggle direction",
+                },
+                Keybinding {
+                    combo: "Tab",
+                    description: "Flip the sort direction of the current column",
+                },
+                Keybinding {
+                    combo: "u",
+                    description: "Show/hide CPU and memory columns",
+                },
+                Keybinding {
+                    combo: "o",
+                    description: "Show/hide process owner column",
+                },
+                Keybinding {
+                    combo: "a",
+                    description: "Show/hide remote address column",
+                },
+                Keybinding {
+                    combo: "d",
+                    description: "Toggle reverse DNS lookup of remote addresses",
+                },
                 Keybinding {
                     combo: "Shift+Pg Up, Shift+Pg Down",
                     description: "Jump to start/end of table",
@@ -102,13 +163,113 @@ pub fn default_keybindings() -> Vec<KeybindingsGroup> {
                     description: "Open kill-process confirmation for selected row",
                 },
                 Keybinding {
-                    combo: "Ctrl+S",
+                    combo: "y",
+                    description: "Copy a kill command for the selected row to the clipboard",
+                },
+                Keybinding {
+                    combo: "Y",
+                    description: "Copy every visible row to the clipboard as JSON",
+                },
+                Keybinding {
+                    combo: "r",
+                    description: "Refresh the port list now",
+                },
+                Keybinding {
+                    combo: "c",
+                    description: "Choose which columns are shown",
+                },
+                Keybinding {
+                    combo: "t",
+                    description: "Open the protocol/state quick-filter bar",
+                },
+                Keybinding {
+                    combo: "p",
+                    description: "Pause/resume automatic list updates",
+                },
+                Keybinding {
+                    combo: "+, -",
+                    description: "Refresh a second less / more often",
+                },
+                Keybinding {
+                    combo: "h",
+                    description: "Hide/show processes with unknown or restricted paths",
+                },
+                Keybinding {
+                    combo: "b",
+                    description: "Show only externally reachable ports / all ports",
+                },
+                Keybinding {
+                    combo: "n",
+                    description: "Follow / stop following ports opened while auditing",
+                },
+                Keybinding {
+                    combo: "L",
+                    description: "Show kills, exports and audited port changes",
+                },
+                Keybinding {
+                    combo: "F12",
+                    description: "Show how long the last port fetch and frame took",
+                },
+                Keybinding {
+                    combo: "i",
+                    description: "Hide / show harboor-sweep's own process",
+                },
+                Keybinding {
+                    combo: "H",
+                    description: "Hide / show kernel and system processes such as PID 0",
+                },
+                Keybinding {
+                    combo: "M",
+                    description: "Merge / split the IPv4 and IPv6 rows of one socket",
+                },
+                Keybinding {
+                    combo: "m",
+                    description: "Only show ports owned by the current user",
+                },
+                Keybinding {
+                    combo: "v",
+                    description: "Group rows by process",
+                },
+                Keybinding {
+                    combo: "l",
+                    description: "Toggle the compact one-line-per-row layout",
+                },
+                Keybinding {
+                    combo: "Shift+X",
+                    description: "Show PIDs in hex / decimal",
+                },
+                Keybinding {
+                    combo: "Enter",
+                    description: "Expand/collapse the selected process group",
+                },
+                Keybinding {
+                    combo: "x",
+                    description: "Export only the selected process (snapshot format)",
+                },
+                Keybinding {
+                    combo: "O",
+                    description: "Show the selected executable in the file manager",
+                },
+                Keybinding {
+                    combo: "F3",
+                    description: "Browse saved snapshots",
+                },
+                Keybinding {
+                    combo: "F4",
+                    description: "Compare two saved snapshots",
+                },
+                Keybinding {
+                    combo: "S, Ctrl+S",
                     description: "Start/Stop auditing of port activity (live tracking)",
                 },
                 Keybinding {
-                    combo: "Ctrl+e",
+                    combo: "E, Ctrl+e",
                     description: "Export current tracked port changes immediately",
                 },
+                Keybinding {
+                    combo: "K",
+                    description: "Export the processes killed this session",
+                },
                 Keybinding {
                     combo: "f",
                     description: "Change export format for tracked port events",
@@ -117,6 +278,26 @@ pub fn default_keybindings() -> Vec<KeybindingsGroup> {
                     combo: "Shift+Right, Shift+Left",
                     description: "Cycle through available themes",
                 },
+                Keybinding {
+                    combo: "j, k (vim_keys)",
+                    description: "Move selection down/up",
+                },
+                Keybinding {
+                    combo: "gg, G (vim_keys)",
+                    description: "Jump to start/end of table",
+                },
+                Keybinding {
+                    combo: "Ctrl+D, Ctrl+U",
+                    description: "Scroll half a page down/up",
+                },
+                Keybinding {
+                    combo: "dd (vim_keys)",
+                    description: "Open kill-process confirmation for selected row",
+                },
+                Keybinding {
+                    combo: "D (vim_keys)",
+                    description: "Toggle reverse DNS lookup of remote addresses",
+                },
             ],
         },
         KeybindingsGroup {
@@ -130,16 +311,40 @@ pub fn default_keybindings() -> Vec<KeybindingsGroup> {
                     combo: "Backspace",
                     description: "Delete character from search field",
                 },
+                Keybinding {
+                    combo: "Ctrl+U",
+                    description: "Clear search field up to the cursor",
+                },
+                Keybinding {
+                    combo: "Ctrl+W",
+                    description: "Delete previous word in search field",
+                },
+                Keybinding {
+                    combo: "Alt+C",
+                    description: "Toggle case-sensitive matching",
+                },
+                Keybinding {
+                    combo: "Tab",
+                    description: "Focus the quick-filter bar",
+                },
                 Keybinding {
                     combo: "Left, Right",
                     description: "Move cursor in search input",
                 },
                 Keybinding {
-                    combo: "Down",
+                    combo: "Up, Down",
+                    description: "Browse previous searches",
+                },
+                Keybinding {
+                    combo: "Enter",
+                    description: "Submit search and return to the table",
+                },
+                Keybinding {
+                    combo: "Shift+Down",
                     description: "Submit search and move selection down",
                 },
                 Keybinding {
-                    combo: "Up",
+                    combo: "Shift+Up",
                     description: "Submit search and move selection up",
                 },
                 Keybinding {
@@ -159,13 +364,33 @@ pub fn default_keybindings() -> Vec<KeybindingsGroup> {
                     combo: "Right",
                     description: "Select 'Cancel' action",
                 },
+                Keybinding {
+                    combo: "t",
+                    description: "Toggle killing child processes as well",
+                },
+                Keybinding {
+                    combo: "a",
+                    description: "Toggle killing every process on the selected port",
+                },
+                Keybinding {
+                    combo: "g",
+                    description: "Toggle signalling the whole process group (Unix)",
+                },
+                Keybinding {
+                    combo: "o",
+                    description: "Override the protection of a protected process",
+                },
                 Keybinding {
                     combo: "Enter",
-                    description: "Confirm selected kill/cancel action",
+                    description: "Confirm selected kill/cancel action (kills after a countdown)",
+                },
+                Keybinding {
+                    combo: "Enter (after access denied)",
+                    description: "Retry the kill as administrator (sudo/pkexec/UAC)",
                 },
                 Keybinding {
                     combo: "Esc",
-                    description: "Abort kill & close confirmation",
+                    description: "Abort kill or its countdown & close confirmation",
                 },
             ],
         },
@@ -176,6 +401,10 @@ pub fn default_keybindings() -> Vec<KeybindingsGroup> {
                     combo: "Up, Down",
                     description: "Navigate export type entries",
                 },
+                Keybinding {
+                    combo: "Tab",
+                    description: "Switch between exporting the filtered view and all ports",
+                },
                 Keybinding {
                     combo: "Left",
                     description: "Select 'Export' action",
@@ -194,6 +423,145 @@ pub fn default_keybindings() -> Vec<KeybindingsGroup> {
                 },
             ],
         },
+        KeybindingsGroup {
+            mode: ApplicationMode::Comparing,
+            bindings: vec![
+                Keybinding {
+                    combo: "Up, Down",
+                    description: "Navigate snapshots or differences",
+                },
+                Keybinding {
+                    combo: "Enter",
+                    description: "Pick snapshot A, then snapshot B to compare",
+                },
+                Keybinding {
+                    combo: "Backspace",
+                    description: "Start over with a new pair of snapshots",
+                },
+                Keybinding {
+                    combo: "Esc, F4",
+                    description: "Exit snapshot comparison",
+                },
+            ],
+        },
+        KeybindingsGroup {
+            mode: ApplicationMode::Browsing,
+            bindings: vec![
+                Keybinding {
+                    combo: "Up, Down",
+                    description: "Navigate saved snapshots",
+                },
+                Keybinding {
+                    combo: "Pg Up, Pg Down",
+                    description: "Page through saved snapshots",
+                },
+                Keybinding {
+                    combo: "Shift+Pg Up, Shift+Pg Down",
+                    description: "Jump to newest/oldest snapshot",
+                },
+                Keybinding {
+                    combo: "Enter",
+                    description: "Open snapshot in the compare view",
+                },
+                Keybinding {
+                    combo: "r",
+                    description: "Rescan the snapshots folder",
+                },
+                Keybinding {
+                    combo: "Esc, F3",
+                    description: "Exit snapshot browser",
+                },
+            ],
+        },
+        KeybindingsGroup {
+            mode: ApplicationMode::Columns,
+            bindings: vec![
+                Keybinding {
+                    combo: "Up, Down",
+                    description: "Navigate columns",
+                },
+                Keybinding {
+                    combo: "Space, Enter",
+                    description: "Show/hide the selected column (saved to config)",
+                },
+                Keybinding {
+                    combo: "Esc, c",
+                    description: "Close column settings",
+                },
+            ],
+        },
+        KeybindingsGroup {
+            mode: ApplicationMode::Filtering,
+            bindings: vec![
+                Keybinding {
+                    combo: "Left, Right",
+                    description: "Move between TCP/UDP/IPv4/IPv6/Listening/Established",
+                },
+                Keybinding {
+                    combo: "Space, Enter",
+                    description: "Show/hide ports of the focused kind",
+                },
+                Keybinding {
+                    combo: "Tab",
+                    description: "Back to the search box (when open)",
+                },
+                Keybinding {
+                    combo: "Esc, t",
+                    description: "Leave the quick-filter bar",
+                },
+            ],
+        },
+        KeybindingsGroup {
+            mode: ApplicationMode::Jumping,
+            bindings: vec![
+                Keybinding {
+                    combo: "0-9",
+                    description: "Select the first row with that port as you type",
+                },
+                Keybinding {
+                    combo: "Enter",
+                    description: "Keep the selection and close the prompt",
+                },
+                Keybinding {
+                    combo: "Esc",
+                    description: "Return to the previously selected row",
+                },
+            ],
+        },
+        KeybindingsGroup {
+            mode: ApplicationMode::Activity,
+            bindings: vec![
+                Keybinding {
+                    combo: "Up, Down",
+                    description: "Navigate entries, newest first",
+                },
+                Keybinding {
+                    combo: "Home, End",
+                    description: "Jump to newest/oldest entry",
+                },
+                Keybinding {
+                    combo: "Esc, L",
+                    description: "Close the activity log",
+                },
+            ],
+        },
+        KeybindingsGroup {
+            mode: ApplicationMode::Quitting,
+            bindings: vec![
+                Keybinding {
+                    combo: "Left, Right",
+                    description: "Select 'Export & quit', 'Quit without saving' or 'Cancel'",
+                },
+                Keybinding {
+                    combo: "Enter",
+                    description: "Confirm the selected choice",
+                },
+                Keybinding {
+                    combo: "Esc",
+                    description: "Keep auditing and stay in the app",
+                },
+            ],
+        },
     ]
 }
 /// Internal helper: either a section‐header or an actual keybinding entry
@@ -216,6 +584,15 @@ impl KeybindingRow {
     fn is_section(&self) -> bool {
         matches!(self, KeybindingRow::Section(_))
     }
+    /// Whether the combo or description contains the already lowercased `query`
+    fn matches(&self, query: &str) -> bool {
+        match self {
+            KeybindingRow::Section(_) => false,
+            KeybindingRow::Entry { combo, description } => {
+                combo.to_lowercase().contains(query) || description.to_lowercase().contains(query)
+            }
+        }
+    }
 }
 
 /// A component that handles the help/keybindings popup
@@ -223,6 +600,10 @@ impl KeybindingRow {
 pub struct KeybindingsComponent {
     /// Flattened list of sections + entries
     items: Vec<KeybindingRow>,
+    /// Indexes into `items` matching the filter, sections included when they have matches
+    visible: Vec<usize>,
+    /// Filter input typed while the popup is open
+    pub filter: ProcessSearchComponent,
     /// Whether the popup is displayed
     pub display: bool,
     /// Table selection state
@@ -233,6 +614,8 @@ pub struct KeybindingsComponent {
     pub visible_rows: usize,
     /// Column width constraints (combo, description)
     pub col_widths: (u16, u16),
+    /// Moving past the last keybinding selects the first and vice versa, instead of stopping
+    pub wrap_selection: bool,
 }
 
 impl Default for KeybindingsComponent {
@@ -245,6 +628,13 @@ impl Default for KeybindingsComponent {
                 ApplicationMode::Editing => "---- Searching ----",
                 ApplicationMode::Killing => "---- Killing ----",
                 ApplicationMode::Snapshotting => "---- Instant Snapshot ----",
+                ApplicationMode::Comparing => "---- Compare Snapshots ----",
+                ApplicationMode::Browsing => "---- Snapshot Browser ----",
+                ApplicationMode::Columns => "---- Columns ----",
+                ApplicationMode::Filtering => "---- Quick Filters ----",
+                ApplicationMode::Jumping => "---- Jump to Port ----",
+                ApplicationMode::Activity => "---- Activity Log ----",
+                ApplicationMode::Quitting => "---- Quit ----",
             };
             items.push(KeybindingRow::Section(header));
             for kb in bindings {
@@ -256,95 +646,137 @@ impl Default for KeybindingsComponent {
         }
 
         Self {
+            visible: (0..items.len()).collect(),
             items,
+            filter: ProcessSearchComponent::default(),
             display: false,
             state: TableState::default(),
             scroll: ScrollbarState::new(1),
             visible_rows: 0,
             col_widths: (30, 70),
+            wrap_selection: true,
         }
     }
 }
 
 impl KeybindingsComponent {
-    /// Toggle display on/off, clear selection when opening
+    /// Toggle display on/off, clear selection and filter when opening
     pub fn toggle(&mut self) {
         self.display = !self.display;
         if self.display {
-            self.state.select(Some(0));
-            self.scroll = self.scroll.position(0);
+            self.filter.clear();
+            self.apply_filter();
         }
     }
 
-    /// Move selection down by one row
-    pub fn next_row(&mut self) {
-        let len = self.items.len();
-        if len == 0 {
-            return;
+    /// Recomputes the visible rows from the filter input and selects the first keybinding
+    pub fn apply_filter(&mut self) {
+        let query = self.filter.value.trim().to_lowercase();
+        self.visible.clear();
+
+        let mut section = None;
+        for (i, row) in self.items.iter().enumerate() {
+            if row.is_section() {
+                section = Some(i);
+            } else if query.is_empty() || row.matches(&query) {
+                // Only show a section header once one of its entries matches
+                if let Some(header) = section.take() {
+                    self.visible.push(header);
+                }
+                self.visible.push(i);
+            }
         }
-        let idx = match self.state.selected() {
-            Some(i) if i + 1 < len => i + 1,
-            _ => 0,
-        };
-        self.state.select(Some(idx));
-        self.scroll = self.scroll.position(idx * crate::ITEM_HEIGHT as usize);
+
+        self.scroll = self
+            .scroll
+            .content_length(self.visible.len() * crate::ITEM_HEIGHT as usize)
+            .position(0);
+        self.state.select(None);
+        self.first_row();
     }
 
-    /// Move selection up by one row
-    pub fn previous_row(&mut self) {
-        let len = self.items.len();
-        if len == 0 {
-            return;
+    /// Whether the visible row at `pos` is a keybinding rather than a section header
+    fn is_entry(&self, pos: usize) -> bool {
+        self.visible
+            .get(pos)
+            .is_some_and(|&item| !self.items[item].is_section())
+    }
+
+    /// The keybinding at or after `pos`, else the closest one before it
+    fn entry_near(&self, pos: usize) -> Option<usize> {
+        let pos = pos.min(self.visible.len().checked_sub(1)?);
+        (pos..self.visible.len())
+            .find(|&i| self.is_entry(i))
+            .or_else(|| (0..pos).rev().find(|&i| self.is_entry(i)))
+    }
+
+    /// Selects the visible row at `pos` and scrolls to it
+    fn select(&mut self, pos: Option<usize>) {
+        if let Some(pos) = pos {
+            self.state.select(Some(pos));
+            self.scroll = self.scroll.position(pos * crate::ITEM_HEIGHT as usize);
         }
-        let idx = match self.state.selected() {
-            Some(0) => len - 1,
-            Some(i) => i - 1,
-            _ => 0,
-        };
-        self.state.select(Some(idx));
-        self.scroll = self.scroll.position(idx * crate::ITEM_HEIGHT as usize);
     }
 
-    /// Jump to the first row
+    /// Move selection down to the next keybinding, wrapping to the first
+    /// unless `wrap_selection` is off
+    pub fn next_row(&mut self) {
+        let len = self.visible.len();
+        let start = self.state.selected().map_or(0, |i| i + 1);
+        let wrapped = if self.wrap_selection { 0 } else { start };
+        let next = (start..len)
+            .chain(wrapped..start.min(len))
+            .find(|&i| self.is_entry(i));
+        self.select(next);
+    }
+
+    /// Move selection up to the previous keybinding, wrapping to the last
+    /// unless `wrap_selection` is off
+    pub fn previous_row(&mut self) {
+        let len = self.visible.len();
+        let current = self.state.selected().unwrap_or(0).min(len);
+        let wrapped = if self.wrap_selection { len } else { current };
+        let previous = (0..current)
+            .rev()
+            .chain((current..wrapped).rev())
+            .find(|&i| self.is_entry(i));
+        self.select(previous);
+    }
+
+    /// Jump to the first keybinding
     pub fn first_row(&mut self) {
-        if !self.items.is_empty() {
-            self.state.select(Some(0));
-            self.scroll = self.scroll.position(0);
-        }
+        self.select(self.entry_near(0));
     }
 
-    /// Jump to the last row
+    /// Jump to the last keybinding
     pub fn last_row(&mut self) {
-        let len = self.items.len();
-        if len > 0 {
-            let last = len - 1;
-            self.state.select(Some(last));
-            self.scroll = self.scroll.position(last * crate::ITEM_HEIGHT as usize);
-        }
+        let last = (0..self.visible.len()).rev().find(|&i| self.is_entry(i));
+        self.select(last);
     }
 
     /// Page down
     pub fn page_down(&mut self) {
-        let len = self.items.len();
-        if len == 0 {
-            return;
-        }
         let current = self.state.selected().unwrap_or(0);
-        let new = (current + self.visible_rows).min(len - 1);
-        self.state.select(Some(new));
-        self.scroll = self.scroll.position(new * crate::ITEM_HEIGHT as usize);
+        self.select(self.entry_near(current + self.visible_rows));
     }
 
     /// Page up
     pub fn page_up(&mut self) {
-        let len = self.items.len();
-        if len == 0 {
-            return;
-        }
         let current = self.state.selected().unwrap_or(0);
-        let new = current.saturating_sub(self.visible_rows);
-        self.state.select(Some(new));
-        self.scroll = self.scroll.position(new * crate::ITEM_HEIGHT as usize);
+        self.select(self.entry_near(current.saturating_sub(self.visible_rows)));
+    }
+
+    /// Move down by half a page
+    pub fn half_page_down(&mut self) {
+        let current = self.state.selected().unwrap_or(0);
+        self.select(self.entry_near(current + (self.visible_rows / 2).max(1)));
+    }
+
+    /// Move up by half a page
+    pub fn half_page_up(&mut self) {
+        let current = self.state.selected().unwrap_or(0);
+        let target = current.saturating_sub((self.visible_rows / 2).max(1));
+        self.select(self.entry_near(target));
     }
 
     /// Render the keybindings popup
@@ -372,7 +804,8 @@ impl KeybindingsComponent {
         )
         .height(crate::ITEM_HEIGHT);
 
-        let rows = self.items.iter().enumerate().map(|(i, row)| {
+        let rows = self.visible.iter().enumerate().map(|(i, &item)| {
+            let row = &self.items[item];
             let [left, right] = row.cells();
 
             let is_selected = Some(i) == self.state.selected();
@@ -412,8 +845,24 @@ impl KeybindingsComponent {
                 .title(" Keybindings "),
         );
         let area = popup_area(area, 7, 5);
-
         frame.render_widget(Clear, area);
+
+        let [filter_area, area] =
+            Layout::vertical([Constraint::Length(3), Constraint::Min(1)]).areas(area);
+        let filter = Paragraph::new(self.filter.value.as_str())
+            .style(Style::default().fg(colors.row_fg).bg(colors.buffer_bg))
+            .block(
+                Block::bordered()
+                    .border_type(BorderType::Plain)
+                    .border_style(Style::new().fg(colors.footer_border_color))
+                    .title(" Filter "),
+            );
+        frame.render_widget(filter, filter_area);
+        frame.set_cursor_position(Position::new(
+            filter_area.x + self.filter.cursor_index as u16 + 1,
+            filter_area.y + 1,
+        ));
+
         frame.render_stateful_widget(table, area, &mut self.state);
 
         frame.render_stateful_widget(
@@ -429,3 +878,61 @@ impl KeybindingsComponent {
         );
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn selected(kb: &KeybindingsComponent) -> &KeybindingRow {
+        &kb.items[kb.visible[kb.state.selected().unwrap()]]
+    }
+
+    #[test]
+    fn navigation_skips_section_headers() {
+        let mut kb = KeybindingsComponent::default();
+        kb.toggle();
+        assert_eq!(kb.state.selected(), Some(1));
+
+        // Walking the whole list both ways never stops on a header
+        for _ in 0..kb.visible.len() {
+            kb.next_row();
+            assert!(!selected(&kb).is_section());
+        }
+        for _ in 0..kb.visible.len() {
+            kb.previous_row();
+            assert!(!selected(&kb).is_section());
+        }
+
+        // Up from the first entry wraps past the leading header to the last entry
+        kb.first_row();
+        kb.previous_row();
+        assert_eq!(kb.state.selected(), Some(kb.visible.len() - 1));
+        kb.next_row();
+        assert_eq!(kb.state.selected(), Some(1));
+
+        // Down from the last entry of a section lands on the first of the next
+        let header = (2..kb.visible.len())
+            .find(|&i| kb.items[kb.visible[i]].is_section())
+            .unwrap();
+        kb.select(Some(header - 1));
+        kb.next_row();
+        assert_eq!(kb.state.selected(), Some(header + 1));
+        kb.previous_row();
+        assert_eq!(kb.state.selected(), Some(header - 1));
+
+        // A page landing on a header moves on to its first entry
+        kb.visible_rows = header - 1;
+        kb.first_row();
+        kb.page_down();
+        assert_eq!(kb.state.selected(), Some(header + 1));
+
+        // Without wrapping both ends hold the selection
+        kb.wrap_selection = false;
+        kb.first_row();
+        kb.previous_row();
+        assert_eq!(kb.state.selected(), Some(1));
+        kb.last_row();
+        kb.next_row();
+        assert_eq!(kb.state.selected(), Some(kb.visible.len() - 1));
+    }
+}
@@ -2,26 +2,32 @@ use crate::model::PortInfo;
 use crate::ui::theme::TableColors;
 use crate::util::popup_area;
 
+use std::time::{Duration, Instant};
+
 use ratatui::{
     Frame,
     layout::{Constraint, Direction, Flex, Layout, Margin, Rect},
     prelude::Style,
     style::Stylize,
-    text::Line,
+    text::{Line, Span},
     widgets::{Block, BorderType, Clear, Paragraph, Wrap},
 };
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
 pub enum KillAction {
+    #[default]
     Kill,
     Cancel,
 }
 
-impl Default for KillAction {
-    fn default() -> Self {
-        KillAction::Kill
-    }
-}
+/// Explains how an elevated retry asks for privileges on this platform.
+#[cfg(target_os = "macos")]
+const ELEVATION_HINT: &str =
+    "Runs `sudo -n kill`, falling back to the macOS administrator password prompt.";
+#[cfg(target_family = "windows")]
+const ELEVATION_HINT: &str = "Runs taskkill through a UAC prompt.";
+#[cfg(all(target_family = "unix", not(target_os = "macos")))]
+const ELEVATION_HINT: &str = "Runs `sudo -n kill`, falling back to `pkexec` which asks for your password in a desktop dialog.";
 
 /// A popup component that asks “Kill process?” and lets you choose Kill/Cancel.
 
@@ -33,6 +39,24 @@ pub struct KillComponent {
     pub item: Option<PortInfo>,
     /// which button is focused
     pub action: KillAction,
+    /// whether child processes are killed along with the selected one
+    pub kill_tree: bool,
+    /// the previous attempt was denied and confirming retries with elevated privileges
+    pub elevate: bool,
+    /// distinct PIDs holding the same port as `item`
+    pub port_pids: Vec<u32>,
+    /// whether every PID in `port_pids` is killed instead of just `item`
+    pub kill_all: bool,
+    /// process group of `item`, when the platform has them
+    pub pgid: Option<u32>,
+    /// whether the signal goes to the whole process group of `item`
+    pub kill_group: bool,
+    /// a target is protected in the config and the kill is refused unless overridden
+    pub protected: bool,
+    /// the user explicitly chose to kill a protected process anyway
+    pub override_protection: bool,
+    /// the kill was confirmed and happens at this instant unless aborted
+    pub countdown_until: Option<Instant>,
 }
 
 impl Default for KillComponent {
@@ -41,6 +65,15 @@ impl Default for KillComponent {
             display: false,
             item: None,
             action: KillAction::Kill,
+            kill_tree: false,
+            elevate: false,
+            port_pids: Vec::new(),
+            kill_all: false,
+            pgid: None,
+            kill_group: false,
+            protected: false,
+            override_protection: false,
+            countdown_until: None,
         }
     }
 }
@@ -51,12 +84,48 @@ impl KillComponent {
         self.display = true;
         self.item = Some(item);
         self.action = KillAction::Kill;
+        self.elevate = false;
+    }
+
+    /// Re-open the popup offering to retry the kill with elevated privileges
+    pub fn offer_elevation(&mut self, item: PortInfo) {
+        self.show(item);
+        self.elevate = true;
+        self.kill_all = false;
+        self.kill_group = false;
     }
 
     /// Hide the popup (Cancel)
     pub fn hide(&mut self) {
         self.display = false;
         self.item = None;
+        self.countdown_until = None;
+    }
+
+    /// Delay the confirmed kill by `delay` so it can still be aborted
+    pub fn start_countdown(&mut self, delay: Duration) {
+        self.countdown_until = Some(Instant::now() + delay);
+    }
+
+    /// Whether the countdown has run out and the kill should happen now
+    pub fn countdown_due(&self) -> bool {
+        self.countdown_until
+            .is_some_and(|until| Instant::now() >= until)
+    }
+
+    /// Whole seconds left in the countdown, rounded up
+    fn countdown_secs(&self) -> Option<u64> {
+        let left = self
+            .countdown_until?
+            .saturating_duration_since(Instant::now());
+        Some(left.as_millis().div_ceil(1000) as u64)
+    }
+
+    /// When the countdown label next changes, so the UI can redraw it
+    pub fn next_tick(&self) -> Option<Instant> {
+        let until = self.countdown_until?;
+        let left = until.saturating_duration_since(Instant::now());
+        Some(until - Duration::from_secs(left.as_secs()))
     }
 
     /// Move focus left (towards Kill)
@@ -69,6 +138,39 @@ impl KillComponent {
         self.action = KillAction::Cancel;
     }
 
+    /// Toggle killing the whole process tree
+    pub fn toggle_kill_tree(&mut self) {
+        self.kill_tree = !self.kill_tree;
+    }
+
+    /// Toggle killing every process on the port, not offered for elevated retries
+    pub fn toggle_kill_all(&mut self) {
+        if !self.elevate {
+            self.kill_all = !self.kill_all;
+            self.kill_group &= !self.kill_all;
+        }
+    }
+
+    /// Toggle signalling the whole process group, offered when the group is known
+    pub fn toggle_kill_group(&mut self) {
+        if !self.elevate && self.pgid.is_some() {
+            self.kill_group = !self.kill_group;
+            self.kill_all &= !self.kill_group;
+        }
+    }
+
+    /// Toggle killing protected processes anyway
+    pub fn toggle_override_protection(&mut self) {
+        if self.protected {
+            self.override_protection = !self.override_protection;
+        }
+    }
+
+    /// Whether confirming has to be refused because of a protected target
+    pub fn blocked(&self) -> bool {
+        self.protected && !self.override_protection
+    }
+
     /// Returns true if user pressed Enter on “Kill”
     pub fn confirm(&mut self) -> bool {
         let do_kill = self.action == KillAction::Kill;
@@ -92,15 +194,16 @@ impl KillComponent {
         frame.render_widget(Clear, area);
         frame.render_widget(block, area);
 
-        // split into prompt / description / buttons
+        // split into prompt / command line / description / buttons
         let chunks = Layout::default()
             .direction(Direction::Vertical)
             .constraints(
                 [
                     Constraint::Length(2),
                     Constraint::Length(3),
+                    Constraint::Length(2),
                     Constraint::Length(3),
-                    Constraint::Min(1),
+                    Constraint::Min(3),
                     Constraint::Length(3),
                     Constraint::Length(1),
                 ]
@@ -110,6 +213,33 @@ impl KillComponent {
 
         // 1) prompt line
         let prompt = match &self.item {
+            Some(item) if self.countdown_until.is_some() => {
+                let target = if self.kill_all {
+                    format!("every process on port {}", item.port)
+                } else if let Some(pgid) = self.pgid.filter(|_| self.kill_group) {
+                    format!("process group {}", pgid)
+                } else {
+                    item.process_name.clone()
+                };
+                Paragraph::new(Line::from(format!(
+                    "Killing {} in {}… (Esc to abort)",
+                    target,
+                    self.countdown_secs().unwrap_or(0)
+                )))
+            }
+            Some(item) if self.elevate => Paragraph::new(Line::from(format!(
+                "Access denied killing {}. Retry as administrator?",
+                item.process_name
+            ))),
+            Some(item) if self.kill_all => Paragraph::new(Line::from(format!(
+                "Kill every process on port {} ?",
+                item.port
+            ))),
+            Some(item) if self.kill_group => Paragraph::new(Line::from(format!(
+                "Kill the process group of {} ({}) ?",
+                item.process_name,
+                self.pgid.unwrap_or(item.pid)
+            ))),
             Some(item) => {
                 let t = format!(
                     "Kill {} {:?} port {} ?",
@@ -130,8 +260,48 @@ impl KillComponent {
             }),
         );
 
+        // command line, telling apart processes with the same name
+        if let Some(cmdline) = self.item.as_ref().and_then(|item| item.cmdline.as_deref()) {
+            let cmdline = Paragraph::new(Line::from(cmdline))
+                .style(
+                    Style::default()
+                        .fg(colors.selected_row_style_fg)
+                        .bg(colors.buffer_bg),
+                )
+                .alignment(ratatui::layout::Alignment::Center)
+                .wrap(Wrap { trim: true });
+            frame.render_widget(
+                cmdline,
+                chunks[2].inner(Margin {
+                    horizontal: 2,
+                    vertical: 0,
+                }),
+            );
+        }
+
         // 2) description
         let desc = match &self.item {
+            Some(_) if self.elevate => Paragraph::new(Line::from(ELEVATION_HINT)),
+            Some(_) if self.blocked() => Paragraph::new(
+                Line::from(
+                    "Protected in the config (protected_processes / protected_pids). Override with o to kill it anyway.",
+                )
+                .light_red(),
+            ),
+            Some(_) if self.kill_all => {
+                let pids: Vec<String> = self.port_pids.iter().map(u32::to_string).collect();
+                Paragraph::new(Line::from(format!("PIDs to kill: {}", pids.join(", "))))
+            }
+            Some(_) if self.kill_group => Paragraph::new(Line::from(
+                "Signals every process in the group, e.g. children forked by a launch script that inherited the socket.",
+            )),
+            Some(item) if item.restricted => {
+                let s = format!(
+                    "This process could not be inspected with your current privileges. Killing it (port {}) will likely require running as administrator/root.",
+                    item.port
+                );
+                Paragraph::new(Line::from(s))
+            }
             Some(item) => {
                 let s = format!(
                     "Ending this process may disrupt services using port {}. Proceeding could result in data loss, network issues, or instability.",
@@ -146,20 +316,69 @@ impl KillComponent {
             .wrap(Wrap { trim: true });
         frame.render_widget(
             desc,
-            chunks[2].inner(Margin {
+            chunks[3].inner(Margin {
+                horizontal: 2,
+                vertical: 0,
+            }),
+        );
+
+        // 3) process tree, whole port and process group options
+        let checkbox = |checked: bool| {
+            if checked {
+                Span::styled("[x]", Style::default().fg(colors.footer_border_color))
+            } else {
+                Span::raw("[ ]")
+            }
+        };
+        let mut options = vec![Line::from(vec![
+            checkbox(self.kill_tree),
+            Span::raw(" Also kill child processes (t)"),
+        ])];
+        if !self.elevate {
+            options.push(Line::from(vec![
+                checkbox(self.kill_all),
+                Span::raw(format!(
+                    " Kill all {} processes on this port (a)",
+                    self.port_pids.len()
+                )),
+            ]));
+            if let Some(pgid) = self.pgid {
+                options.push(Line::from(vec![
+                    checkbox(self.kill_group),
+                    Span::raw(format!(" Kill process group {} (g)", pgid)),
+                ]));
+            }
+        }
+        if self.protected {
+            options.push(Line::from(vec![
+                checkbox(self.override_protection),
+                Span::raw(" Override protection (o)"),
+            ]));
+        }
+        let tree_option = Paragraph::new(options)
+            .style(Style::default().fg(colors.row_fg).bg(colors.buffer_bg))
+            .alignment(ratatui::layout::Alignment::Center);
+        frame.render_widget(
+            tree_option,
+            chunks[4].inner(Margin {
                 horizontal: 2,
                 vertical: 0,
             }),
         );
 
-        // 3) buttons
+        // 4) buttons
         let buttons = Layout::default()
             .direction(Direction::Horizontal)
             .constraints([Constraint::Ratio(1, 3), Constraint::Ratio(1, 3)])
             .flex(Flex::Center)
-            .split(chunks[4]);
+            .split(chunks[5]);
 
-        let kill_btn = Paragraph::new("Kill")
+        let kill_label = if self.elevate {
+            "Retry as admin"
+        } else {
+            "Kill"
+        };
+        let kill_btn = Paragraph::new(kill_label)
             .alignment(ratatui::layout::Alignment::Center)
             .block(if self.action == KillAction::Kill {
                 Block::bordered().border_style(Style::new().fg(colors.selected_cell_style_fg))
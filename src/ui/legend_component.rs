@@ -0,0 +1,23 @@
+use crate::ui::theme::TableColors;
+
+use ratatui::{Frame, layout::Rect, prelude::Style, widgets::Paragraph};
+
+/// A one-line recap of the active sort, search and toggles, explaining why rows may be missing
+#[derive(Debug, Default)]
+pub struct LegendComponent {
+    /// One entry per active setting, e.g. `Sort: Port ▲`
+    pub items: Vec<String>,
+}
+
+impl LegendComponent {
+    /// Renders the entries separated by bullets
+    pub fn render(&self, frame: &mut Frame, area: Rect, colors: &TableColors) {
+        let legend = Paragraph::new(self.items.join(" • ")).style(
+            Style::default()
+                .fg(colors.selected_row_style_fg)
+                .bg(colors.buffer_bg),
+        );
+
+        frame.render_widget(legend, area);
+    }
+}
@@ -1,7 +1,18 @@
+pub(crate) mod activity_log_component;
+pub(crate) mod columns_component;
+pub(crate) mod debug_overlay_component;
 pub(crate) mod footer_component;
+pub(crate) mod jump_component;
 pub(crate) mod keybindings_component;
 pub(crate) mod kill_process_component;
+pub(crate) mod legend_component;
 pub(crate) mod process_search_component;
 pub(crate) mod process_table_component;
+pub(crate) mod quick_filter_component;
+pub(crate) mod quit_component;
+pub(crate) mod snapshot_browser_component;
+pub(crate) mod snapshot_diff_component;
 pub(crate) mod snapshots_component;
+pub(crate) mod status_component;
+pub(crate) mod summary_component;
 pub(crate) mod theme;
@@ -8,8 +8,11 @@ use ratatui::{
     widgets::{Block, BorderType, Paragraph},
 };
 
+/// Maximum number of remembered search queries
+const HISTORY_LIMIT: usize = 50;
+
 /// A component that handles the search input state and rendering.
-#[derive(Debug)]
+#[derive(Debug, Default)]
 pub struct ProcessSearchComponent {
     /// Current input value
     pub value: String,
@@ -17,16 +20,14 @@ pub struct ProcessSearchComponent {
     pub cursor_index: usize,
     /// Whether the search input is displayed
     pub display: bool,
-}
-
-impl Default for ProcessSearchComponent {
-    fn default() -> Self {
-        Self {
-            value: String::new(),
-            cursor_index: 0,
-            display: false,
-        }
-    }
+    /// Match letter case exactly instead of ignoring it
+    pub case_sensitive: bool,
+    /// Previously submitted queries, oldest first
+    pub history: Vec<String>,
+    /// Position while browsing history, `None` when editing a fresh query
+    history_index: Option<usize>,
+    /// Query being typed before history browsing started
+    draft: String,
 }
 
 impl ProcessSearchComponent {
@@ -41,6 +42,11 @@ impl ProcessSearchComponent {
         self.clear();
     }
 
+    /// Switches between case-sensitive and case-insensitive matching
+    pub fn toggle_case_sensitive(&mut self) {
+        self.case_sensitive = !self.case_sensitive;
+    }
+
     /// Clamps a proposed cursor position to valid range
     fn clamp_cursor(&self, pos: usize) -> usize {
         pos.clamp(0, self.value.chars().count())
@@ -84,6 +90,81 @@ impl ProcessSearchComponent {
         }
     }
 
+    /// Deletes everything before the cursor
+    pub fn clear_to_start(&mut self) {
+        self.value = self.value.chars().skip(self.cursor_index).collect();
+        self.cursor_index = 0;
+    }
+
+    /// Deletes the word before the cursor, along with any whitespace after it
+    pub fn delete_word(&mut self) {
+        let chars: Vec<char> = self.value.chars().collect();
+        let mut start = self.cursor_index.min(chars.len());
+        while start > 0 && chars[start - 1].is_whitespace() {
+            start -= 1;
+        }
+        while start > 0 && !chars[start - 1].is_whitespace() {
+            start -= 1;
+        }
+
+        let before = chars[..start].iter();
+        let after = chars[self.cursor_index.min(chars.len())..].iter();
+        self.value = before.chain(after).collect();
+        self.cursor_index = start;
+    }
+
+    /// Records the current query in the history
+    pub fn submit(&mut self) {
+        self.history_index = None;
+        let query = self.value.trim();
+        if query.is_empty() {
+            return;
+        }
+
+        self.history.retain(|q| q != query);
+        self.history.push(query.to_string());
+        if self.history.len() > HISTORY_LIMIT {
+            self.history.remove(0);
+        }
+    }
+
+    /// Replaces the input with the previous (older) history entry
+    pub fn history_prev(&mut self) {
+        if self.history.is_empty() {
+            return;
+        }
+        let idx = match self.history_index {
+            None => {
+                self.draft = self.value.clone();
+                self.history.len() - 1
+            }
+            Some(i) => i.saturating_sub(1),
+        };
+        self.history_index = Some(idx);
+        self.set_value(self.history[idx].clone());
+    }
+
+    /// Replaces the input with the next (newer) history entry, or the draft past the end
+    pub fn history_next(&mut self) {
+        let Some(i) = self.history_index else {
+            return;
+        };
+        if i + 1 < self.history.len() {
+            self.history_index = Some(i + 1);
+            self.set_value(self.history[i + 1].clone());
+        } else {
+            self.history_index = None;
+            let draft = std::mem::take(&mut self.draft);
+            self.set_value(draft);
+        }
+    }
+
+    /// Replaces the input and moves the cursor to its end
+    fn set_value(&mut self, value: String) {
+        self.cursor_index = value.chars().count();
+        self.value = value;
+    }
+
     /// Renders the search input box
     pub fn render(
         &self,
@@ -98,7 +179,11 @@ impl ProcessSearchComponent {
                 Block::bordered()
                     .border_type(BorderType::Plain)
                     .border_style(Style::new().fg(colors.footer_border_color))
-                    .title("Search"),
+                    .title(if self.case_sensitive {
+                        "Search (case-sensitive, Alt+C)"
+                    } else {
+                        "Search (Alt+C: match case)"
+                    }),
             );
 
         frame.render_widget(input, area);
@@ -1,22 +1,443 @@
-use crate::model::PortInfo;
+use crate::model::{PortInfo, ProcessPortState, is_sensitive_service, service_name};
 use crate::ui::theme::TableColors;
+use crate::util::{format_bytes, format_duration, natural_cmp};
 
 use ratatui::widgets::ScrollbarOrientation;
 use ratatui::{
     Frame,
-    layout::{Constraint, Margin, Rect},
-    style::{Modifier, Style, Stylize},
+    layout::{Alignment, Constraint, Margin, Rect},
+    style::{Color, Modifier, Style, Stylize},
+    text::{Line, Span},
     widgets::HighlightSpacing,
-    widgets::{Cell, Row, Scrollbar, ScrollbarState, Table, TableState},
+    widgets::{Cell, Paragraph, Row, Scrollbar, ScrollbarState, Table, TableState},
 };
 
-#[derive(Debug, Copy, PartialEq, Default, Clone)]
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{HashMap, HashSet},
+    time::{Duration, Instant},
+};
+
+/// How long rows stay highlighted after their port opened or closed
+const CHANGE_HIGHLIGHT: Duration = Duration::from_secs(3);
+
+#[derive(Debug, Copy, PartialEq, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum SortBy {
     #[default]
     Port,
-    PID,
+    Pid,
+    ParentPid,
     ProcessName,
     ProcessPath,
+    User,
+    Cpu,
+    Memory,
+    Uptime,
+}
+
+impl SortBy {
+    /// Title of the column sorted by
+    pub fn label(self) -> &'static str {
+        match self {
+            SortBy::Port => "Port",
+            SortBy::Pid => "PID",
+            SortBy::ParentPid => "PPID",
+            SortBy::ProcessName => "Process Name",
+            SortBy::ProcessPath => "Process Path",
+            SortBy::User => "User",
+            SortBy::Cpu => "CPU %",
+            SortBy::Memory => "Memory",
+            SortBy::Uptime => "Uptime",
+        }
+    }
+}
+
+/// A column that can be shown in the table
+#[derive(Debug, Copy, PartialEq, Eq, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Column {
+    Port,
+    Protocol,
+    Bind,
+    Pid,
+    ParentPid,
+    ProcessName,
+    ProcessPath,
+    User,
+    Listener,
+    Remote,
+    Cpu,
+    Memory,
+    Uptime,
+    SocketInode,
+    NetNamespace,
+}
+
+/// How a column claims horizontal space
+#[derive(Debug, Copy, PartialEq, Eq, Clone)]
+pub enum ColumnWidth {
+    /// Always this many cells
+    Fixed(u16),
+    /// At least `min` cells, growing with its `weight` share of the spare width
+    Flexible { min: u16, weight: u16 },
+}
+
+/// A table column and whether it is currently shown
+#[derive(Debug, Copy, PartialEq, Eq, Clone)]
+pub struct ColumnSetting {
+    pub column: Column,
+    pub visible: bool,
+}
+
+impl Column {
+    /// Every column, in display order
+    pub const ALL: [Column; 15] = [
+        Column::Port,
+        Column::Protocol,
+        Column::Bind,
+        Column::Pid,
+        Column::ParentPid,
+        Column::ProcessName,
+        Column::ProcessPath,
+        Column::User,
+        Column::Listener,
+        Column::Remote,
+        Column::Cpu,
+        Column::Memory,
+        Column::Uptime,
+        Column::SocketInode,
+        Column::NetNamespace,
+    ];
+
+    /// Columns shown when the table is first opened
+    pub const DEFAULT: [Column; 8] = [
+        Column::Port,
+        Column::Protocol,
+        Column::Bind,
+        Column::Pid,
+        Column::ParentPid,
+        Column::ProcessName,
+        Column::ProcessPath,
+        Column::Listener,
+    ];
+
+    /// Resource usage columns, hidden by default
+    pub const USAGE: [Column; 2] = [Column::Cpu, Column::Memory];
+
+    pub fn title(self) -> &'static str {
+        match self {
+            Column::Port => "Port",
+            Column::Protocol => "Proto",
+            Column::Bind => "Bind",
+            Column::Pid => "PID",
+            Column::ParentPid => "PPID",
+            Column::ProcessName => "Process Name",
+            Column::ProcessPath => "Process Path",
+            Column::User => "User",
+            Column::Listener => "Listener",
+            Column::Remote => "Remote Address",
+            Column::Cpu => "CPU %",
+            Column::Memory => "Memory",
+            Column::Uptime => "Uptime",
+            Column::SocketInode => "Inode",
+            Column::NetNamespace => "Netns",
+        }
+    }
+
+    /// The sort criteria behind this column, if it is sortable
+    pub fn sort_by(self) -> Option<SortBy> {
+        match self {
+            Column::Port => Some(SortBy::Port),
+            Column::Pid => Some(SortBy::Pid),
+            Column::ParentPid => Some(SortBy::ParentPid),
+            Column::ProcessName => Some(SortBy::ProcessName),
+            Column::ProcessPath => Some(SortBy::ProcessPath),
+            Column::User => Some(SortBy::User),
+            Column::Protocol | Column::Bind => None,
+            Column::Listener | Column::Remote => None,
+            Column::SocketInode | Column::NetNamespace => None,
+            Column::Cpu => Some(SortBy::Cpu),
+            Column::Memory => Some(SortBy::Memory),
+            Column::Uptime => Some(SortBy::Uptime),
+        }
+    }
+
+    pub fn width(self) -> ColumnWidth {
+        match self {
+            Column::Port => ColumnWidth::Fixed(18),
+            Column::Protocol => ColumnWidth::Fixed(5),
+            Column::Bind => ColumnWidth::Fixed(15),
+            Column::Pid | Column::ParentPid => ColumnWidth::Fixed(6),
+            Column::ProcessName => ColumnWidth::Flexible { min: 12, weight: 1 },
+            Column::ProcessPath => ColumnWidth::Flexible { min: 20, weight: 2 },
+            Column::User => ColumnWidth::Fixed(12),
+            Column::Listener => ColumnWidth::Fixed(10),
+            Column::Remote => ColumnWidth::Flexible { min: 22, weight: 1 },
+            Column::Cpu => ColumnWidth::Fixed(8),
+            Column::Memory => ColumnWidth::Fixed(11),
+            Column::Uptime => ColumnWidth::Fixed(8),
+            Column::SocketInode | Column::NetNamespace => ColumnWidth::Fixed(10),
+        }
+    }
+
+    /// Lays out `columns` across `width` cells: fixed columns keep their size and
+    /// the rest is shared by the flexible ones in proportion to their weight.
+    pub fn constraints(columns: &[Column], width: u16) -> Vec<Constraint> {
+        // One cell of spacing between columns
+        let spacing = columns.len().saturating_sub(1) as u16;
+        let (fixed, total_weight) = columns
+            .iter()
+            .fold((0u16, 0u16), |(fixed, weight), c| match c.width() {
+                ColumnWidth::Fixed(w) => (fixed + w, weight),
+                ColumnWidth::Flexible { weight: w, .. } => (fixed, weight + w),
+            });
+        let remainder = width.saturating_sub(fixed + spacing);
+
+        let mut left = remainder;
+        let mut weight_left = total_weight;
+        columns
+            .iter()
+            .map(|c| match c.width() {
+                ColumnWidth::Fixed(w) => Constraint::Length(w),
+                ColumnWidth::Flexible { min, weight } => {
+                    // The last flexible column takes whatever rounding left over
+                    let share = if weight_left == weight {
+                        left
+                    } else {
+                        (remainder as u32 * weight as u32 / total_weight as u32) as u16
+                    };
+                    left = left.saturating_sub(share);
+                    weight_left -= weight;
+                    Constraint::Length(share.max(min))
+                }
+            })
+            .collect()
+    }
+
+    /// Text shown in this column for the given item
+    pub fn value(self, item: &PortInfo) -> String {
+        match self {
+            Column::Port => match service_name(item.port) {
+                Some(service) => format!("{} {}", item.port, service),
+                None => item.port.to_string(),
+            },
+            Column::Protocol => item.protocol_label(),
+            Column::Bind => match item.local_address {
+                _ if item.is_loopback() => "local".into(),
+                _ if item.is_wildcard() => "all".into(),
+                Some(addr) => addr.to_string(),
+                None => "-".into(),
+            },
+            Column::Pid => item.pid.to_string(),
+            Column::ParentPid => item.ppid.to_string(),
+            Column::ProcessName => item.process_name.clone(),
+            Column::ProcessPath if item.restricted => "<restricted>".into(),
+            Column::ProcessPath => item.process_path.clone(),
+            Column::User => item.user.clone(),
+            Column::Listener => format!("{:?}", item.port_state),
+            Column::Remote => match (item.remote_address, &item.remote_host) {
+                (Some(addr), Some(host)) => format!("{}:{}", host, addr.port()),
+                (Some(addr), None) => addr.to_string(),
+                (None, _) if item.port_state == ProcessPortState::Hosting => "*".into(),
+                (None, _) => "-".into(),
+            },
+            Column::Cpu => item
+                .cpu_percent
+                .map(|cpu| format!("{:.1}", cpu))
+                .unwrap_or_else(|| "-".into()),
+            Column::Memory => item
+                .memory_bytes
+                .map(format_bytes)
+                .unwrap_or_else(|| "-".into()),
+            Column::Uptime => item
+                .started_at
+                .and_then(|started| (Utc::now() - started).to_std().ok())
+                .map(format_duration)
+                .unwrap_or_else(|| "-".into()),
+            Column::SocketInode => item
+                .socket_inode
+                .map(|inode| inode.to_string())
+                .unwrap_or_else(|| "-".into()),
+            Column::NetNamespace => item
+                .net_namespace
+                .map(|ns| ns.to_string())
+                .unwrap_or_else(|| "-".into()),
+        }
+    }
+}
+
+/// Byte ranges of `text` matched by any of `terms`, sorted and merged. Letters are
+/// compared one by one so case folding never shifts the ranges off char boundaries.
+fn match_ranges(text: &str, terms: &[String], case_sensitive: bool) -> Vec<(usize, usize)> {
+    let same = |a: char, b: char| {
+        if case_sensitive {
+            a == b
+        } else {
+            a.to_lowercase().eq(b.to_lowercase())
+        }
+    };
+
+    let mut ranges: Vec<(usize, usize)> = Vec::new();
+    for (start, _) in text.char_indices() {
+        for term in terms.iter().filter(|t| !t.is_empty()) {
+            let mut chars = text[start..].char_indices();
+            let mut end = start;
+            let matched = term.chars().all(|t| match chars.next() {
+                Some((i, c)) if same(c, t) => {
+                    end = start + i + c.len_utf8();
+                    true
+                }
+                _ => false,
+            });
+            if !matched {
+                continue;
+            }
+            match ranges.last_mut() {
+                Some(last) if start <= last.1 => last.1 = last.1.max(end),
+                _ => ranges.push((start, end)),
+            }
+        }
+    }
+    ranges
+}
+
+/// `text` with the parts matching the search terms picked out
+fn highlighted(text: String, terms: &[String], case_sensitive: bool) -> Line<'static> {
+    let ranges = match_ranges(&text, terms, case_sensitive);
+    if ranges.is_empty() {
+        return Line::from(text);
+    }
+
+    let style = Style::default()
+        .fg(Color::Yellow)
+        .add_modifier(Modifier::BOLD | Modifier::UNDERLINED);
+    let mut spans = Vec::with_capacity(ranges.len() * 2 + 1);
+    let mut last = 0;
+    for (start, end) in ranges {
+        if start > last {
+            spans.push(Span::raw(text[last..start].to_string()));
+        }
+        spans.push(Span::styled(text[start..end].to_string(), style));
+        last = end;
+    }
+    if last < text.len() {
+        spans.push(Span::raw(text[last..].to_string()));
+    }
+    Line::from(spans)
+}
+
+/// One row of the compact layout, e.g. `8080 tcp node (1234)  …/bin/node`, the path
+/// shortened from the front to whatever room is left. Groups read `▸ node (1234) 3 ports`.
+fn compact_line(
+    item: &PortInfo,
+    group: Option<(usize, bool)>,
+    width: usize,
+    hex_pids: bool,
+) -> String {
+    let pid = format_pid(item.pid, hex_pids);
+    let line = match group {
+        Some((count, expanded)) => format!(
+            "{} {} ({}) {} port{}",
+            if expanded { "▾" } else { "▸" },
+            item.process_name,
+            pid,
+            count,
+            if count == 1 { "" } else { "s" }
+        ),
+        None => format!(
+            "{} {} {} ({})",
+            item.port,
+            item.protocol_label(),
+            item.process_name,
+            pid
+        ),
+    };
+
+    let used = line.chars().count() + 2;
+    if group.is_some() || !item.has_known_path() || used + 8 > width {
+        return line;
+    }
+    let room = width - used;
+    let path_len = item.process_path.chars().count();
+    let path = if path_len <= room {
+        item.process_path.clone()
+    } else {
+        let tail: String = item
+            .process_path
+            .chars()
+            .skip(path_len - room + 1)
+            .collect();
+        format!("…{}", tail)
+    };
+    format!("{}  {}", line, path)
+}
+
+/// PID as shown in the table, e.g. `0x4d2` instead of `1234` in hex mode
+fn format_pid(pid: u32, hex: bool) -> String {
+    if hex {
+        format!("{:#x}", pid)
+    } else {
+        pid.to_string()
+    }
+}
+
+/// A port that recently opened or closed
+#[derive(Debug, Clone)]
+pub enum RowChange {
+    Appeared,
+    /// Keeps the closed entry so it can still be drawn until the highlight expires
+    Closed(Box<PortInfo>),
+}
+
+/// A line of the table: a port, or in grouped view a process holding several ports
+#[derive(Debug, Clone, PartialEq)]
+pub enum TableRow {
+    /// Index into `items`
+    Port(usize),
+    /// All ports of one process; `first` is the index of its first item
+    Group {
+        pid: u32,
+        count: usize,
+        first: usize,
+        expanded: bool,
+    },
+}
+
+/// Which externally reachable ports are drawn in the warning color
+#[derive(Debug, Copy, PartialEq, Eq, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExposureHighlight {
+    /// Never highlight
+    Off,
+    /// Only well-known sensitive services such as databases and caches
+    #[default]
+    Sensitive,
+    /// Every port reachable from other machines
+    All,
+}
+
+/// Why the table has no rows, told apart so an empty list does not look broken
+#[derive(Debug, Copy, PartialEq, Eq, Default, Clone)]
+pub enum EmptyState {
+    /// The first fetch has not arrived yet
+    #[default]
+    Loading,
+    /// The system reported no ports at all
+    NoPorts,
+    /// Ports exist but the search and toggles hide all of them
+    NoMatches,
+}
+
+impl EmptyState {
+    pub fn message(self) -> &'static str {
+        match self {
+            EmptyState::Loading => "Loading ports…",
+            EmptyState::NoPorts => "No ports found",
+            EmptyState::NoMatches => "No matching ports",
+        }
+    }
 }
 
 #[derive(Debug, Copy, PartialEq, Default, Clone)]
@@ -37,12 +458,38 @@ pub struct ProcessTableComponent {
     pub scroll: ScrollbarState,
     /// Number of visible rows (set during render)
     pub visible_rows: usize,
-    /// Pre-computed column width constraints
-    pub column_widths: (u16, u16, u16, u16, u16),
+    /// Every column in display order, with its visibility
+    pub columns: Vec<ColumnSetting>,
+    /// Index of the leftmost rendered column, for horizontal scrolling
+    pub first_visible_column: usize,
     /// Sorting state by column
     pub sort_by: SortBy,
     /// Sorting direction
     pub sort_direction: SortDirection,
+    /// Sort columns that start out descending when selected
+    pub descending_by_default: Vec<SortBy>,
+    /// Recently opened/closed ports by `PortInfo.id`, with the time of the change
+    pub recent_changes: HashMap<String, (RowChange, Instant)>,
+    /// Collapse rows into one per process
+    pub grouped: bool,
+    /// Processes whose ports are listed under their group, by `(pid, process_name)`
+    pub expanded: HashSet<(u32, String)>,
+    /// Lines currently drawn, selection indexes into this
+    pub rows: Vec<TableRow>,
+    /// Which ports exposed beyond loopback get the warning color
+    pub exposure_highlight: ExposureHighlight,
+    /// Draw each row as one line of text instead of columns, for narrow terminals
+    pub compact: bool,
+    /// Moving past the last row selects the first and vice versa, instead of stopping
+    pub wrap_selection: bool,
+    /// Show PIDs in hex, for matching them against kernel logs. Sorting stays numeric
+    pub hex_pids: bool,
+    /// Message drawn in place of the rows when there are none
+    pub empty_state: EmptyState,
+    /// Search terms picked out in the name and path cells
+    pub highlight_terms: Vec<String>,
+    /// Match `highlight_terms` letter case exactly
+    pub highlight_case_sensitive: bool,
 }
 
 impl Default for ProcessTableComponent {
@@ -52,21 +499,125 @@ impl Default for ProcessTableComponent {
             state: TableState::default(),
             scroll: ScrollbarState::new(1),
             visible_rows: 0,
-            column_widths: (6, 6, 23, 50, 10), // Port, PID, ProcessName, ProcessPath, Listener
+            columns: Column::ALL
+                .iter()
+                .map(|&column| ColumnSetting {
+                    column,
+                    visible: Column::DEFAULT.contains(&column),
+                })
+                .collect(),
+            first_visible_column: 0,
             sort_by: SortBy::Port,
             sort_direction: SortDirection::Ascending,
+            descending_by_default: Vec::new(),
+            recent_changes: HashMap::new(),
+            grouped: false,
+            expanded: HashSet::new(),
+            rows: Vec::new(),
+            exposure_highlight: ExposureHighlight::default(),
+            compact: false,
+            wrap_selection: true,
+            hex_pids: false,
+            empty_state: EmptyState::default(),
+            highlight_terms: Vec::new(),
+            highlight_case_sensitive: false,
         }
     }
 }
 
 impl ProcessTableComponent {
+    /// Sets the search terms to highlight, one per whitespace separated word of `query`
+    pub fn set_highlight(&mut self, query: &str, case_sensitive: bool) {
+        self.highlight_terms = query.split_whitespace().map(str::to_string).collect();
+        self.highlight_case_sensitive = case_sensitive;
+    }
+
     /// Replace current items and update scrollbar length
     pub fn set_items(&mut self, items: Vec<PortInfo>) {
         self.items = items;
         self.sort_items();
-        let content_len = self.items.len() * crate::ITEM_HEIGHT as usize;
+    }
+    /// The item under the selection, if any. For a group this is its first port.
+    pub fn selected(&self) -> Option<&PortInfo> {
+        match self.rows.get(self.state.selected()?)? {
+            TableRow::Port(idx) => self.items.get(*idx),
+            TableRow::Group { first, .. } => self.items.get(*first),
+        }
+    }
+
+    /// Rebuild the drawn lines from the sorted items. Groups appear in the
+    /// position of their first port, so sorting applies within and across groups.
+    fn rebuild_rows(&mut self) {
+        self.rows.clear();
+        if !self.grouped {
+            self.rows.extend((0..self.items.len()).map(TableRow::Port));
+        } else {
+            let mut groups: Vec<((u32, &str), Vec<usize>)> = Vec::new();
+            for (idx, item) in self.items.iter().enumerate() {
+                let key = (item.pid, item.process_name.as_str());
+                match groups.iter_mut().find(|(k, _)| *k == key) {
+                    Some((_, members)) => members.push(idx),
+                    None => groups.push((key, vec![idx])),
+                }
+            }
+
+            for ((pid, name), members) in groups {
+                let expanded = self.expanded.contains(&(pid, name.to_string()));
+                self.rows.push(TableRow::Group {
+                    pid,
+                    count: members.len(),
+                    first: members[0],
+                    expanded,
+                });
+                if expanded {
+                    self.rows.extend(members.into_iter().map(TableRow::Port));
+                }
+            }
+        }
+
+        if let Some(selected) = self.state.selected()
+            && selected >= self.rows.len()
+        {
+            self.state.select(self.rows.len().checked_sub(1));
+        }
+        let content_len = self.rows.len() * crate::ITEM_HEIGHT as usize;
         self.scroll = self.scroll.content_length(content_len);
     }
+
+    /// Switch between one row per port and one row per process
+    pub fn toggle_grouped(&mut self) {
+        self.grouped = !self.grouped;
+        self.state
+            .select(if self.items.is_empty() { None } else { Some(0) });
+        self.scroll = self.scroll.position(0);
+        self.rebuild_rows();
+    }
+
+    /// Expand or collapse the group of the selected row
+    pub fn toggle_expanded(&mut self) {
+        if !self.grouped {
+            return;
+        }
+        let Some(item) = self.selected() else {
+            return;
+        };
+        let key = (item.pid, item.process_name.clone());
+        if !self.expanded.remove(&key) {
+            self.expanded.insert(key.clone());
+        }
+        self.rebuild_rows();
+
+        // Keep the selection on the group header
+        let (pid, name) = key;
+        if let Some(idx) = self.rows.iter().position(|row| {
+            matches!(row, TableRow::Group { pid: p, first, .. }
+                if *p == pid && self.items[*first].process_name == name)
+        }) {
+            self.state.select(Some(idx));
+            self.scroll = self.scroll.position(idx * crate::ITEM_HEIGHT as usize);
+        }
+    }
+
     /// Sort items by current sort criteria
     pub fn sort_items(&mut self) {
         match (self.sort_by, self.sort_direction) {
@@ -74,32 +625,167 @@ impl ProcessTableComponent {
             (SortBy::Port, SortDirection::Descending) => {
                 self.items.sort_by_key(|i| std::cmp::Reverse(i.port))
             }
-            (SortBy::PID, SortDirection::Ascending) => self.items.sort_by_key(|i| i.pid),
-            (SortBy::PID, SortDirection::Descending) => {
+            (SortBy::Pid, SortDirection::Ascending) => self.items.sort_by_key(|i| i.pid),
+            (SortBy::Pid, SortDirection::Descending) => {
                 self.items.sort_by_key(|i| std::cmp::Reverse(i.pid))
             }
-            (SortBy::ProcessName, SortDirection::Ascending) => self.items.sort_by(|a, b| {
-                a.process_name
-                    .to_lowercase()
-                    .cmp(&b.process_name.to_lowercase())
-            }),
-            (SortBy::ProcessName, SortDirection::Descending) => self.items.sort_by(|a, b| {
-                b.process_name
-                    .to_lowercase()
-                    .cmp(&a.process_name.to_lowercase())
-            }),
-            (SortBy::ProcessPath, SortDirection::Ascending) => self.items.sort_by(|a, b| {
-                a.process_path
-                    .to_lowercase()
-                    .cmp(&b.process_path.to_lowercase())
+            (SortBy::ParentPid, SortDirection::Ascending) => self.items.sort_by_key(|i| i.ppid),
+            (SortBy::ParentPid, SortDirection::Descending) => {
+                self.items.sort_by_key(|i| std::cmp::Reverse(i.ppid))
+            }
+            (SortBy::ProcessName, SortDirection::Ascending) => self
+                .items
+                .sort_by(|a, b| natural_cmp(&a.process_name, &b.process_name)),
+            (SortBy::ProcessName, SortDirection::Descending) => self
+                .items
+                .sort_by(|a, b| natural_cmp(&b.process_name, &a.process_name)),
+            // Lowercase each path once rather than on every comparison
+            (SortBy::ProcessPath, SortDirection::Ascending) => self
+                .items
+                .sort_by_cached_key(|i| i.process_path.to_lowercase()),
+            (SortBy::ProcessPath, SortDirection::Descending) => self
+                .items
+                .sort_by_cached_key(|i| std::cmp::Reverse(i.process_path.to_lowercase())),
+            (SortBy::User, SortDirection::Ascending) => {
+                self.items.sort_by_cached_key(|i| i.user.to_lowercase())
+            }
+            (SortBy::User, SortDirection::Descending) => self
+                .items
+                .sort_by_cached_key(|i| std::cmp::Reverse(i.user.to_lowercase())),
+            (SortBy::Cpu, SortDirection::Ascending) => self.items.sort_by(|a, b| {
+                a.cpu_percent
+                    .partial_cmp(&b.cpu_percent)
+                    .unwrap_or(std::cmp::Ordering::Equal)
             }),
-            (SortBy::ProcessPath, SortDirection::Descending) => self.items.sort_by(|a, b| {
-                b.process_path
-                    .to_lowercase()
-                    .cmp(&a.process_path.to_lowercase())
+            (SortBy::Cpu, SortDirection::Descending) => self.items.sort_by(|a, b| {
+                b.cpu_percent
+                    .partial_cmp(&a.cpu_percent)
+                    .unwrap_or(std::cmp::Ordering::Equal)
             }),
+            (SortBy::Memory, SortDirection::Ascending) => {
+                self.items.sort_by_key(|i| i.memory_bytes)
+            }
+            (SortBy::Memory, SortDirection::Descending) => self
+                .items
+                .sort_by_key(|i| std::cmp::Reverse(i.memory_bytes)),
+            // Shortest uptime is the latest start, unknown start times go last
+            (SortBy::Uptime, SortDirection::Ascending) => {
+                self.items.sort_by_key(|i| std::cmp::Reverse(i.started_at))
+            }
+            (SortBy::Uptime, SortDirection::Descending) => self
+                .items
+                .sort_by_key(|i| (i.started_at.is_none(), i.started_at)),
+        }
+        self.rebuild_rows();
+    }
+
+    /// Remember ports that opened or closed in the last refresh so they can be highlighted
+    pub fn mark_changes(&mut self, added: &[PortInfo], removed: Vec<PortInfo>) {
+        let now = Instant::now();
+        self.recent_changes
+            .retain(|_, (_, at)| at.elapsed() < CHANGE_HIGHLIGHT);
+
+        for item in removed {
+            self.recent_changes
+                .insert(item.id.clone(), (RowChange::Closed(Box::new(item)), now));
         }
+        for item in added {
+            self.recent_changes
+                .insert(item.id.clone(), (RowChange::Appeared, now));
+        }
+    }
+
+    /// The highlight for an item, if it changed recently
+    fn recent_change(&self, id: &str) -> Option<&RowChange> {
+        self.recent_changes
+            .get(id)
+            .filter(|(_, at)| at.elapsed() < CHANGE_HIGHLIGHT)
+            .map(|(change, _)| change)
+    }
+
+    /// Show a column if hidden, hide it otherwise
+    pub fn toggle_column(&mut self, column: Column) {
+        if let Some(setting) = self.columns.iter_mut().find(|s| s.column == column) {
+            setting.visible = !setting.visible;
+        }
+        self.clamp_first_visible_column();
+    }
+
+    /// Switch between the column table and one line of text per row
+    pub fn toggle_compact(&mut self) {
+        self.compact = !self.compact;
     }
+
+    /// Switch the PID columns between decimal and hex
+    pub fn toggle_hex_pids(&mut self) {
+        self.hex_pids = !self.hex_pids;
+    }
+
+    /// Show the CPU and memory columns if hidden, hide them otherwise
+    pub fn toggle_usage_columns(&mut self) {
+        let show = !self
+            .columns
+            .iter()
+            .any(|s| s.visible && Column::USAGE.contains(&s.column));
+        for setting in self.columns.iter_mut() {
+            if Column::USAGE.contains(&setting.column) {
+                setting.visible = show;
+            }
+        }
+        self.clamp_first_visible_column();
+    }
+
+    /// Columns currently shown, in display order
+    pub fn visible_columns(&self) -> Vec<Column> {
+        self.columns
+            .iter()
+            .filter(|s| s.visible)
+            .map(|s| s.column)
+            .collect()
+    }
+
+    /// Show exactly the given columns, e.g. as restored from the config
+    pub fn set_visible_columns(&mut self, visible: &[Column]) {
+        for setting in self.columns.iter_mut() {
+            setting.visible = visible.contains(&setting.column);
+        }
+        self.clamp_first_visible_column();
+    }
+
+    /// Puts the columns in the given order, e.g. `column_order` from the config. The order
+    /// must name every column exactly once, otherwise the current one is kept.
+    pub fn set_column_order(&mut self, order: &[Column]) -> Result<(), String> {
+        for column in Column::ALL {
+            match order.iter().filter(|c| **c == column).count() {
+                0 => return Err(format!("Column order is missing {}", column.title())),
+                1 => {}
+                _ => return Err(format!("Column order lists {} twice", column.title())),
+            }
+        }
+        self.columns
+            .sort_by_key(|s| order.iter().position(|c| *c == s.column));
+        Ok(())
+    }
+
+    /// Whether any listed row has a value to sort `by`, e.g. a saved snapshot has no
+    /// CPU figures. An empty list has nothing to contradict, so it counts as having data.
+    pub fn has_sort_data(&self, by: SortBy) -> bool {
+        if self.items.is_empty() {
+            return true;
+        }
+        match by {
+            SortBy::User => self.items.iter().any(|i| !i.user.is_empty()),
+            SortBy::Cpu => self.items.iter().any(|i| i.cpu_percent.is_some()),
+            SortBy::Memory => self.items.iter().any(|i| i.memory_bytes.is_some()),
+            SortBy::Uptime => self.items.iter().any(|i| i.started_at.is_some()),
+            SortBy::Port
+            | SortBy::Pid
+            | SortBy::ParentPid
+            | SortBy::ProcessName
+            | SortBy::ProcessPath => true,
+        }
+    }
+
     /// Set sort column and toggle sort direction if it's already set to this column
     pub fn set_or_toggle_sort(&mut self, by: SortBy) {
         if self.sort_by == by {
@@ -109,15 +795,44 @@ impl ProcessTableComponent {
         }
     }
 
-    /// Set sort column and reset sort direction to ascending
+    /// Set sort column and reset sort direction to the column's default
     pub fn set_sort_column(&mut self, by: SortBy) {
         if self.sort_by != by {
             self.sort_by = by;
-            self.sort_direction = SortDirection::Ascending;
+            self.sort_direction = self.default_direction(by);
             self.sort_items();
         }
     }
 
+    /// Columns listed here sort descending when first selected, e.g. as set in the config.
+    /// The current column switches over right away.
+    pub fn set_descending_by_default(&mut self, columns: &[SortBy]) {
+        self.descending_by_default = columns.to_vec();
+        self.sort_direction = self.default_direction(self.sort_by);
+        self.sort_items();
+    }
+
+    fn default_direction(&self, by: SortBy) -> SortDirection {
+        if self.descending_by_default.contains(&by) {
+            SortDirection::Descending
+        } else {
+            SortDirection::Ascending
+        }
+    }
+
+    /// Whether the row gets the warning color: a listening or unconnected socket bound
+    /// beyond loopback that meets the configured threshold
+    pub fn is_exposed(&self, item: &PortInfo) -> bool {
+        if !item.is_external() || item.remote_address.is_some() {
+            return false;
+        }
+        match self.exposure_highlight {
+            ExposureHighlight::Off => false,
+            ExposureHighlight::Sensitive => is_sensitive_service(item.port),
+            ExposureHighlight::All => true,
+        }
+    }
+
     /// Toggle or explicitly set sort direction
     pub fn toggle_sort_direction(&mut self, direction: Option<SortDirection>) {
         self.sort_direction = match direction {
@@ -130,11 +845,30 @@ impl ProcessTableComponent {
         self.sort_items();
     }
 
+    /// Keep the horizontal offset within the visible columns
+    fn clamp_first_visible_column(&mut self) {
+        self.first_visible_column = self
+            .first_visible_column
+            .min(self.visible_columns().len().saturating_sub(1));
+    }
+
+    /// Pan one column to the left
+    pub fn scroll_left(&mut self) {
+        self.first_visible_column = self.first_visible_column.saturating_sub(1);
+    }
+
+    /// Pan one column to the right
+    pub fn scroll_right(&mut self) {
+        self.first_visible_column += 1;
+        self.clamp_first_visible_column();
+    }
+
     /// Move selection down by one row
     pub fn next_row(&mut self) {
-        let len = self.items.len();
+        let len = self.rows.len();
         let idx = match self.state.selected() {
             Some(i) if i + 1 < len => i + 1,
+            Some(i) if !self.wrap_selection => i,
             _ if len > 0 => 0,
             _ => return,
         };
@@ -144,8 +878,9 @@ impl ProcessTableComponent {
 
     /// Move selection up by one row
     pub fn previous_row(&mut self) {
-        let len = self.items.len();
+        let len = self.rows.len();
         let idx = match self.state.selected() {
+            Some(0) if !self.wrap_selection => 0,
             Some(0) if len > 0 => len - 1,
             Some(i) => i - 1,
             _ if len > 0 => 0,
@@ -157,15 +892,58 @@ impl ProcessTableComponent {
 
     /// Jump to the first row
     pub fn first_row(&mut self) {
-        if !self.items.is_empty() {
+        if !self.rows.is_empty() {
             self.state.select(Some(0));
             self.scroll = self.scroll.position(0);
         }
     }
 
+    /// Select the row at `row` in the drawn lines
+    pub fn select_row(&mut self, row: usize) {
+        if row < self.rows.len() {
+            self.state.select(Some(row));
+            self.scroll = self.scroll.position(row * crate::ITEM_HEIGHT as usize);
+        }
+    }
+
+    /// Drawn line of the port with this `PortInfo.id`, or of its group when collapsed
+    pub fn row_of(&self, id: &str) -> Option<usize> {
+        let item = self.items.iter().position(|item| item.id == id)?;
+        self.rows.iter().position(|row| match row {
+            TableRow::Port(idx) => *idx == item,
+            TableRow::Group {
+                pid,
+                first,
+                expanded: false,
+                ..
+            } => {
+                *pid == self.items[item].pid
+                    && self.items[*first].process_name == self.items[item].process_name
+            }
+            TableRow::Group { .. } => false,
+        })
+    }
+
+    /// First drawn line whose port is exactly `digits`, or else the first one
+    /// starting with them, so typing `30` already lands near `3000`
+    pub fn find_port(&self, digits: &str) -> Option<usize> {
+        let port_of = |row: &TableRow| match row {
+            TableRow::Port(idx) | TableRow::Group { first: idx, .. } => self.items[*idx].port,
+        };
+        let exact = digits.parse::<u16>().ok();
+        self.rows
+            .iter()
+            .position(|row| Some(port_of(row)) == exact)
+            .or_else(|| {
+                self.rows
+                    .iter()
+                    .position(|row| port_of(row).to_string().starts_with(digits))
+            })
+    }
+
     /// Jump to the last row
     pub fn last_row(&mut self) {
-        let len = self.items.len();
+        let len = self.rows.len();
         if len > 0 {
             let last = len - 1;
             self.state.select(Some(last));
@@ -175,7 +953,7 @@ impl ProcessTableComponent {
 
     /// Page down by visible_rows
     pub fn page_down(&mut self) {
-        let len = self.items.len();
+        let len = self.rows.len();
         if len == 0 {
             return;
         }
@@ -187,7 +965,7 @@ impl ProcessTableComponent {
 
     /// Page up by visible_rows
     pub fn page_up(&mut self) {
-        let len = self.items.len();
+        let len = self.rows.len();
         if len == 0 {
             return;
         }
@@ -197,9 +975,33 @@ impl ProcessTableComponent {
         self.scroll = self.scroll.position(new * crate::ITEM_HEIGHT as usize);
     }
 
+    /// Move down by half a page
+    pub fn half_page_down(&mut self) {
+        let len = self.rows.len();
+        if len == 0 {
+            return;
+        }
+        let current = self.state.selected().unwrap_or(0);
+        let new = (current + (self.visible_rows / 2).max(1)).min(len - 1);
+        self.state.select(Some(new));
+        self.scroll = self.scroll.position(new * crate::ITEM_HEIGHT as usize);
+    }
+
+    /// Move up by half a page
+    pub fn half_page_up(&mut self) {
+        if self.rows.is_empty() {
+            return;
+        }
+        let current = self.state.selected().unwrap_or(0);
+        let new = current.saturating_sub((self.visible_rows / 2).max(1));
+        self.state.select(Some(new));
+        self.scroll = self.scroll.position(new * crate::ITEM_HEIGHT as usize);
+    }
+
     /// Display direction indicator if sorting by this column
-    fn header_with_sort(&self, title: &str, column: SortBy) -> String {
-        if self.sort_by == column {
+    fn header_with_sort(&self, column: Column) -> String {
+        let title = column.title();
+        if column.sort_by() == Some(self.sort_by) {
             let arrow = match self.sort_direction {
                 SortDirection::Ascending => " ▲",
                 SortDirection::Descending => " ▼",
@@ -211,58 +1013,193 @@ impl ProcessTableComponent {
         }
     }
 
+    /// Range of display lines to draw out of `total`, scrolled from the previous
+    /// offset just enough to keep the selection in view.
+    fn visible_window(&self, total: usize) -> (usize, usize) {
+        let height = self.visible_rows.max(1);
+        let mut offset = self.state.offset();
+        if let Some(selected) = self.state.selected() {
+            if selected < offset {
+                offset = selected;
+            } else if selected >= offset + height {
+                offset = selected + 1 - height;
+            }
+        }
+        let offset = offset.min(total.saturating_sub(height));
+        (offset, (offset + height).min(total))
+    }
+
     /// Render the table and its scrollbar
     pub fn render(&mut self, frame: &mut Frame, area: Rect, colors: &TableColors) {
-        // Compute how many rows fit
-        self.visible_rows = area.height.saturating_sub(1) as usize;
+        // Compute how many rows fit, the compact layout has no header
+        self.visible_rows = if self.compact {
+            area.height as usize
+        } else {
+            area.height.saturating_sub(1) as usize
+        };
+
+        // Only the columns right of the horizontal offset are rendered
+        let visible = self.visible_columns();
+        let columns = &visible[self.first_visible_column.min(visible.len())..];
+        // Leave room for the scrollbar and the highlight symbol column
+        let line_width = area.width.saturating_sub(3) as usize;
 
         // Build header
-        let headers = [
-            self.header_with_sort("Port", SortBy::Port),
-            self.header_with_sort("PID", SortBy::PID),
-            self.header_with_sort("Process Name", SortBy::ProcessName),
-            self.header_with_sort("Process Path", SortBy::ProcessPath),
-            "Listener".to_string(), // No need to sort this one
-        ];
-
-        let header = Row::new(headers.map(Cell::from))
+        let headers = columns
+            .iter()
+            .map(|&c| Cell::from(self.header_with_sort(c)));
+
+        let header = Row::new(headers)
             .style(Style::default().fg(colors.header_fg).bg(colors.header_bg))
             .height(crate::ITEM_HEIGHT);
 
-        // Build rows
-        let rows = self.items.iter().map(|item| {
-            Row::new(item.ref_array().into_iter().map(Cell::from))
-                .style(Style::default())
-                .height(crate::ITEM_HEIGHT)
+        // Ports that just closed stay at the bottom in red until the highlight expires.
+        // They come after the real rows so selection indexes are unaffected.
+        let closed: Vec<&PortInfo> = self
+            .recent_changes
+            .keys()
+            .filter_map(|id| match self.recent_change(id) {
+                Some(RowChange::Closed(item)) if !self.items.iter().any(|i| &i.id == id) => {
+                    Some(item.as_ref())
+                }
+                _ => None,
+            })
+            .collect();
+
+        let nothing_to_draw = self.rows.is_empty() && closed.is_empty();
+
+        // Only the rows in view are built, so huge lists cost the same per frame
+        let (start, end) = self.visible_window(self.rows.len() + closed.len());
+        let len = self.rows.len();
+
+        // Build rows, newly opened ports are drawn in green and
+        // processes we lack the privileges to inspect are dimmed
+        let rows = self.rows[start.min(len)..end.min(len)].iter().map(|row| {
+            let (item, group) = match row {
+                TableRow::Port(idx) => (&self.items[*idx], None),
+                TableRow::Group {
+                    first,
+                    count,
+                    expanded,
+                    ..
+                } => (&self.items[*first], Some((*count, *expanded))),
+            };
+            let style = match self.recent_change(&item.id) {
+                Some(RowChange::Appeared) => Style::default().fg(Color::Green),
+                _ if self.is_exposed(item) => Style::default()
+                    .fg(Color::LightRed)
+                    .add_modifier(Modifier::BOLD),
+                _ if item.restricted => Style::default()
+                    .fg(Color::DarkGray)
+                    .add_modifier(Modifier::ITALIC),
+                _ => Style::default(),
+            };
+            let style = if group.is_some() {
+                style.add_modifier(Modifier::BOLD)
+            } else {
+                style
+            };
+            if self.compact {
+                return Row::new([Cell::from(compact_line(
+                    item,
+                    group,
+                    line_width,
+                    self.hex_pids,
+                ))])
+                .style(style)
+                .height(crate::ITEM_HEIGHT);
+            }
+            let cells = columns.iter().map(|&c| match (c, group) {
+                (Column::Port, Some((count, _))) => Cell::from(format!(
+                    "{} port{}",
+                    count,
+                    if count == 1 { "" } else { "s" }
+                )),
+                (Column::ProcessName, Some((_, expanded))) => {
+                    let mut line = highlighted(
+                        item.process_name.clone(),
+                        &self.highlight_terms,
+                        self.highlight_case_sensitive,
+                    );
+                    line.spans
+                        .insert(0, Span::raw(if expanded { "▾ " } else { "▸ " }));
+                    Cell::from(line)
+                }
+                (
+                    Column::Bind | Column::Listener | Column::Remote | Column::SocketInode,
+                    Some(_),
+                ) => Cell::from(""),
+                (Column::ProcessName | Column::ProcessPath, _) => Cell::from(highlighted(
+                    c.value(item),
+                    &self.highlight_terms,
+                    self.highlight_case_sensitive,
+                )),
+                (Column::Pid, _) => Cell::from(format_pid(item.pid, self.hex_pids)),
+                (Column::ParentPid, _) => Cell::from(format_pid(item.ppid, self.hex_pids)),
+                _ => Cell::from(c.value(item)),
+            });
+            Row::new(cells).style(style).height(crate::ITEM_HEIGHT)
         });
 
+        let closed_rows = closed[start.saturating_sub(len)..end.saturating_sub(len)]
+            .iter()
+            .map(|item| {
+                let row = if self.compact {
+                    Row::new([Cell::from(compact_line(
+                        item,
+                        None,
+                        line_width,
+                        self.hex_pids,
+                    ))])
+                } else {
+                    Row::new(columns.iter().map(|c| Cell::from(c.value(item))))
+                };
+                row.style(Style::default().fg(Color::Red))
+                    .height(crate::ITEM_HEIGHT)
+            });
+        let rows = rows.chain(closed_rows);
+
         // Construct table
-        let table = Table::new(
-            rows,
-            [
-                Constraint::Length(self.column_widths.0),
-                Constraint::Length(self.column_widths.1),
-                Constraint::Min(self.column_widths.2),
-                Constraint::Min(self.column_widths.3),
-                Constraint::Min(self.column_widths.4),
-            ],
-        )
-        .header(header)
-        .row_highlight_style(
-            Style::default()
-                .add_modifier(Modifier::REVERSED)
-                .fg(colors.selected_row_style_fg),
-        )
-        .cell_highlight_style(
-            Style::default()
-                .add_modifier(Modifier::REVERSED)
-                .fg(colors.selected_cell_style_fg),
-        )
-        .bg(colors.buffer_bg)
-        .highlight_spacing(HighlightSpacing::Always);
-
-        // Render table
-        frame.render_stateful_widget(table, area, &mut self.state);
+        // Leave room for the scrollbar on the right
+        let table = if self.compact {
+            Table::new(rows, [Constraint::Fill(1)])
+        } else {
+            let widths = Column::constraints(columns, area.width.saturating_sub(1));
+            Table::new(rows, widths).header(header)
+        };
+        let table = table
+            .row_highlight_style(
+                Style::default()
+                    .add_modifier(Modifier::REVERSED)
+                    .fg(colors.selected_row_style_fg),
+            )
+            .cell_highlight_style(
+                Style::default()
+                    .add_modifier(Modifier::REVERSED)
+                    .fg(colors.selected_cell_style_fg),
+            )
+            .bg(colors.buffer_bg)
+            .highlight_spacing(HighlightSpacing::Always);
+
+        // Render table, with the selection relative to the built window
+        let mut window_state =
+            TableState::default().with_selected(self.state.selected().map(|i| i - start));
+        frame.render_stateful_widget(table, area, &mut window_state);
+        *self.state.offset_mut() = start;
+
+        // An empty list gets a centered note instead of blank space and a scrollbar
+        if nothing_to_draw {
+            let message = Paragraph::new(self.empty_state.message())
+                .alignment(Alignment::Center)
+                .style(Style::default().fg(Color::DarkGray));
+            let line = Rect {
+                y: area.y + area.height / 2,
+                height: area.height.min(1),
+                ..area
+            };
+            frame.render_widget(message, line);
+            return;
+        }
 
         // Render scrollbar
         self.render_scrollbar(frame, area);
@@ -282,3 +1219,288 @@ impl ProcessTableComponent {
         );
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::PortFilter;
+
+    fn synthetic_ports(count: usize) -> Vec<PortInfo> {
+        (0..count)
+            .map(|i| PortInfo {
+                id: i.to_string(),
+                port: (1024 + i % 60_000) as u16,
+                pid: 1000 + (i / 4) as u32,
+                ppid: 1,
+                process_name: format!("worker{}", i % 97),
+                process_path: format!("/opt/Service{}/bin/worker", i % 13),
+                user: "alice".into(),
+                port_state: if i % 3 == 0 {
+                    ProcessPortState::Hosting
+                } else {
+                    ProcessPortState::Using
+                },
+                ..PortInfo::default()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn sorts_filtered_items_in_place() {
+        let mut table = ProcessTableComponent::default();
+        table.set_items(synthetic_ports(20));
+        assert!(table.items.windows(2).all(|w| w[0].port <= w[1].port));
+
+        table.set_sort_column(SortBy::ProcessPath);
+        assert!(
+            table
+                .items
+                .windows(2)
+                .all(|w| w[0].process_path.to_lowercase() <= w[1].process_path.to_lowercase())
+        );
+        assert_eq!(table.rows.len(), 20);
+    }
+
+    #[test]
+    fn selection_stops_at_the_ends_without_wrapping() {
+        let mut table = ProcessTableComponent::default();
+        table.set_items(synthetic_ports(3));
+        table.first_row();
+        table.previous_row();
+        assert_eq!(table.state.selected(), Some(2));
+        table.next_row();
+        assert_eq!(table.state.selected(), Some(0));
+
+        table.wrap_selection = false;
+        table.previous_row();
+        assert_eq!(table.state.selected(), Some(0));
+        table.last_row();
+        table.next_row();
+        assert_eq!(table.state.selected(), Some(2));
+    }
+
+    #[test]
+    fn window_follows_the_selection() {
+        let mut table = ProcessTableComponent {
+            visible_rows: 10,
+            ..Default::default()
+        };
+        table.set_items(synthetic_ports(1_000));
+        assert_eq!(table.visible_window(1_000), (0, 10));
+
+        table.state.select(Some(500));
+        assert_eq!(table.visible_window(1_000), (491, 501));
+
+        *table.state.offset_mut() = 995;
+        table.state.select(Some(999));
+        assert_eq!(table.visible_window(1_000), (990, 1_000));
+    }
+
+    #[test]
+    fn filters_and_sorts_5000_ports() {
+        let ports = synthetic_ports(5_000);
+        let filter = PortFilter {
+            query: "worker1".into(),
+            ..Default::default()
+        };
+        let mut table = ProcessTableComponent {
+            sort_by: SortBy::ProcessPath,
+            ..Default::default()
+        };
+
+        // Refreshing again with the same ports keeps the same rows in the same order
+        table.set_items(filter.apply(&ports).cloned().collect());
+        let first: Vec<String> = table.items.iter().map(|p| p.id.clone()).collect();
+        table.set_items(filter.apply(&ports).cloned().collect());
+        let second: Vec<String> = table.items.iter().map(|p| p.id.clone()).collect();
+        assert_eq!(first, second);
+
+        // worker1 and worker10 to worker19 out of 97 names
+        assert_eq!(table.items.len(), 572);
+        assert!(
+            table
+                .items
+                .iter()
+                .all(|p| p.process_name.starts_with("worker1"))
+        );
+        assert!(
+            table
+                .items
+                .windows(2)
+                .all(|w| w[0].process_path.to_lowercase() <= w[1].process_path.to_lowercase())
+        );
+    }
+
+    /// Times a refresh of a large table, see "Development" in the README
+    #[test]
+    #[ignore]
+    fn bench_filter_and_sort_5000_ports() {
+        let ports = synthetic_ports(5_000);
+        let filter = PortFilter {
+            query: "worker1".into(),
+            ..Default::default()
+        };
+        let mut table = ProcessTableComponent {
+            sort_by: SortBy::ProcessPath,
+            ..Default::default()
+        };
+
+        let rounds = 100;
+        let start = Instant::now();
+        for _ in 0..rounds {
+            table.set_items(filter.apply(&ports).cloned().collect());
+        }
+        let per_update = start.elapsed() / rounds;
+        assert_eq!(table.items.len(), 572);
+        println!(
+            "filter + sort of {} ports ({} shown): {:?} per update",
+            ports.len(),
+            table.items.len(),
+            per_update
+        );
+    }
+
+    #[test]
+    fn sorts_by_user_and_knows_missing_usage_data() {
+        let mut ports = synthetic_ports(3);
+        ports[1].user = "Bob".into();
+        ports[2].user = "carol".into();
+        let mut table = ProcessTableComponent::default();
+        table.set_items(ports);
+
+        table.set_sort_column(SortBy::User);
+        let users: Vec<&str> = table.items.iter().map(|i| i.user.as_str()).collect();
+        assert_eq!(users, ["alice", "Bob", "carol"]);
+
+        assert!(table.has_sort_data(SortBy::User));
+        assert!(!table.has_sort_data(SortBy::Cpu));
+        table.items[0].cpu_percent = Some(0.0);
+        assert!(table.has_sort_data(SortBy::Cpu));
+    }
+
+    #[test]
+    fn column_order_must_name_every_column_once() {
+        let mut table = ProcessTableComponent::default();
+        let mut order = Column::ALL.to_vec();
+        order.swap(0, 3);
+        table.set_column_order(&order).unwrap();
+        assert_eq!(
+            table.visible_columns()[..2],
+            [Column::Pid, Column::Protocol]
+        );
+
+        let mut twice = Column::ALL.to_vec();
+        twice[1] = Column::Port;
+        assert_eq!(
+            table.set_column_order(&twice),
+            Err("Column order lists Port twice".to_string())
+        );
+        assert!(table.set_column_order(&Column::ALL[1..]).is_err());
+        assert_eq!(table.visible_columns()[0], Column::Pid);
+    }
+
+    #[test]
+    fn configured_columns_start_descending() {
+        let mut table = ProcessTableComponent::default();
+        table.set_descending_by_default(&[SortBy::Pid]);
+        assert_eq!(table.sort_direction, SortDirection::Ascending);
+
+        table.set_sort_column(SortBy::Pid);
+        assert_eq!(table.sort_direction, SortDirection::Descending);
+        table.set_or_toggle_sort(SortBy::Pid);
+        assert_eq!(table.sort_direction, SortDirection::Ascending);
+
+        table.set_sort_column(SortBy::ProcessName);
+        assert_eq!(table.sort_direction, SortDirection::Ascending);
+    }
+
+    #[test]
+    fn compact_lines_elide_the_path_to_fit() {
+        let item = PortInfo {
+            port: 8080,
+            pid: 1234,
+            process_name: "node".into(),
+            process_path: "/home/alice/.nvm/versions/node/v20/bin/node".into(),
+            ..synthetic_ports(1).remove(0)
+        };
+
+        assert_eq!(compact_line(&item, None, 20, false), "8080 tcp node (1234)");
+        assert_eq!(compact_line(&item, None, 20, true), "8080 tcp node (0x4d2)");
+        assert_eq!(
+            compact_line(&item, None, 80, false),
+            "8080 tcp node (1234)  /home/alice/.nvm/versions/node/v20/bin/node"
+        );
+        assert_eq!(
+            compact_line(&item, None, 40, false),
+            "8080 tcp node (1234)  …node/v20/bin/node"
+        );
+        assert_eq!(
+            compact_line(&item, Some((3, false)), 80, false),
+            "▸ node (1234) 3 ports"
+        );
+    }
+
+    #[test]
+    fn highlights_every_term_ignoring_case() {
+        let terms = vec!["NODE".to_string(), "bin".to_string()];
+        assert_eq!(
+            match_ranges("/usr/bin/node", &terms, false),
+            [(5, 8), (9, 13)]
+        );
+        assert_eq!(match_ranges("/usr/bin/node", &terms, true), [(5, 8)]);
+
+        // Overlapping matches merge into one span
+        let terms = vec!["nod".to_string(), "ode".to_string()];
+        assert_eq!(match_ranges("node", &terms, false), [(0, 4)]);
+
+        // Case folding that changes byte lengths keeps ranges on char boundaries
+        let terms = vec!["straße".to_string()];
+        let text = "STRAẞE-proxy";
+        assert_eq!(match_ranges(text, &terms, false), [(0, 8)]);
+        let line = highlighted(text.to_string(), &terms, false);
+        let parts: Vec<&str> = line.spans.iter().map(|s| s.content.as_ref()).collect();
+        assert_eq!(parts, ["STRAẞE", "-proxy"]);
+    }
+
+    #[test]
+    fn finds_ports_by_exact_number_then_prefix() {
+        let mut table = ProcessTableComponent::default();
+        table.set_items(
+            [30000, 3000, 8080]
+                .into_iter()
+                .map(|port| PortInfo {
+                    port,
+                    ..synthetic_ports(1).remove(0)
+                })
+                .collect(),
+        );
+        let port_at = |row: Option<usize>| row.map(|r| table.items[r].port);
+
+        assert_eq!(port_at(table.find_port("3000")), Some(3000));
+        assert_eq!(port_at(table.find_port("300")), Some(3000));
+        assert_eq!(port_at(table.find_port("8")), Some(8080));
+        assert_eq!(table.find_port("9"), None);
+    }
+
+    #[test]
+    fn highlights_sensitive_ports_exposed_beyond_loopback() {
+        let bound = |port, address: &str| PortInfo {
+            port,
+            local_address: Some(address.parse().unwrap()),
+            ..synthetic_ports(1).remove(0)
+        };
+        let mut table = ProcessTableComponent::default();
+
+        assert!(table.is_exposed(&bound(6379, "0.0.0.0")));
+        assert!(table.is_exposed(&bound(5432, "192.168.1.20")));
+        assert!(!table.is_exposed(&bound(6379, "127.0.0.1")));
+        assert!(!table.is_exposed(&bound(8080, "0.0.0.0")));
+
+        table.exposure_highlight = ExposureHighlight::All;
+        assert!(table.is_exposed(&bound(8080, "::")));
+        assert!(!table.is_exposed(&bound(8080, "::1")));
+
+        table.exposure_highlight = ExposureHighlight::Off;
+        assert!(!table.is_exposed(&bound(6379, "0.0.0.0")));
+    }
+}
@@ -0,0 +1,77 @@
+use crate::ApplicationMode;
+use crate::model::{QuickFilter, QuickFilters};
+use crate::ui::theme::TableColors;
+
+use ratatui::{
+    Frame,
+    layout::Rect,
+    prelude::Style,
+    style::Modifier,
+    text::{Line, Span},
+    widgets::Paragraph,
+};
+
+/// A one-line bar of protocol, family and state toggles
+#[derive(Debug, Default)]
+pub struct QuickFilterComponent {
+    /// Whether the bar is shown on its own, without the search box
+    pub display: bool,
+    /// Index into [`QuickFilter::ALL`] of the focused chip
+    pub selected: usize,
+}
+
+impl QuickFilterComponent {
+    /// Toggle display on/off
+    pub fn toggle(&mut self) {
+        self.display = !self.display;
+    }
+
+    /// The focused chip
+    pub fn selected_filter(&self) -> QuickFilter {
+        QuickFilter::ALL[self.selected]
+    }
+
+    /// Move focus to the next chip
+    pub fn next(&mut self) {
+        self.selected = (self.selected + 1) % QuickFilter::ALL.len();
+    }
+
+    /// Move focus to the previous chip
+    pub fn previous(&mut self) {
+        let len = QuickFilter::ALL.len();
+        self.selected = (self.selected + len - 1) % len;
+    }
+
+    /// Renders the chips, highlighting the focused one while the bar has focus
+    pub fn render(
+        &self,
+        frame: &mut Frame,
+        area: Rect,
+        colors: &TableColors,
+        filters: QuickFilters,
+        mode: &ApplicationMode,
+    ) {
+        let focused = matches!(mode, ApplicationMode::Filtering);
+
+        let mut spans = vec![Span::raw(" ")];
+        for (i, filter) in QuickFilter::ALL.iter().enumerate() {
+            let mut style = if filters.is_on(*filter) {
+                Style::default()
+                    .fg(colors.buffer_bg)
+                    .bg(colors.footer_border_color)
+            } else {
+                Style::default().fg(colors.row_fg)
+            };
+            if focused && i == self.selected {
+                style = style.add_modifier(Modifier::REVERSED | Modifier::BOLD);
+            }
+            spans.push(Span::styled(format!(" {} ", filter.label()), style));
+            spans.push(Span::raw(" "));
+        }
+
+        let bar = Paragraph::new(Line::from(spans))
+            .style(Style::default().fg(colors.row_fg).bg(colors.buffer_bg));
+
+        frame.render_widget(bar, area);
+    }
+}
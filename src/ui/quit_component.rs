@@ -0,0 +1,139 @@
+use crate::ui::theme::TableColors;
+use crate::util::popup_area;
+
+use ratatui::{
+    Frame,
+    layout::{Alignment, Constraint, Direction, Flex, Layout, Margin, Rect},
+    prelude::Style,
+    style::Stylize,
+    text::Line,
+    widgets::{Block, BorderType, Clear, Paragraph, Wrap},
+};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum QuitAction {
+    #[default]
+    ExportAndQuit,
+    QuitWithoutSaving,
+    Cancel,
+}
+
+impl QuitAction {
+    const ALL: [QuitAction; 3] = [
+        QuitAction::ExportAndQuit,
+        QuitAction::QuitWithoutSaving,
+        QuitAction::Cancel,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            QuitAction::ExportAndQuit => "Export & quit",
+            QuitAction::QuitWithoutSaving => "Quit without saving",
+            QuitAction::Cancel => "Cancel",
+        }
+    }
+}
+
+/// Asks what to do with a running audit before quitting, so its events are not lost
+#[derive(Debug, Default)]
+pub struct QuitComponent {
+    /// Whether the popup is displayed
+    pub display: bool,
+    pub action: QuitAction,
+    /// Events the audit has recorded so far
+    pub events: usize,
+}
+
+impl QuitComponent {
+    /// Shows the popup with "Export & quit" selected
+    pub fn open(&mut self, events: usize) {
+        self.display = true;
+        self.action = QuitAction::default();
+        self.events = events;
+    }
+
+    pub fn hide(&mut self) {
+        self.display = false;
+    }
+
+    /// Select the button to the right, stopping at the last
+    pub fn next_action(&mut self) {
+        let i = QuitAction::ALL.iter().position(|a| *a == self.action);
+        if let Some(next) = i.and_then(|i| QuitAction::ALL.get(i + 1)) {
+            self.action = *next;
+        }
+    }
+
+    /// Select the button to the left, stopping at the first
+    pub fn previous_action(&mut self) {
+        let i = QuitAction::ALL.iter().position(|a| *a == self.action);
+        if let Some(i) = i.and_then(|i| i.checked_sub(1)) {
+            self.action = QuitAction::ALL[i];
+        }
+    }
+
+    /// Renders the popup
+    pub fn render(&self, frame: &mut Frame, area: Rect, colors: &TableColors) {
+        if !self.display {
+            return;
+        }
+
+        let block = Block::bordered()
+            .border_type(BorderType::Plain)
+            .border_style(Style::new().fg(colors.footer_border_color))
+            .bg(colors.buffer_bg)
+            .title(" Quit ");
+
+        let area = popup_area(area, 5, 3);
+        frame.render_widget(Clear, area);
+        frame.render_widget(block, area);
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(1),
+                Constraint::Min(2),
+                Constraint::Length(3),
+                Constraint::Length(1),
+            ])
+            .split(area);
+
+        let prompt = format!(
+            "An audit is running with {} recorded event{} that would be lost. Export it before quitting?",
+            self.events,
+            if self.events == 1 { "" } else { "s" }
+        );
+        let prompt = Paragraph::new(Line::from(prompt))
+            .style(Style::default().fg(colors.row_fg).bg(colors.buffer_bg))
+            .alignment(Alignment::Center)
+            .wrap(Wrap { trim: true });
+        frame.render_widget(
+            prompt,
+            chunks[1].inner(Margin {
+                horizontal: 2,
+                vertical: 0,
+            }),
+        );
+
+        let buttons = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Ratio(1, 3); 3])
+            .flex(Flex::Center)
+            .split(chunks[2].inner(Margin {
+                horizontal: 1,
+                vertical: 0,
+            }));
+
+        for (action, button_area) in QuitAction::ALL.iter().zip(buttons.iter()) {
+            let border = if *action == self.action {
+                colors.selected_cell_style_fg
+            } else {
+                colors.buffer_bg
+            };
+            let button = Paragraph::new(action.label())
+                .alignment(Alignment::Center)
+                .block(Block::bordered().border_style(Style::new().fg(border)));
+            frame.render_widget(button, *button_area);
+        }
+    }
+}
@@ -0,0 +1,182 @@
+use crate::portwatch::snapshot::{SnapshotFile, browse_snapshots};
+use crate::ui::theme::TableColors;
+use crate::util::{format_bytes, popup_area};
+
+use ratatui::{
+    Frame,
+    layout::{Constraint, Margin, Rect},
+    prelude::Style,
+    style::{Modifier, Stylize},
+    widgets::{
+        Block, BorderType, Cell, Clear, HighlightSpacing, Row, Scrollbar, ScrollbarOrientation,
+        ScrollbarState, Table, TableState,
+    },
+};
+
+/// A popup listing the files saved under `snapshots/`
+#[derive(Debug, Default)]
+pub struct SnapshotBrowserComponent {
+    /// Whether the popup is displayed
+    pub display: bool,
+    /// Files found when the popup was opened, most recently modified first
+    pub files: Vec<SnapshotFile>,
+    /// Error reading the snapshots folder, shown in the title
+    pub error: Option<String>,
+    /// Table selection state
+    pub state: TableState,
+    /// Scrollbar state
+    pub scroll: ScrollbarState,
+    /// Number of visible rows
+    pub visible_rows: usize,
+}
+
+impl SnapshotBrowserComponent {
+    /// Toggle display on/off, rescanning the snapshots folder when opening
+    pub fn toggle(&mut self) {
+        self.display = !self.display;
+        if self.display {
+            self.refresh();
+        }
+    }
+
+    /// Re-read the snapshots folder
+    pub fn refresh(&mut self) {
+        match browse_snapshots(None) {
+            Ok(files) => {
+                self.files = files;
+                self.error = None;
+            }
+            Err(e) => {
+                self.files.clear();
+                self.error = Some(e.to_string());
+            }
+        }
+        self.scroll = ScrollbarState::new(self.files.len().saturating_sub(1));
+        self.select(0);
+    }
+
+    /// Currently highlighted file
+    pub fn selected(&self) -> Option<&SnapshotFile> {
+        self.state.selected().and_then(|i| self.files.get(i))
+    }
+
+    fn select(&mut self, idx: usize) {
+        if self.files.is_empty() {
+            self.state.select(None);
+            return;
+        }
+        let idx = idx.min(self.files.len() - 1);
+        self.state.select(Some(idx));
+        self.scroll = self.scroll.position(idx);
+    }
+
+    /// Move selection down by one row
+    pub fn next_row(&mut self) {
+        match self.state.selected() {
+            Some(i) if i + 1 < self.files.len() => self.select(i + 1),
+            _ => self.select(0),
+        }
+    }
+
+    /// Move selection up by one row
+    pub fn previous_row(&mut self) {
+        match self.state.selected() {
+            Some(0) | None => self.select(self.files.len().saturating_sub(1)),
+            Some(i) => self.select(i - 1),
+        }
+    }
+
+    /// Jump to the first row
+    pub fn first_row(&mut self) {
+        self.select(0);
+    }
+
+    /// Jump to the last row
+    pub fn last_row(&mut self) {
+        self.select(self.files.len().saturating_sub(1));
+    }
+
+    /// Page down
+    pub fn page_down(&mut self) {
+        let current = self.state.selected().unwrap_or(0);
+        self.select(current + self.visible_rows);
+    }
+
+    /// Page up
+    pub fn page_up(&mut self) {
+        let current = self.state.selected().unwrap_or(0);
+        self.select(current.saturating_sub(self.visible_rows));
+    }
+
+    /// Renders the popup
+    pub fn render(&mut self, frame: &mut Frame, area: Rect, colors: &TableColors) {
+        if !self.display {
+            return;
+        }
+
+        let area = popup_area(area, 7, 5);
+        // Borders and header
+        self.visible_rows = area.height.saturating_sub(3) as usize;
+
+        let title = match &self.error {
+            Some(error) => format!(" Snapshots — {} ", error),
+            None => format!(" Snapshots — {} files ", self.files.len()),
+        };
+
+        let selected_row_style = Style::default()
+            .add_modifier(Modifier::REVERSED)
+            .fg(colors.selected_row_style_fg);
+
+        let header = Row::new(["Taken", "Format", "Size", "File"])
+            .style(Style::default().fg(colors.header_fg).bg(colors.header_bg));
+
+        let rows = self.files.iter().map(|file| {
+            let taken = file
+                .taken_at
+                .map(|t| t.format("%Y-%m-%d %H:%M:%S").to_string())
+                .unwrap_or_else(|| "-".into());
+            Row::new([
+                Cell::from(taken),
+                Cell::from(file.format().to_uppercase()),
+                Cell::from(format_bytes(file.size)),
+                Cell::from(file.name()),
+            ])
+            .style(Style::default().fg(colors.row_fg))
+        });
+
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(19),
+                Constraint::Length(6),
+                Constraint::Length(10),
+                Constraint::Min(20),
+            ],
+        )
+        .header(header)
+        .row_highlight_style(selected_row_style)
+        .bg(colors.buffer_bg)
+        .highlight_spacing(HighlightSpacing::Always)
+        .block(
+            Block::bordered()
+                .border_type(BorderType::Plain)
+                .border_style(Style::new().fg(colors.footer_border_color))
+                .title(title),
+        );
+
+        frame.render_widget(Clear, area);
+        frame.render_stateful_widget(table, area, &mut self.state);
+
+        frame.render_stateful_widget(
+            Scrollbar::default()
+                .orientation(ScrollbarOrientation::VerticalRight)
+                .begin_symbol(None)
+                .end_symbol(None),
+            area.inner(Margin {
+                vertical: 1,
+                horizontal: 1,
+            }),
+            &mut self.scroll,
+        );
+    }
+}
@@ -0,0 +1,221 @@
+use crate::portwatch::compare::{SnapshotDiff, diff_snapshots};
+use crate::portwatch::snapshot::{is_loadable_snapshot, list_snapshots, load_snapshot};
+use crate::ui::theme::TableColors;
+use crate::util::popup_area;
+
+use ratatui::{
+    Frame,
+    layout::{Constraint, Rect},
+    prelude::{Color, Style},
+    style::{Modifier, Stylize},
+    widgets::{Block, BorderType, Cell, Clear, HighlightSpacing, Row, Table, TableState},
+};
+use std::path::{Path, PathBuf};
+
+/// A popup that picks two saved snapshots and shows what changed between them
+#[derive(Debug, Default)]
+pub struct SnapshotDiffComponent {
+    /// Whether the popup is displayed
+    pub display: bool,
+    /// Loadable snapshot files, newest first
+    pub files: Vec<PathBuf>,
+    /// First picked snapshot
+    pub first: Option<PathBuf>,
+    /// Result of comparing the two picked snapshots
+    pub diff: Option<Vec<SnapshotDiff>>,
+    /// Last loading error, shown in the title
+    pub error: Option<String>,
+    /// Table selection state
+    pub state: TableState,
+}
+
+impl SnapshotDiffComponent {
+    /// Toggle display on/off, rescanning the snapshots folder when opening
+    pub fn toggle(&mut self) {
+        self.display = !self.display;
+        if self.display {
+            self.reset();
+        }
+    }
+
+    /// Open the popup with `path` already picked as the first snapshot
+    pub fn open_with(&mut self, path: PathBuf) {
+        self.display = true;
+        self.reset();
+        self.first = Some(path);
+    }
+
+    /// Go back to picking the first snapshot
+    pub fn reset(&mut self) {
+        self.first = None;
+        self.diff = None;
+        self.error = None;
+        self.files = list_snapshots(None)
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|path| is_loadable_snapshot(path))
+            .collect();
+        self.state.select((!self.files.is_empty()).then_some(0));
+    }
+
+    fn len(&self) -> usize {
+        match &self.diff {
+            Some(diff) => diff.len(),
+            None => self.files.len(),
+        }
+    }
+
+    /// Move selection down by one row
+    pub fn next_row(&mut self) {
+        let len = self.len();
+        if len == 0 {
+            return;
+        }
+        let idx = match self.state.selected() {
+            Some(i) if i + 1 < len => i + 1,
+            _ => 0,
+        };
+        self.state.select(Some(idx));
+    }
+
+    /// Move selection up by one row
+    pub fn previous_row(&mut self) {
+        let len = self.len();
+        if len == 0 {
+            return;
+        }
+        let idx = match self.state.selected() {
+            Some(0) | None => len - 1,
+            Some(i) => i - 1,
+        };
+        self.state.select(Some(idx));
+    }
+
+    /// Pick the selected file, comparing once two have been chosen
+    pub fn select(&mut self) {
+        if self.diff.is_some() {
+            return;
+        }
+        let Some(picked) = self
+            .state
+            .selected()
+            .and_then(|i| self.files.get(i))
+            .cloned()
+        else {
+            return;
+        };
+
+        match self.first.take() {
+            None => self.first = Some(picked),
+            Some(first) => match (load_snapshot(&first), load_snapshot(&picked)) {
+                (Ok(a), Ok(b)) => {
+                    let diff = diff_snapshots(&a, &b);
+                    self.state.select((!diff.is_empty()).then_some(0));
+                    self.diff = Some(diff);
+                    self.first = Some(first);
+                    self.error = None;
+                }
+                (Err(e), _) | (_, Err(e)) => {
+                    self.error = Some(e.to_string());
+                }
+            },
+        }
+    }
+
+    fn file_name(path: &Path) -> String {
+        path.file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default()
+    }
+
+    fn process_label(item: &crate::model::PortInfo) -> String {
+        format!("{} ({})", item.process_name, item.pid)
+    }
+
+    /// Renders the popup
+    pub fn render(&mut self, frame: &mut Frame, area: Rect, colors: &TableColors) {
+        if !self.display {
+            return;
+        }
+
+        let title = match (&self.error, &self.diff, &self.first) {
+            (Some(error), _, _) => format!(" Compare Snapshots — {} ", error),
+            (None, Some(diff), _) => format!(" Compare Snapshots — {} differences ", diff.len()),
+            (None, None, None) => " Compare Snapshots — pick the first snapshot ".to_string(),
+            (None, None, Some(_)) => " Compare Snapshots — pick the second snapshot ".to_string(),
+        };
+
+        let block = Block::bordered()
+            .border_type(BorderType::Plain)
+            .border_style(Style::new().fg(colors.footer_border_color))
+            .title(title);
+
+        let selected_row_style = Style::default()
+            .add_modifier(Modifier::REVERSED)
+            .fg(colors.selected_row_style_fg);
+
+        let table = match &self.diff {
+            None => {
+                let rows = self.files.iter().map(|path| {
+                    let marker = if self.first.as_ref() == Some(path) {
+                        "[A]"
+                    } else {
+                        "   "
+                    };
+                    Row::new([marker.to_string(), Self::file_name(path)])
+                        .style(Style::default().fg(colors.row_fg))
+                });
+                Table::new(rows, [Constraint::Length(4), Constraint::Min(10)]).header(
+                    Row::new(["", "Snapshot"])
+                        .style(Style::default().fg(colors.header_fg).bg(colors.header_bg)),
+                )
+            }
+            Some(diff) => {
+                let rows = diff.iter().map(|entry| {
+                    let (change, before, after, fg) = match entry {
+                        SnapshotDiff::OnlyInA(p) => {
+                            ("Closed", Self::process_label(p), "-".into(), Color::Red)
+                        }
+                        SnapshotDiff::OnlyInB(p) => {
+                            ("Opened", "-".into(), Self::process_label(p), Color::Green)
+                        }
+                        SnapshotDiff::Changed { before, after } => (
+                            "Changed",
+                            Self::process_label(before),
+                            Self::process_label(after),
+                            Color::Yellow,
+                        ),
+                    };
+                    Row::new([
+                        Cell::from(change),
+                        Cell::from(entry.port().to_string()),
+                        Cell::from(before),
+                        Cell::from(after),
+                    ])
+                    .style(Style::default().fg(fg))
+                });
+                Table::new(
+                    rows,
+                    [
+                        Constraint::Length(8),
+                        Constraint::Length(6),
+                        Constraint::Min(20),
+                        Constraint::Min(20),
+                    ],
+                )
+                .header(
+                    Row::new(["Change", "Port", "Snapshot A", "Snapshot B"])
+                        .style(Style::default().fg(colors.header_fg).bg(colors.header_bg)),
+                )
+            }
+        }
+        .row_highlight_style(selected_row_style)
+        .bg(colors.buffer_bg)
+        .highlight_spacing(HighlightSpacing::Always)
+        .block(block);
+
+        let area = popup_area(area, 7, 5);
+        frame.render_widget(Clear, area);
+        frame.render_stateful_widget(table, area, &mut self.state);
+    }
+}
@@ -25,6 +25,8 @@ pub struct SnapshotsComponent {
     pub display: bool,
     pub action: ExportAction,
     pub selected_format: ExportFormat,
+    /// Export only the rows matching the search instead of every port
+    pub filtered_only: bool,
 }
 
 impl Default for SnapshotsComponent {
@@ -33,6 +35,7 @@ impl Default for SnapshotsComponent {
             display: false,
             action: ExportAction::Export,
             selected_format: ExportFormat::Json,
+            filtered_only: true,
         }
     }
 }
@@ -66,6 +69,11 @@ impl SnapshotsComponent {
         self.selected_format = self.selected_format.prev();
     }
 
+    /// Switch between exporting the filtered view and all ports
+    pub fn toggle_scope(&mut self) {
+        self.filtered_only = !self.filtered_only;
+    }
+
     /// Renders the popup
     pub fn render(&self, frame: &mut Frame, area: Rect, colors: &TableColors) {
         if !self.display {
@@ -139,7 +147,25 @@ impl SnapshotsComponent {
             }),
         );
 
-        // 3) buttons
+        // 3) Scope
+        let mut scope = vec![Span::raw("Scope (Tab): ")];
+        scope.extend(self.render_radio(
+            "Filtered view (rows matching search)",
+            self.filtered_only,
+            colors,
+        ));
+        scope.push(Span::raw("   "));
+        scope.extend(self.render_radio("All ports", !self.filtered_only, colors));
+
+        frame.render_widget(
+            Paragraph::new(Line::from(scope)).style(Style::default().bg(colors.buffer_bg)),
+            chunks[4].inner(Margin {
+                horizontal: 2,
+                vertical: 0,
+            }),
+        );
+
+        // 4) buttons
         let buttons = Layout::default()
             .direction(Direction::Horizontal)
             .constraints([Constraint::Ratio(1, 3), Constraint::Ratio(1, 3)])
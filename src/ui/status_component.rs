@@ -0,0 +1,79 @@
+use crate::ui::theme::TableColors;
+
+use ratatui::{
+    Frame,
+    layout::{Alignment, Rect},
+    prelude::{Color, Style},
+    text::{Line, Span},
+    widgets::Paragraph,
+};
+use std::time::{Duration, Instant};
+
+/// How long a status message stays on screen
+const STATUS_TTL: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StatusKind {
+    Info,
+    Error,
+}
+
+/// A single transient message shown in the status line
+#[derive(Debug, Clone)]
+pub struct StatusMessage {
+    pub text: String,
+    pub kind: StatusKind,
+    pub created_at: Instant,
+}
+
+/// A one-line toast at the bottom of the screen for short-lived feedback
+#[derive(Debug, Default)]
+pub struct StatusComponent {
+    pub message: Option<StatusMessage>,
+}
+
+impl StatusComponent {
+    /// Show an informational message
+    pub fn info(&mut self, text: impl Into<String>) {
+        self.set(text.into(), StatusKind::Info);
+    }
+
+    /// Show an error message
+    pub fn error(&mut self, text: impl Into<String>) {
+        self.set(text.into(), StatusKind::Error);
+    }
+
+    fn set(&mut self, text: String, kind: StatusKind) {
+        self.message = Some(StatusMessage {
+            text,
+            kind,
+            created_at: Instant::now(),
+        });
+    }
+
+    /// Whether there is a message that has not expired yet
+    pub fn is_visible(&self) -> bool {
+        self.message
+            .as_ref()
+            .is_some_and(|m| m.created_at.elapsed() < STATUS_TTL)
+    }
+
+    /// Renders the status line
+    pub fn render(&self, frame: &mut Frame, area: Rect, colors: &TableColors) {
+        let Some(message) = self.message.as_ref().filter(|_| self.is_visible()) else {
+            return;
+        };
+
+        let fg = match message.kind {
+            StatusKind::Info => Color::Green,
+            StatusKind::Error => Color::Red,
+        };
+
+        let line = Line::from(Span::styled(message.text.as_str(), Style::default().fg(fg)));
+        let status = Paragraph::new(line)
+            .alignment(Alignment::Left)
+            .style(Style::default().bg(colors.buffer_bg));
+
+        frame.render_widget(status, area);
+    }
+}
@@ -0,0 +1,45 @@
+use crate::model::{PortInfo, ProcessPortState, TransportProtocol};
+use crate::ui::theme::TableColors;
+
+use ratatui::{
+    Frame,
+    layout::{Alignment, Rect},
+    prelude::Style,
+    widgets::Paragraph,
+};
+
+/// A one-line tally of all ports by state and protocol, ignoring the search filter
+#[derive(Debug, Default)]
+pub struct SummaryComponent {
+    pub total: usize,
+    pub listening: usize,
+    pub in_use: usize,
+    pub tcp: usize,
+    pub udp: usize,
+}
+
+impl SummaryComponent {
+    /// Recount from the full, unfiltered port list
+    pub fn update(&mut self, ports: &[PortInfo]) {
+        let count = |pred: &dyn Fn(&PortInfo) -> bool| ports.iter().filter(|p| pred(p)).count();
+
+        self.total = ports.len();
+        self.listening = count(&|p| p.port_state == ProcessPortState::Hosting);
+        self.in_use = count(&|p| p.port_state == ProcessPortState::Using);
+        self.tcp = count(&|p| p.protocol == TransportProtocol::Tcp);
+        self.udp = count(&|p| p.protocol == TransportProtocol::Udp);
+    }
+
+    /// Renders the summary line
+    pub fn render(&self, frame: &mut Frame, area: Rect, colors: &TableColors) {
+        let text = format!(
+            "{} ports • {} listening • {} in use • {} TCP • {} UDP",
+            self.total, self.listening, self.in_use, self.tcp, self.udp
+        );
+        let summary = Paragraph::new(text)
+            .alignment(Alignment::Right)
+            .style(Style::default().fg(colors.row_fg).bg(colors.buffer_bg));
+
+        frame.render_widget(summary, area);
+    }
+}
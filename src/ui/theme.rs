@@ -18,15 +18,19 @@ pub struct Theme {
 
 impl Default for Theme {
     fn default() -> Self {
-        let idx = 0;
+        Theme::new(0)
+    }
+}
+
+impl Theme {
+    /// The theme of `PALETTES[idx]`, the first one when out of range
+    pub fn new(idx: usize) -> Self {
+        let idx = if idx < PALETTES.len() { idx } else { 0 };
         Theme {
             idx,
             table: TableColors::new(&PALETTES[idx]),
         }
     }
-}
-
-impl Theme {
     pub fn cycle_next(&mut self) {
         self.idx = (self.idx + 1) % PALETTES.len();
         self.table = TableColors::new(&PALETTES[self.idx]);
@@ -0,0 +1,58 @@
+use std::{
+    io::{self, Write},
+    process::{Command, Stdio},
+};
+
+/// Puts `text` on the system clipboard through the platform's command line tool:
+/// `pbcopy` on macOS, `clip` on Windows, and `wl-copy`, `xclip` or `xsel` elsewhere,
+/// whichever is installed.
+pub fn copy_to_clipboard(text: &str) -> io::Result<()> {
+    let mut last_error = io::Error::new(io::ErrorKind::NotFound, "No clipboard tool found");
+    for (program, args) in clipboard_commands() {
+        match pipe_to(program, args, text) {
+            Ok(()) => return Ok(()),
+            Err(e) => last_error = e,
+        }
+    }
+    Err(last_error)
+}
+
+fn pipe_to(program: &str, args: &[&str], text: &str) -> io::Result<()> {
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(text.as_bytes())?;
+    }
+    let status = child.wait()?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(io::Error::other(format!(
+            "{} failed with {}",
+            program, status
+        )))
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn clipboard_commands() -> &'static [(&'static str, &'static [&'static str])] {
+    &[("pbcopy", &[])]
+}
+
+#[cfg(target_family = "windows")]
+fn clipboard_commands() -> &'static [(&'static str, &'static [&'static str])] {
+    &[("clip", &[])]
+}
+
+#[cfg(all(target_family = "unix", not(target_os = "macos")))]
+fn clipboard_commands() -> &'static [(&'static str, &'static [&'static str])] {
+    &[
+        ("wl-copy", &[]),
+        ("xclip", &["-selection", "clipboard"]),
+        ("xsel", &["--clipboard", "--input"]),
+    ]
+}
@@ -0,0 +1,53 @@
+/// Matches `text` against a shell-style pattern where `*` stands for any run of
+/// characters and `?` for exactly one, e.g. `/usr/sbin/*` or `postgres*`.
+pub fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+
+    let (mut p, mut t) = (0, 0);
+    // Position of the last `*` and the text index it was tried at
+    let mut backtrack = None;
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, t));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match backtrack {
+                // Let the last `*` swallow one more character
+                Some((star, at)) => {
+                    p = star + 1;
+                    t = at + 1;
+                    backtrack = Some((star, at + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_names_and_path_globs() {
+        assert!(glob_match("sshd", "sshd"));
+        assert!(!glob_match("sshd", "sshd-session"));
+        assert!(glob_match("sshd*", "sshd-session"));
+        assert!(glob_match("/usr/sbin/*", "/usr/sbin/gdm3"));
+        assert!(!glob_match("/usr/sbin/*", "/usr/bin/node"));
+        assert!(glob_match(
+            "*/postgres",
+            "/usr/lib/postgresql/16/bin/postgres"
+        ));
+        assert!(glob_match("gdm?", "gdm3"));
+        assert!(!glob_match("gdm?", "gdm"));
+        assert!(glob_match("*", ""));
+    }
+}
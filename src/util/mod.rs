@@ -1,5 +1,17 @@
+mod clipboard;
+mod format;
+mod glob;
 mod layout;
+mod notify;
+mod open;
+mod sort;
 mod width;
 
+pub use clipboard::copy_to_clipboard;
+pub use format::{format_bytes, format_duration};
+pub use glob::glob_match;
 pub use layout::popup_area;
+pub use notify::desktop_notify;
+pub use open::reveal_in_file_manager;
+pub use sort::natural_cmp;
 pub use width::center_str;
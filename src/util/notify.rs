@@ -0,0 +1,49 @@
+use std::process::{Command, Stdio};
+
+/// Shows a desktop notification using the platform's own tooling.
+/// Fire-and-forget: failures are ignored since notifications are best effort.
+pub fn desktop_notify(title: &str, body: &str) {
+    let mut command = notify_command(title, body);
+    let _ = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn();
+}
+
+#[cfg(target_os = "macos")]
+fn notify_command(title: &str, body: &str) -> Command {
+    let script = format!(
+        "display notification {:?} with title {:?}",
+        body.replace('"', "'"),
+        title.replace('"', "'")
+    );
+    let mut command = Command::new("osascript");
+    command.args(["-e", &script]);
+    command
+}
+
+#[cfg(target_family = "windows")]
+fn notify_command(title: &str, body: &str) -> Command {
+    let escape = |s: &str| s.replace('\'', "''");
+    let script = format!(
+        "[Windows.UI.Notifications.ToastNotificationManager, Windows.UI.Notifications, ContentType = WindowsRuntime] | Out-Null; \
+         $xml = [Windows.UI.Notifications.ToastNotificationManager]::GetTemplateContent([Windows.UI.Notifications.ToastTemplateType]::ToastText02); \
+         $text = $xml.GetElementsByTagName('text'); \
+         $text.Item(0).AppendChild($xml.CreateTextNode('{}')) | Out-Null; \
+         $text.Item(1).AppendChild($xml.CreateTextNode('{}')) | Out-Null; \
+         [Windows.UI.Notifications.ToastNotificationManager]::CreateToastNotifier('harboor-sweep').Show([Windows.UI.Notifications.ToastNotification]::new($xml))",
+        escape(title),
+        escape(body)
+    );
+    let mut command = Command::new("powershell");
+    command.args(["-NoProfile", "-NonInteractive", "-Command", &script]);
+    command
+}
+
+#[cfg(all(target_family = "unix", not(target_os = "macos")))]
+fn notify_command(title: &str, body: &str) -> Command {
+    let mut command = Command::new("notify-send");
+    command.args(["--app-name=harboor-sweep", title, body]);
+    command
+}
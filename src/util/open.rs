@@ -0,0 +1,48 @@
+use std::{
+    io,
+    path::Path,
+    process::{Command, Stdio},
+    thread,
+};
+
+/// Shows `path` in the platform's file manager: revealed in Finder or Explorer,
+/// or its containing folder opened through `xdg-open` elsewhere.
+/// The opener runs detached so the terminal is never taken over.
+pub fn reveal_in_file_manager(path: &Path) -> io::Result<()> {
+    let mut child = reveal_command(path)?
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+    // Reap the opener once it exits so it does not linger as a zombie
+    thread::spawn(move || child.wait());
+    Ok(())
+}
+
+#[cfg(target_os = "macos")]
+fn reveal_command(path: &Path) -> io::Result<Command> {
+    let mut command = Command::new("open");
+    command.arg("-R").arg(path);
+    Ok(command)
+}
+
+#[cfg(target_family = "windows")]
+fn reveal_command(path: &Path) -> io::Result<Command> {
+    use std::os::windows::process::CommandExt;
+
+    // Explorer expects `/select,<path>` as a single, unquoted argument
+    let mut command = Command::new("explorer");
+    command.raw_arg(format!("/select,\"{}\"", path.display()));
+    Ok(command)
+}
+
+#[cfg(all(target_family = "unix", not(target_os = "macos")))]
+fn reveal_command(path: &Path) -> io::Result<Command> {
+    let dir = path
+        .parent()
+        .filter(|dir| !dir.as_os_str().is_empty())
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "Path has no parent folder"))?;
+    let mut command = Command::new("xdg-open");
+    command.arg(dir);
+    Ok(command)
+}
@@ -0,0 +1,75 @@
+use std::cmp::Ordering;
+use std::iter::Peekable;
+use std::str::Chars;
+
+/// Compares strings the way people read them: case-insensitively, with runs of
+/// digits compared by value, so `worker2` sorts before `worker10`.
+pub fn natural_cmp(a: &str, b: &str) -> Ordering {
+    let mut a = a.chars().peekable();
+    let mut b = b.chars().peekable();
+
+    loop {
+        match (a.peek(), b.peek()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(x), Some(y)) if x.is_ascii_digit() && y.is_ascii_digit() => {
+                let ordering = cmp_numbers(&take_digits(&mut a), &take_digits(&mut b));
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+            }
+            (Some(&x), Some(&y)) => {
+                let ordering = x.to_lowercase().cmp(y.to_lowercase());
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+                a.next();
+                b.next();
+            }
+        }
+    }
+}
+
+fn take_digits(chars: &mut Peekable<Chars>) -> String {
+    let mut digits = String::new();
+    while let Some(&c) = chars.peek().filter(|c| c.is_ascii_digit()) {
+        digits.push(c);
+        chars.next();
+    }
+    digits
+}
+
+/// Compares digit strings by value without parsing, so any length works.
+fn cmp_numbers(a: &str, b: &str) -> Ordering {
+    let a_trimmed = a.trim_start_matches('0');
+    let b_trimmed = b.trim_start_matches('0');
+    a_trimmed
+        .len()
+        .cmp(&b_trimmed.len())
+        .then_with(|| a_trimmed.cmp(b_trimmed))
+        // `007` after `7` keeps the order stable for equal values
+        .then_with(|| a.len().cmp(&b.len()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn orders_embedded_numbers_by_value() {
+        let mut names = vec!["worker10", "worker2", "Worker1", "worker"];
+        names.sort_by(|a, b| natural_cmp(a, b));
+        assert_eq!(names, ["worker", "Worker1", "worker2", "worker10"]);
+    }
+
+    #[test]
+    fn handles_leading_zeros_and_long_numbers() {
+        assert_eq!(natural_cmp("a7", "a007"), Ordering::Less);
+        assert_eq!(
+            natural_cmp("id99999999999999999999", "id100000000000000000000"),
+            Ordering::Less
+        );
+        assert_eq!(natural_cmp("node-1.2", "node-1.10"), Ordering::Less);
+    }
+}